@@ -0,0 +1,293 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use super::blocks::BlockId;
+
+// Borda repetida ao redor de cada textura do atlas; potência de 2, limita os mipmaps a log2(PADDING) níveis
+pub const PADDING: u32 = 4;
+
+// Onde uma textura ficou: retângulo (u, v, largura, altura) em UV do atlas, ou a camada do array
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tile {
+    pub rect: [f32; 4],
+    pub layer: u32,
+}
+
+impl Tile {
+    // Textura inteira; é o que as camadas do array usam
+    pub const FULL: Tile = Tile { rect: [0.0, 0.0, 1.0, 1.0], layer: 0 };
+}
+
+// Canto do conteúdo de cada textura, já descontado o padding
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Placement {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Packing {
+    pub width: u32,
+    pub height: u32,
+    pub placements: Vec<Placement>, // Na mesma ordem de `sizes`
+}
+
+fn align(value: u32, to: u32) -> u32 {
+    if to == 0 { value } else { value.div_ceil(to) * to }
+}
+
+// Empacota retângulos em prateleiras, dos mais altos para os mais baixos, no menor atlas quadrado
+// (ou quase) com lados potência de 2. `None` se não couber em `max_size`
+pub fn pack(sizes: &[(u32, u32)], padding: u32, max_size: u32) -> Option<Packing> {
+    // Tamanhos com padding arredondados para múltiplos dele, para cada textura começar alinhada aos mipmaps
+    let padded: Vec<(u32, u32)> = sizes
+        .iter()
+        .map(|&(w, h)| (align(w + 2 * padding, padding), align(h + 2 * padding, padding)))
+        .collect();
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| (Reverse(padded[i].1), Reverse(padded[i].0), i));
+
+    let widest = padded.iter().map(|&(w, _)| w).max().unwrap_or(1);
+    let mut width = widest.next_power_of_two();
+    while width <= max_size {
+        let mut placements = vec![Placement::default(); sizes.len()];
+        let (mut x, mut y, mut shelf) = (0, 0, 0);
+        for &i in &order {
+            let (w, h) = padded[i];
+            if x + w > width {
+                x = 0;
+                y += shelf;
+                shelf = 0;
+            }
+            placements[i] = Placement { x: x + padding, y: y + padding };
+            x += w;
+            shelf = shelf.max(h);
+        }
+
+        // Alargar o atlas só encurta as prateleiras; o primeiro que não é mais alto que largo é o menor
+        let height = (y + shelf).max(1).next_power_of_two();
+        if height <= width {
+            return Some(Packing { width, height, placements });
+        }
+        width *= 2;
+    }
+    None
+}
+
+// Cerca a textura com `padding` pixels da borda oposta, como o GL_REPEAT faria, para que a
+// filtragem e os mipmaps nas bordas vejam a continuação dela e não a vizinha no atlas
+pub fn pad_tile(tile: &RgbaImage, padding: u32) -> RgbaImage {
+    let (width, height) = tile.dimensions();
+    let wrap = |value: u32, size: u32| (value as i64 - padding as i64).rem_euclid(size as i64) as u32;
+    RgbaImage::from_fn(width + 2 * padding, height + 2 * padding, |x, y| {
+        *tile.get_pixel(wrap(x, width), wrap(y, height))
+    })
+}
+
+pub enum AtlasImages {
+    Atlas(RgbaImage),
+    Layers(Vec<RgbaImage>), // Camadas de um GL_TEXTURE_2D_ARRAY, todas do mesmo tamanho
+}
+
+// Texturas de bloco prontas para enviar à GPU, com o tile de cada uma pelo nome
+pub struct TextureAtlas {
+    pub images: AtlasImages,
+    pub tiles: HashMap<String, Tile>,
+}
+
+impl TextureAtlas {
+    // Tenta um atlas de até `max_size`; se não couber, usa um array de no máximo `max_layers` camadas
+    pub fn build(textures: &[(String, RgbaImage)], max_size: u32, max_layers: u32) -> Option<Self> {
+        Self::atlas(textures, max_size).or_else(|| Self::layers(textures, max_size, max_layers))
+    }
+
+    pub fn atlas(textures: &[(String, RgbaImage)], max_size: u32) -> Option<Self> {
+        let sizes: Vec<_> = textures.iter().map(|(_, image)| image.dimensions()).collect();
+        let packing = pack(&sizes, PADDING, max_size)?;
+
+        let mut atlas = RgbaImage::new(packing.width, packing.height);
+        let mut tiles = HashMap::new();
+        let (atlas_width, atlas_height) = (packing.width as f32, packing.height as f32);
+        for ((name, image), place) in textures.iter().zip(&packing.placements) {
+            let padded = pad_tile(image, PADDING);
+            imageops::replace(&mut atlas, &padded, (place.x - PADDING) as i64, (place.y - PADDING) as i64);
+            let rect = [
+                place.x as f32 / atlas_width,
+                place.y as f32 / atlas_height,
+                image.width() as f32 / atlas_width,
+                image.height() as f32 / atlas_height,
+            ];
+            tiles.insert(name.clone(), Tile { rect, layer: 0 });
+        }
+        Some(Self { images: AtlasImages::Atlas(atlas), tiles })
+    }
+
+    // As camadas têm o tamanho mais comum entre as texturas (até `max_size`); as outras são redimensionadas
+    pub fn layers(textures: &[(String, RgbaImage)], max_size: u32, max_layers: u32) -> Option<Self> {
+        if textures.len() > max_layers as usize {
+            return None;
+        }
+        let mut counts: HashMap<(u32, u32), usize> = HashMap::new();
+        for (_, image) in textures {
+            *counts.entry(image.dimensions()).or_default() += 1;
+        }
+        let (width, height) = counts
+            .into_iter()
+            .max_by_key(|&(size, count)| (count, size))
+            .map_or((1, 1), |(size, _)| size);
+        let (width, height) = (width.min(max_size), height.min(max_size));
+
+        let mut layers = Vec::with_capacity(textures.len());
+        let mut tiles = HashMap::new();
+        for (layer, (name, image)) in textures.iter().enumerate() {
+            layers.push(if image.dimensions() == (width, height) {
+                image.clone()
+            } else {
+                imageops::resize(image, width, height, FilterType::Triangle)
+            });
+            tiles.insert(name.clone(), Tile { layer: layer as u32, ..Tile::FULL });
+        }
+        Some(Self { images: AtlasImages::Layers(layers), tiles })
+    }
+
+    // Último nível de mipmap seguro: no atlas, além de log2(PADDING) as texturas vizinhas se misturam.
+    // As camadas do array são independentes e usam a cadeia inteira
+    pub fn max_mip_level(&self) -> Option<u32> {
+        match self.images {
+            AtlasImages::Atlas(_) => Some(PADDING.trailing_zeros()),
+            AtlasImages::Layers(_) => None,
+        }
+    }
+}
+
+// Tile de cada face (na ordem de FACE_OFFSETS) de cada bloco; blocos sem texturas usam `missing`
+#[derive(Clone, Debug)]
+pub struct BlockTextures {
+    faces: HashMap<BlockId, [Tile; 6]>,
+    missing: Tile,
+}
+
+impl Default for BlockTextures {
+    fn default() -> Self {
+        Self::new(Tile::FULL)
+    }
+}
+
+impl BlockTextures {
+    pub fn new(missing: Tile) -> Self {
+        Self { faces: HashMap::new(), missing }
+    }
+
+    pub fn insert(&mut self, block: BlockId, faces: [Tile; 6]) {
+        self.faces.insert(block, faces);
+    }
+
+    pub fn get(&self, block: BlockId, face: usize) -> Tile {
+        self.faces.get(&block).map_or(self.missing, |faces| faces[face])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn overlaps(a: (Placement, (u32, u32)), b: (Placement, (u32, u32)), padding: u32) -> bool {
+        let (pa, (wa, ha)) = a;
+        let (pb, (wb, hb)) = b;
+        pa.x < pb.x + wb + 2 * padding && pb.x < pa.x + wa + 2 * padding
+            && pa.y < pb.y + hb + 2 * padding && pb.y < pa.y + ha + 2 * padding
+    }
+
+    #[test]
+    fn packs_without_overlap_inside_the_atlas() {
+        let sizes = [(16, 16), (16, 16), (256, 256), (15, 15), (64, 32), (16, 16), (8, 128)];
+        let packing = pack(&sizes, PADDING, 4096).unwrap();
+        assert!(packing.width.is_power_of_two() && packing.height.is_power_of_two());
+
+        for (i, (&place, &size)) in packing.placements.iter().zip(&sizes).enumerate() {
+            assert!(place.x >= PADDING && place.x + size.0 + PADDING <= packing.width);
+            assert!(place.y >= PADDING && place.y + size.1 + PADDING <= packing.height);
+            // Alinhado ao padding para os mipmaps não misturarem texturas
+            assert_eq!((place.x % PADDING, place.y % PADDING), (0, 0));
+            for (j, (&other, &other_size)) in packing.placements.iter().zip(&sizes).take(i).enumerate() {
+                assert!(!overlaps((place, size), (other, other_size), PADDING), "{} e {}", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn chooses_the_smallest_square_atlas() {
+        // 16 texturas de 16x16 com padding 4 ocupam 24x24 cada: 5 por linha, 4 linhas em 128
+        let packing = pack(&[(16, 16); 16], PADDING, 4096).unwrap();
+        assert_eq!((packing.width, packing.height), (128, 128));
+        assert_eq!(pack(&[], PADDING, 4096).unwrap().placements, vec![]);
+    }
+
+    #[test]
+    fn too_large_for_max_size_fails() {
+        assert_eq!(pack(&[(1500, 1500)], PADDING, 1024), None);
+        assert!(pack(&[(1500, 1500)], PADDING, 2048).is_some());
+    }
+
+    #[test]
+    fn padding_wraps_the_opposite_edge() {
+        let tile = RgbaImage::from_fn(2, 2, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+        let padded = pad_tile(&tile, 2);
+        assert_eq!(padded.dimensions(), (6, 6));
+        assert_eq!(padded.get_pixel(2, 2), tile.get_pixel(0, 0));
+        // À esquerda do pixel (0, 0) vem a última coluna, como no GL_REPEAT
+        assert_eq!(padded.get_pixel(1, 2), tile.get_pixel(1, 0));
+        assert_eq!(padded.get_pixel(0, 0), tile.get_pixel(0, 0));
+    }
+
+    fn solid(size: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(size, size, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn atlas_tiles_point_at_their_pixels() {
+        let textures = vec![("dirt".to_string(), solid(16, 10)), ("stone".to_string(), solid(16, 20))];
+        let atlas = TextureAtlas::build(&textures, 4096, 256).unwrap();
+        let AtlasImages::Atlas(image) = &atlas.images else { panic!("esperava um atlas") };
+
+        for (name, image_value) in [("dirt", 10), ("stone", 20)] {
+            let tile = atlas.tiles[name];
+            assert_eq!(tile.rect[2] * image.width() as f32, 16.0);
+            let x = (tile.rect[0] * image.width() as f32) as u32;
+            let y = (tile.rect[1] * image.height() as f32) as u32;
+            assert_eq!(image.get_pixel(x, y)[0], image_value);
+            assert_eq!(image.get_pixel(x + 15, y + 15)[0], image_value);
+            // O padding repete a própria textura
+            assert_eq!(image.get_pixel(x - 1, y - 1)[0], image_value);
+        }
+        assert_eq!(atlas.max_mip_level(), Some(2));
+    }
+
+    #[test]
+    fn falls_back_to_layers_of_the_common_size() {
+        let textures = vec![
+            ("a".to_string(), solid(16, 1)),
+            ("b".to_string(), solid(64, 2)),
+            ("c".to_string(), solid(16, 3)),
+        ];
+        let atlas = TextureAtlas::build(&textures, 64, 256).unwrap();
+        let AtlasImages::Layers(layers) = &atlas.images else { panic!("esperava um array") };
+        assert!(layers.iter().all(|layer| layer.dimensions() == (16, 16)));
+        assert_eq!(atlas.tiles["b"], Tile { layer: 1, ..Tile::FULL });
+        assert_eq!(layers[1].get_pixel(8, 8)[0], 2);
+
+        assert!(TextureAtlas::build(&textures, 64, 2).is_none());
+    }
+
+    #[test]
+    fn unknown_blocks_use_the_missing_tile() {
+        let missing = Tile { layer: 7, ..Tile::FULL };
+        let mut textures = BlockTextures::new(missing);
+        let top = Tile { layer: 1, ..Tile::FULL };
+        textures.insert(BlockId(3), [Tile::FULL, Tile::FULL, top, Tile::FULL, Tile::FULL, Tile::FULL]);
+        assert_eq!(textures.get(BlockId(3), 2), top);
+        assert_eq!(textures.get(BlockId(4), 2), missing);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::game::resources::resource_location;
+use crate::json::Json;
+
+// Variante de blockstates/*.json: o modelo e o giro do bloco inteiro em múltiplos de 90 graus.
+// `uvlock` é ignorado: as texturas giram junto com o modelo
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    pub model: String,
+    pub x: u32,
+    pub y: u32,
+}
+
+// Variantes com as condições ("axis=x,waterlogged=false") que escolhem o estado; condições vazias
+// valem para todos. O parser de JSON guarda os objetos ordenados por chave, então a ordem do
+// arquivo se perde e a escolha é pela variante mais específica
+#[derive(Clone, Debug, Default)]
+pub struct BlockStates {
+    variants: Vec<(Vec<(String, String)>, Variant)>,
+}
+
+fn parse_variant(value: &Json) -> Result<Variant, &'static str> {
+    // Listas são variantes sorteadas por peso; sem sorteio, fica a primeira
+    let value = match value.as_array() {
+        Some(choices) => choices.first().ok_or("variant lists can't be empty")?,
+        None => value,
+    };
+    let model = value.get("model").and_then(Json::as_str).ok_or("variants need a model")?;
+    let rotation = |key| match value.get(key).map(Json::as_f64) {
+        None => Ok(0),
+        Some(Some(angle)) if [0.0, 90.0, 180.0, 270.0].contains(&angle) => Ok(angle as u32),
+        Some(_) => Err("variant rotations must be 0, 90, 180 or 270"),
+    };
+    Ok(Variant { model: resource_location(model), x: rotation("x")?, y: rotation("y")? })
+}
+
+pub fn parse_blockstates(value: &Json) -> Result<BlockStates, &'static str> {
+    if value.get("multipart").is_some() {
+        return Err("multipart blockstates are not supported yet");
+    }
+    let map = value.get("variants").and_then(Json::as_object).ok_or("missing variants")?;
+
+    let mut variants = Vec::new();
+    for (key, variant) in map {
+        let conditions = key
+            .split(',')
+            .filter(|condition| !condition.is_empty())
+            .map(|condition| {
+                let (name, value) = condition.split_once('=').ok_or("variant keys look like axis=x,waterlogged=false")?;
+                Ok((name.to_string(), value.to_string()))
+            })
+            .collect::<Result<_, &'static str>>()?;
+        variants.push((conditions, parse_variant(variant)?));
+    }
+    Ok(BlockStates { variants })
+}
+
+impl BlockStates {
+    // Variante com mais condições entre as que o estado cumpre; no empate fica a primeira chave.
+    // `properties` vêm de `BlockRegistry::properties_of`
+    pub fn variant(&self, properties: &[(&str, &str)]) -> Option<&Variant> {
+        self.variants
+            .iter()
+            .filter(|(conditions, _)| {
+                conditions
+                    .iter()
+                    .all(|(name, value)| properties.iter().any(|&(n, v)| n == name && v == value))
+            })
+            .min_by_key(|(conditions, _)| std::cmp::Reverse(conditions.len()))
+            .map(|(_, variant)| variant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    fn parse(text: &str) -> Result<BlockStates, &'static str> {
+        parse_blockstates(&json::parse(text).unwrap())
+    }
+
+    #[test]
+    fn variants_match_state_properties() {
+        let log = parse(r#"{ "variants": {
+            "axis=x": { "model": "block/oak_log_horizontal", "x": 90, "y": 90 },
+            "axis=y": { "model": "block/oak_log" },
+            "axis=z": [{ "model": "block/oak_log_horizontal", "x": 90 }, { "model": "block/other" }]
+        } }"#)
+        .unwrap();
+        let variant = |axis| log.variant(&[("axis", axis), ("waterlogged", "false")]).cloned();
+        assert_eq!(variant("y"), Some(Variant { model: "minecraft:block/oak_log".to_string(), x: 0, y: 0 }));
+        assert_eq!(variant("x").map(|v| (v.x, v.y)), Some((90, 90)));
+        assert_eq!(variant("z").map(|v| v.model), Some("minecraft:block/oak_log_horizontal".to_string()));
+        assert_eq!(variant("w"), None);
+
+        // Uma chave vazia vale para qualquer estado
+        let stone = parse(r#"{ "variants": { "": { "model": "block/stone" } } }"#).unwrap();
+        assert!(stone.variant(&[]).is_some());
+        assert!(stone.variant(&[("level", "3")]).is_some());
+    }
+
+    #[test]
+    fn most_specific_variant_wins_over_the_empty_key() {
+        let slab = parse(r#"{ "variants": {
+            "": { "model": "block/slab" },
+            "type=top": { "model": "block/slab_top" },
+            "type=top,waterlogged=true": { "model": "block/slab_top_wet" },
+            "waterlogged=true": { "model": "block/slab_wet" }
+        } }"#)
+        .unwrap();
+        let model = |properties: &[(&str, &str)]| slab.variant(properties).map(|v| v.model.clone());
+        assert_eq!(model(&[("type", "bottom"), ("waterlogged", "false")]), Some("minecraft:block/slab".to_string()));
+        assert_eq!(model(&[("type", "top"), ("waterlogged", "false")]), Some("minecraft:block/slab_top".to_string()));
+        assert_eq!(model(&[("type", "top"), ("waterlogged", "true")]), Some("minecraft:block/slab_top_wet".to_string()));
+        assert_eq!(model(&[("type", "bottom"), ("waterlogged", "true")]), Some("minecraft:block/slab_wet".to_string()));
+    }
+
+    #[test]
+    fn invalid_blockstates_are_errors() {
+        for text in [
+            r#"{}"#,
+            r#"{ "multipart": [] }"#,
+            r#"{ "variants": { "axis": { "model": "block/a" } } }"#,
+            r#"{ "variants": { "": { "model": "block/a", "y": 45 } } }"#,
+            r#"{ "variants": { "": [] } }"#,
+            r#"{ "variants": { "": {} } }"#,
+        ] {
+            assert!(parse(text).is_err(), "{}", text);
+        }
+    }
+}
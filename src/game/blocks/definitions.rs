@@ -0,0 +1,274 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::game::resources::resource_location;
+use crate::json::{self, Json, JsonError};
+use super::registry::{BlockProperties, BlockRegistry, RenderLayer, StateProperty};
+
+// Definição de um bloco lida de JSON, como em blocks/grass_block.json:
+// { "id": "minecraft:grass_block", "textures": { "top": "block/grass_block_top", "bottom": "block/dirt", "side": "block/grass_block_side" },
+//   "render_layer": "opaque", "collision": true, "hardness": 0.6 }
+// Em vez de `textures`, um bloco pode usar um modelo dos resource packs ("model": "block/stone")
+// ou, sem nenhum dos dois, as variantes de blockstates/<nome>.json. Propriedades de estado são
+// listadas em "states": { "axis": ["x", "y", "z"] }, com "default_state": { "axis": "y" }
+#[derive(Clone, Debug)]
+pub struct BlockDefinition {
+    pub name: String,
+    pub properties: BlockProperties,
+    pub states: Vec<StateProperty>,
+    pub textures: Option<[String; 6]>, // Ids de textura ("minecraft:block/stone") na ordem de FACE_OFFSETS; `None` para blocos invisíveis
+    pub model: Option<String>,         // Id do modelo ("minecraft:block/stone_slab")
+    pub tint: [f32; 3],                // Cor das faces do modelo com `tintindex`, de "#91BD59"
+}
+
+#[derive(Debug)]
+pub enum DefinitionError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, JsonError),
+    Invalid(PathBuf, &'static str),
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            DefinitionError::Json(path, err) => write!(f, "{}: {}", path.display(), err),
+            DefinitionError::Invalid(path, reason) => write!(f, "{}: invalid block definition: {}", path.display(), reason),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+// Chaves de textura de cada face, da mais específica para a mais geral (ordem de FACE_OFFSETS)
+const FACE_KEYS: [&[&str]; 6] = [
+    &["south", "side", "all"],
+    &["north", "side", "all"],
+    &["top", "all"],
+    &["bottom", "all"],
+    &["west", "side", "all"],
+    &["east", "side", "all"],
+];
+
+pub fn parse_definition(value: &Json) -> Result<BlockDefinition, &'static str> {
+    let name = value.get("id").and_then(Json::as_str).ok_or("missing id")?;
+    if !name.contains(':') {
+        return Err("id must have a namespace, like minecraft:stone");
+    }
+
+    let flag = |key, default| match value.get(key) {
+        None => Ok(default),
+        Some(flag) => flag.as_bool().ok_or("flags must be true or false"),
+    };
+    let render_layer = match value.get("render_layer").map(Json::as_str) {
+        None | Some(Some("opaque")) => RenderLayer::Opaque,
+        Some(Some("cutout")) => RenderLayer::Cutout,
+        Some(Some("translucent")) => RenderLayer::Translucent,
+        Some(_) => return Err("render_layer must be opaque, cutout or translucent"),
+    };
+    let hardness = match value.get("hardness") {
+        None => 1.0,
+        Some(hardness) => hardness.as_f64().ok_or("hardness must be a number")? as f32,
+    };
+    let light_emission = match value.get("light_emission") {
+        None => 0,
+        Some(level) => match level.as_f64() {
+            Some(level) if (0.0..=15.0).contains(&level) && level.fract() == 0.0 => level as u8,
+            _ => return Err("light_emission must be an integer from 0 to 15"),
+        },
+    };
+
+    let textures = match value.get("textures") {
+        None => None,
+        Some(textures) => {
+            let textures = textures.as_object().ok_or("textures must be an object")?;
+            let mut faces: [String; 6] = Default::default();
+            for (face, keys) in faces.iter_mut().zip(FACE_KEYS) {
+                let texture = keys.iter().find_map(|&key| textures.get(key)).ok_or("textures must cover every face")?;
+                *face = resource_location(texture.as_str().ok_or("texture names must be strings")?);
+            }
+            Some(faces)
+        }
+    };
+    let model = match value.get("model") {
+        None => None,
+        Some(_) if textures.is_some() => return Err("use either textures or model"),
+        Some(model) => Some(resource_location(model.as_str().ok_or("model must be a string")?)),
+    };
+    let mut states = Vec::new();
+    if let Some(map) = value.get("states") {
+        for (name, values) in map.as_object().ok_or("states must be an object")? {
+            let values = values
+                .as_array()
+                .filter(|values| !values.is_empty())
+                .ok_or("each state property needs a list of values")?
+                .iter()
+                .map(|value| value.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .ok_or("state values must be strings")?;
+            states.push(StateProperty { name: name.clone(), values, default: 0 });
+        }
+    }
+    if let Some(defaults) = value.get("default_state") {
+        for (name, default) in defaults.as_object().ok_or("default_state must be an object")? {
+            let property = states.iter_mut().find(|p| p.name == *name).ok_or("default_state names an unknown property")?;
+            let default = default.as_str().and_then(|default| property.values.iter().position(|v| v == default));
+            property.default = default.ok_or("default_state value is not in the property's list")?;
+        }
+    }
+    let tint = match value.get("tint") {
+        None => [1.0; 3],
+        Some(tint) => tint.as_str().and_then(parse_color).ok_or("tint must be a color like #91BD59")?,
+    };
+
+    Ok(BlockDefinition {
+        name: name.to_string(),
+        properties: BlockProperties {
+            solid: flag("collision", true)?,
+            transparent: render_layer != RenderLayer::Opaque,
+            render_layer,
+            fluid: flag("fluid", false)?,
+            gravity: flag("gravity", false)?,
+            hardness,
+            light_emission,
+        },
+        states,
+        textures,
+        model,
+        tint,
+    })
+}
+
+fn parse_color(hex: &str) -> Option<[f32; 3]> {
+    let hex = hex.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some([16, 8, 0].map(|shift| ((rgb >> shift) & 0xFF) as f32 / 255.0))
+}
+
+// Todas as definições *.json de `dir`, em ordem de nome de arquivo para que os ids sejam estáveis
+pub fn load_definitions(dir: &Path) -> Result<Vec<BlockDefinition>, DefinitionError> {
+    let entries = fs::read_dir(dir).map_err(|err| DefinitionError::Io(dir.to_path_buf(), err))?;
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let text = fs::read_to_string(&path).map_err(|err| DefinitionError::Io(path.clone(), err))?;
+            let value = json::parse(&text).map_err(|err| DefinitionError::Json(path.clone(), err))?;
+            parse_definition(&value).map_err(|reason| DefinitionError::Invalid(path, reason))
+        })
+        .collect()
+}
+
+impl BlockRegistry {
+    // Ar mais os blocos definidos, com ids na ordem das definições
+    pub fn from_definitions(definitions: &[BlockDefinition]) -> Self {
+        let mut registry = Self::new();
+        for definition in definitions {
+            registry.register_states(&definition.name, definition.properties.clone(), definition.states.clone());
+        }
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<BlockDefinition, &'static str> {
+        parse_definition(&json::parse(text).unwrap())
+    }
+
+    #[test]
+    fn faces_fall_back_from_specific_to_side_to_all() {
+        let grass = parse(r#"{
+            "id": "minecraft:grass_block",
+            "textures": { "top": "block/grass_block_top", "bottom": "block/dirt", "side": "block/grass_block_side", "east": "mod:block/custom" }
+        }"#)
+        .unwrap();
+        let (side, top, bottom) = ("minecraft:block/grass_block_side", "minecraft:block/grass_block_top", "minecraft:block/dirt");
+        assert_eq!(grass.textures.unwrap(), [side, side, top, bottom, side, "mod:block/custom"]);
+
+        let stone = parse(r#"{ "id": "minecraft:stone", "textures": { "all": "block/stone" } }"#).unwrap();
+        assert!(stone.textures.unwrap().iter().all(|name| name == "minecraft:block/stone"));
+        assert_eq!(parse(r#"{ "id": "a:b", "textures": { "side": "x" } }"#).unwrap_err(), "textures must cover every face");
+    }
+
+    #[test]
+    fn properties_have_defaults() {
+        let stone = parse(r#"{ "id": "minecraft:stone" }"#).unwrap();
+        assert!(stone.properties.solid && !stone.properties.transparent);
+        assert_eq!((stone.properties.hardness, stone.properties.render_layer), (1.0, RenderLayer::Opaque));
+        assert!(stone.textures.is_none() && stone.states.is_empty());
+        assert_eq!((stone.model, stone.tint), (None, [1.0; 3]));
+
+        let slab = parse(r##"{ "id": "minecraft:stone_slab", "model": "block/stone_slab", "tint": "#FF8000" }"##).unwrap();
+        assert_eq!(slab.model.as_deref(), Some("minecraft:block/stone_slab"));
+        assert_eq!(slab.tint, [1.0, 128.0 / 255.0, 0.0]);
+
+        let water = parse(r#"{
+            "id": "minecraft:water", "render_layer": "translucent", "collision": false,
+            "fluid": true, "hardness": 100, "light_emission": 0
+        }"#)
+        .unwrap();
+        assert!(!water.properties.solid && water.properties.fluid && water.properties.transparent);
+        assert_eq!(water.properties.render_layer, RenderLayer::Translucent);
+    }
+
+    #[test]
+    fn state_properties_have_a_default() {
+        let log = parse(r#"{
+            "id": "minecraft:oak_log",
+            "states": { "axis": ["x", "y", "z"] },
+            "default_state": { "axis": "y" }
+        }"#)
+        .unwrap();
+        assert_eq!(log.states, vec![StateProperty::new("axis", &["x", "y", "z"], "y")]);
+
+        let registry = BlockRegistry::from_definitions(&[log]);
+        let id = registry.id("minecraft:oak_log").unwrap();
+        assert_eq!(registry.property(id, "axis"), Some("y"));
+        assert_eq!(registry.get(id).state_count(), 3);
+    }
+
+    #[test]
+    fn invalid_fields_are_errors() {
+        for text in [
+            r#"{}"#,
+            r#"{ "id": "stone" }"#,
+            r#"{ "id": "a:b", "render_layer": "glass" }"#,
+            r#"{ "id": "a:b", "collision": 1 }"#,
+            r#"{ "id": "a:b", "light_emission": 16 }"#,
+            r#"{ "id": "a:b", "textures": "stone" }"#,
+            r#"{ "id": "a:b", "textures": { "all": "x" }, "model": "block/x" }"#,
+            r##"{ "id": "a:b", "tint": "#12345" }"##,
+            r#"{ "id": "a:b", "states": { "axis": [] } }"#,
+            r#"{ "id": "a:b", "states": { "axis": ["x"] }, "default_state": { "axis": "z" } }"#,
+            r#"{ "id": "a:b", "default_state": { "axis": "x" } }"#,
+        ] {
+            assert!(parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn shipped_definitions_cover_the_generated_blocks() {
+        let definitions = load_definitions(Path::new(env!("CARGO_MANIFEST_DIR")).join("blocks").as_path()).unwrap();
+        let registry = BlockRegistry::from_definitions(&definitions);
+        let vanilla = BlockRegistry::vanilla();
+        for name in ["stone", "dirt", "grass_block", "sand", "bedrock", "water", "oak_log", "oak_leaves"] {
+            let name = format!("minecraft:{}", name);
+            let (id, vanilla_id) = (registry.id(&name).unwrap(), vanilla.id(&name).unwrap());
+            assert_eq!(registry.is_solid(id), vanilla.is_solid(vanilla_id), "{}", name);
+            assert_eq!(registry.render_layer(id), vanilla.render_layer(vanilla_id), "{}", name);
+            // Mesmas propriedades de estado: o gerador e os mundos salvos dependem delas
+            assert_eq!(registry.properties_of(id), vanilla.properties_of(vanilla_id), "{}", name);
+            assert_eq!(registry.get(id).state_count(), vanilla.get(vanilla_id).state_count(), "{}", name);
+        }
+    }
+}
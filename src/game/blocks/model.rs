@@ -0,0 +1,833 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use glam::{Quat, Vec3};
+use crate::game::atlas::{BlockTextures, Tile};
+use crate::game::resources::resource_location;
+use crate::json::Json;
+use super::blockstates::{parse_blockstates, Variant};
+use super::definitions::BlockDefinition;
+use super::registry::BlockRegistry;
+use super::BlockId;
+
+// Limite da cadeia de `parent`; modelos vanilla têm no máximo 3 ou 4 níveis
+const MAX_PARENTS: usize = 32;
+
+// Textura de variáveis (#side) que nenhum modelo da cadeia define; cai no tile `missing`
+pub const MISSING_TEXTURE: &str = "minecraft:missingno";
+
+// Nomes das faces no formato vanilla, na ordem de FACE_OFFSETS
+const FACE_NAMES: [&str; 6] = ["south", "north", "up", "down", "west", "east"];
+
+// Cantos de cada face vistos de fora, em ordem anti-horária (superior esquerdo, inferior esquerdo,
+// inferior direito, superior direito); 0 pega a coordenada de `from` e 1 a de `to`
+const FACE_CORNERS: [[[usize; 3]; 4]; 6] = [
+    [[0, 1, 1], [0, 0, 1], [1, 0, 1], [1, 1, 1]],
+    [[1, 1, 0], [1, 0, 0], [0, 0, 0], [0, 1, 0]],
+    [[0, 1, 0], [0, 1, 1], [1, 1, 1], [1, 1, 0]],
+    [[0, 0, 1], [0, 0, 0], [1, 0, 0], [1, 0, 1]],
+    [[0, 1, 0], [0, 0, 0], [0, 0, 1], [0, 1, 1]],
+    [[1, 1, 1], [1, 0, 1], [1, 0, 0], [1, 1, 0]],
+];
+
+const FACE_NORMALS: [Vec3; 6] = [Vec3::Z, Vec3::NEG_Z, Vec3::Y, Vec3::NEG_Y, Vec3::NEG_X, Vec3::X];
+
+#[derive(Debug, PartialEq)]
+pub enum ModelError {
+    Missing(String), // O modelo ou um dos pais não existe em nenhum pack
+    Cycle(String),
+    Invalid(String, &'static str),
+    Blockstates(String, &'static str), // Id do bloco cujo blockstates/*.json falhou
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::Missing(name) => write!(f, "missing block model {}", name),
+            ModelError::Cycle(name) => write!(f, "block model {} inherits from itself", name),
+            ModelError::Invalid(name, reason) => write!(f, "invalid block model {}: {}", name, reason),
+            ModelError::Blockstates(block, reason) => write!(f, "invalid blockstates for {}: {}", block, reason),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementFace {
+    pub uv: Option<[f32; 4]>, // Em pixels (0 a 16); sem `uv`, vem da posição do elemento
+    pub texture: String,      // Variável ("#side") ou nome de textura
+    pub cullface: Option<usize>,
+    pub rotation: u32, // Giro da textura em graus, no sentido horário
+    pub tint_index: Option<i32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementRotation {
+    pub origin: Vec3, // Em pixels
+    pub axis: usize,
+    pub angle: f32, // Em graus: 0, ±22.5 ou ±45
+    pub rescale: bool,
+}
+
+// Caixa de `from` a `to` (em pixels, 0 a 16) com as faces que existem, na ordem de FACE_OFFSETS
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub from: Vec3,
+    pub to: Vec3,
+    pub rotation: Option<ElementRotation>,
+    pub faces: [Option<ElementFace>; 6],
+}
+
+// Um arquivo models/block/*.json como está, antes de resolver a herança
+#[derive(Clone, Debug, Default)]
+pub struct ModelFile {
+    pub parent: Option<String>,
+    pub textures: BTreeMap<String, String>,
+    pub elements: Option<Vec<Element>>,
+}
+
+fn face_index(name: &str) -> Option<usize> {
+    // "bottom" é um nome antigo de "down" que o vanilla ainda aceita
+    let name = if name == "bottom" { "down" } else { name };
+    FACE_NAMES.iter().position(|&face| face == name)
+}
+
+fn vec3(value: Option<&Json>) -> Option<Vec3> {
+    match value?.as_array()? {
+        [x, y, z] => Some(Vec3::new(x.as_f64()? as f32, y.as_f64()? as f32, z.as_f64()? as f32)),
+        _ => None,
+    }
+}
+
+fn parse_face(value: &Json) -> Result<ElementFace, &'static str> {
+    let uv = match value.get("uv") {
+        None => None,
+        Some(uv) => match uv.as_array() {
+            Some([u1, v1, u2, v2]) => {
+                let uv = [u1, v1, u2, v2].map(Json::as_f64);
+                if uv.contains(&None) {
+                    return Err("face uv must have four numbers");
+                }
+                Some(uv.map(|value| value.unwrap_or_default() as f32))
+            }
+            _ => return Err("face uv must have four numbers"),
+        },
+    };
+    let texture = value.get("texture").and_then(Json::as_str).ok_or("faces need a texture")?;
+    let cullface = match value.get("cullface") {
+        None => None,
+        Some(face) => Some(face.as_str().and_then(face_index).ok_or("unknown cullface")?),
+    };
+    let rotation = match value.get("rotation").map(Json::as_f64) {
+        None => 0,
+        Some(Some(rotation)) if [0.0, 90.0, 180.0, 270.0].contains(&rotation) => rotation as u32,
+        Some(_) => return Err("face rotation must be 0, 90, 180 or 270"),
+    };
+    let tint_index = match value.get("tintindex") {
+        None => None,
+        Some(index) => Some(index.as_f64().ok_or("tintindex must be a number")? as i32),
+    };
+    Ok(ElementFace { uv, texture: texture.to_string(), cullface, rotation, tint_index })
+}
+
+fn parse_element(value: &Json) -> Result<Element, &'static str> {
+    let from = vec3(value.get("from")).ok_or("elements need from and to")?;
+    let to = vec3(value.get("to")).ok_or("elements need from and to")?;
+    // O vanilla aceita de -16 a 32, para modelos que passam um pouco do bloco
+    let in_range = |v: Vec3| v.cmpge(Vec3::splat(-16.0)).all() && v.cmple(Vec3::splat(32.0)).all();
+    if !in_range(from) || !in_range(to) {
+        return Err("element coordinates must be between -16 and 32");
+    }
+
+    let rotation = match value.get("rotation") {
+        None => None,
+        Some(rotation) => {
+            let origin = vec3(rotation.get("origin")).ok_or("rotation needs an origin")?;
+            let axis = match rotation.get("axis").and_then(Json::as_str) {
+                Some("x") => 0,
+                Some("y") => 1,
+                Some("z") => 2,
+                _ => return Err("rotation axis must be x, y or z"),
+            };
+            let angle = match rotation.get("angle").and_then(Json::as_f64) {
+                Some(angle) if [-45.0, -22.5, 0.0, 22.5, 45.0].contains(&angle) => angle as f32,
+                _ => return Err("rotation angle must be 0, 22.5 or 45, positive or negative"),
+            };
+            let rescale = match rotation.get("rescale") {
+                None => false,
+                Some(rescale) => rescale.as_bool().ok_or("rescale must be true or false")?,
+            };
+            Some(ElementRotation { origin, axis, angle, rescale })
+        }
+    };
+
+    let mut faces: [Option<ElementFace>; 6] = Default::default();
+    if let Some(map) = value.get("faces") {
+        for (name, face) in map.as_object().ok_or("faces must be an object")? {
+            let index = face_index(name).ok_or("unknown face name")?;
+            faces[index] = Some(parse_face(face)?);
+        }
+    }
+    Ok(Element { from, to, rotation, faces })
+}
+
+pub fn parse_model(value: &Json) -> Result<ModelFile, &'static str> {
+    let parent = match value.get("parent") {
+        None => None,
+        Some(parent) => Some(resource_location(parent.as_str().ok_or("parent must be a string")?)),
+    };
+
+    let mut textures = BTreeMap::new();
+    if let Some(map) = value.get("textures") {
+        for (key, texture) in map.as_object().ok_or("textures must be an object")? {
+            let texture = texture.as_str().ok_or("texture names must be strings")?;
+            // Variáveis ficam como estão; nomes ganham o namespace
+            let texture = if texture.starts_with('#') { texture.to_string() } else { resource_location(texture) };
+            textures.insert(key.clone(), texture);
+        }
+    }
+
+    let elements = match value.get("elements") {
+        None => None,
+        Some(elements) => Some(
+            elements
+                .as_array()
+                .ok_or("elements must be an array")?
+                .iter()
+                .map(parse_element)
+                .collect::<Result<_, _>>()?,
+        ),
+    };
+    Ok(ModelFile { parent, textures, elements })
+}
+
+// Modelo com a herança já aplicada: texturas de toda a cadeia (o filho sobrescreve o pai) e os
+// elementos do modelo mais próximo que os define
+#[derive(Clone, Debug, Default)]
+pub struct BlockModel {
+    pub textures: BTreeMap<String, String>,
+    pub elements: Vec<Element>,
+}
+
+// Resolve `name` ("minecraft:block/oak_slab") seguindo os `parent`; `load` busca o JSON de cada
+// modelo, normalmente com `ResourceManager::model`
+pub fn resolve_model(name: &str, mut load: impl FnMut(&str) -> Option<Json>) -> Result<BlockModel, ModelError> {
+    let mut chain: Vec<(String, ModelFile)> = Vec::new();
+    let mut next = Some(resource_location(name));
+    while let Some(name) = next {
+        if chain.iter().any(|(seen, _)| *seen == name) {
+            return Err(ModelError::Cycle(name));
+        }
+        if chain.len() == MAX_PARENTS {
+            return Err(ModelError::Invalid(name, "too many parents"));
+        }
+        let value = load(&name).ok_or_else(|| ModelError::Missing(name.clone()))?;
+        let model = parse_model(&value).map_err(|reason| ModelError::Invalid(name.clone(), reason))?;
+        next = model.parent.clone();
+        chain.push((name, model));
+    }
+
+    let mut textures = BTreeMap::new();
+    for (_, model) in chain.iter().rev() {
+        textures.extend(model.textures.clone());
+    }
+    let elements = chain.into_iter().find_map(|(_, model)| model.elements).unwrap_or_default();
+    Ok(BlockModel { textures, elements })
+}
+
+// Face já em coordenadas de bloco (0 a 1), pronta para virar vértices
+#[derive(Clone, Debug, PartialEq)]
+pub struct BakedQuad {
+    pub positions: [[f32; 3]; 4], // Anti-horário visto de fora
+    pub uvs: [[f32; 2]; 4],       // Na textura (0 a 1), com v para cima como nas imagens carregadas
+    pub normal: [f32; 3],
+    pub texture: String,
+    pub cullface: Option<usize>,
+    pub tint_index: Option<i32>,
+}
+
+impl BlockModel {
+    // Nome final de uma textura, seguindo variáveis que apontam para outras (#all -> #texture)
+    pub fn texture(&self, reference: &str) -> String {
+        let mut name = reference;
+        for _ in 0..=self.textures.len() {
+            let Some(variable) = name.strip_prefix('#') else {
+                return resource_location(name);
+            };
+            match self.textures.get(variable) {
+                Some(texture) => name = texture,
+                None => break,
+            }
+        }
+        MISSING_TEXTURE.to_string()
+    }
+
+    // Um único elemento ocupando o bloco todo, com as seis faces escondidas por vizinhos opacos:
+    // esconde as faces dos vizinhos, mesmo com as texturas giradas
+    pub fn is_full_cube(&self) -> bool {
+        let [element] = self.elements.as_slice() else {
+            return false;
+        };
+        element.from == Vec3::ZERO
+            && element.to == Vec3::splat(16.0)
+            && element.rotation.is_none()
+            && element.faces.iter().enumerate().all(|(index, face)| face.as_ref().is_some_and(|face| face.cullface == Some(index)))
+    }
+
+    // Texturas por face se o modelo é um cubo inteiro comum (como block/cube_all), que o mesher
+    // pode mesclar com os vizinhos; `None` para qualquer outra forma
+    pub fn cube_textures(&self) -> Option<[String; 6]> {
+        let [element] = self.elements.as_slice() else {
+            return None;
+        };
+        if !self.is_full_cube() {
+            return None;
+        }
+        const FULL_UV: [f32; 4] = [0.0, 0.0, 16.0, 16.0];
+        let mut textures: [String; 6] = Default::default();
+        for (texture, face) in textures.iter_mut().zip(&element.faces) {
+            let face = face.as_ref()?;
+            let full_uv = face.uv.unwrap_or(FULL_UV) == FULL_UV;
+            if face.rotation != 0 || face.tint_index.is_some() || !full_uv {
+                return None;
+            }
+            *texture = self.texture(&face.texture);
+        }
+        Some(textures)
+    }
+
+    pub fn bake(&self) -> Vec<BakedQuad> {
+        let mut quads = Vec::new();
+        for element in &self.elements {
+            for (index, face) in element.faces.iter().enumerate() {
+                if let Some(face) = face {
+                    quads.push(self.bake_face(element, index, face));
+                }
+            }
+        }
+        quads
+    }
+
+    // Quads com o giro de uma variante de blockstate: primeiro em x, depois em y, em volta do centro
+    pub fn bake_rotated(&self, x: u32, y: u32) -> Vec<BakedQuad> {
+        let mut quads = self.bake();
+        if x == 0 && y == 0 {
+            return quads;
+        }
+        // Ângulos negativos: o vanilla gira no sentido horário olhando para o eixo
+        let turn = Quat::from_rotation_y(-(y as f32).to_radians()) * Quat::from_rotation_x(-(x as f32).to_radians());
+        let center = Vec3::splat(0.5);
+        for quad in &mut quads {
+            quad.positions = quad.positions.map(|p| (center + turn * (Vec3::from(p) - center)).to_array());
+            quad.normal = (turn * Vec3::from(quad.normal)).to_array();
+            // A face que esconde o quad gira junto
+            quad.cullface = quad.cullface.and_then(|face| {
+                let normal = turn * FACE_NORMALS[face];
+                FACE_NORMALS.iter().position(|other| other.dot(normal) > 0.5)
+            });
+        }
+        quads
+    }
+
+    fn bake_face(&self, element: &Element, index: usize, face: &ElementFace) -> BakedQuad {
+        let (from, to) = (element.from, element.to);
+        let positions = FACE_CORNERS[index].map(|corner| {
+            let pick = |axis: usize| if corner[axis] == 0 { from[axis] } else { to[axis] };
+            Vec3::new(pick(0), pick(1), pick(2))
+        });
+
+        // Sem rotação do elemento a normal é a da face; com rotação, gira junto
+        let (positions, normal) = match &element.rotation {
+            None => (positions, FACE_NORMALS[index]),
+            Some(rotation) => {
+                let mut axis = Vec3::ZERO;
+                axis[rotation.axis] = 1.0;
+                let turn = Quat::from_axis_angle(axis, rotation.angle.to_radians());
+                // Reescala estica os outros dois eixos para que a diagonal ocupe o bloco todo
+                let mut scale = Vec3::ONE;
+                if rotation.rescale {
+                    scale = Vec3::splat(1.0 / rotation.angle.to_radians().cos());
+                    scale[rotation.axis] = 1.0;
+                }
+                let positions = positions.map(|p| rotation.origin + turn * (p - rotation.origin) * scale);
+                (positions, turn * FACE_NORMALS[index])
+            }
+        };
+
+        // UVs padrão projetam o elemento na face, como no vanilla
+        let uv = face.uv.unwrap_or(match index {
+            0 => [from.x, 16.0 - to.y, to.x, 16.0 - from.y],
+            1 => [16.0 - to.x, 16.0 - to.y, 16.0 - from.x, 16.0 - from.y],
+            2 => [from.x, from.z, to.x, to.z],
+            3 => [from.x, 16.0 - to.z, to.x, 16.0 - from.z],
+            4 => [from.z, 16.0 - to.y, to.z, 16.0 - from.y],
+            _ => [16.0 - to.z, 16.0 - to.y, 16.0 - from.z, 16.0 - from.y],
+        });
+        // Os pixels do JSON contam de cima para baixo; as imagens carregadas têm v para cima
+        let [u1, v1, u2, v2] = uv.map(|value| value / 16.0);
+        let corners = [[u1, 1.0 - v1], [u1, 1.0 - v2], [u2, 1.0 - v2], [u2, 1.0 - v1]];
+        // Girar a textura no sentido horário leva cada canto dela para o próximo canto no sentido horário
+        let steps = (face.rotation / 90) as usize;
+        let uvs = std::array::from_fn(|corner| corners[(corner + steps) % 4]);
+
+        BakedQuad {
+            positions: positions.map(|p| (p / 16.0).to_array()),
+            uvs,
+            normal: normal.to_array(),
+            texture: self.texture(&face.texture),
+            cullface: face.cullface,
+            tint_index: face.tint_index,
+        }
+    }
+}
+
+// Como cada estado de bloco é desenhado
+#[derive(Clone, Debug, PartialEq)]
+pub enum Appearance {
+    Cube([String; 6]), // Uma textura por face, na ordem de FACE_OFFSETS; entra no greedy meshing
+    Quads { quads: Vec<BakedQuad>, tint: [f32; 3] },
+}
+
+impl Appearance {
+    fn of(model: &BlockModel, x: u32, y: u32, tint: [f32; 3]) -> Self {
+        match model.cube_textures() {
+            Some(textures) if x == 0 && y == 0 => Appearance::Cube(textures),
+            _ => Appearance::Quads { quads: model.bake_rotated(x, y), tint },
+        }
+    }
+}
+
+// Resolve cada modelo uma vez só; um modelo com erro é reportado uma vez e fica `None`
+fn cached_model<'a>(
+    cache: &'a mut HashMap<String, Option<BlockModel>>,
+    name: &str,
+    load: &mut impl FnMut(&str) -> Option<Json>,
+    errors: &mut Vec<ModelError>,
+) -> Option<&'a BlockModel> {
+    cache
+        .entry(name.to_string())
+        .or_insert_with(|| resolve_model(name, &mut *load).map_err(|err| errors.push(err)).ok())
+        .as_ref()
+}
+
+// Aparência de todos os estados dos blocos definidos. `textures` ou `model` da definição valem para
+// todos os estados; sem eles, as variantes de blockstates/<nome>.json escolhem o modelo de cada um.
+// Blocos com algum estado que não é um cubo inteiro deixam de esconder as faces dos vizinhos;
+// estados que falham ficam sem aparência e usam a textura `missing`
+pub fn resolve_appearances(
+    definitions: &[BlockDefinition],
+    registry: &mut BlockRegistry,
+    mut load_model: impl FnMut(&str) -> Option<Json>,
+    mut load_blockstates: impl FnMut(&str) -> Option<Json>,
+) -> (HashMap<BlockId, Appearance>, Vec<ModelError>) {
+    let mut appearances = HashMap::new();
+    let mut errors = Vec::new();
+    let mut models = HashMap::new();
+
+    for definition in definitions {
+        let Some(id) = registry.id(&definition.name) else {
+            continue;
+        };
+        let states: Vec<BlockId> = registry.get(id).state_ids().collect();
+        if let Some(textures) = &definition.textures {
+            appearances.extend(states.iter().map(|&state| (state, Appearance::Cube(textures.clone()))));
+            continue;
+        }
+
+        let variants: Vec<(BlockId, Variant)> = match &definition.model {
+            Some(model) => states.iter().map(|&state| (state, Variant { model: model.clone(), x: 0, y: 0 })).collect(),
+            None => {
+                let file = load_blockstates(&definition.name).ok_or("missing blockstates file");
+                let file = match file.and_then(|value| parse_blockstates(&value)) {
+                    Ok(file) => file,
+                    Err(reason) => {
+                        errors.push(ModelError::Blockstates(definition.name.clone(), reason));
+                        continue;
+                    }
+                };
+                let variants: Vec<_> = states
+                    .iter()
+                    .filter_map(|&state| Some((state, file.variant(&registry.properties_of(state))?.clone())))
+                    .collect();
+                if variants.len() < states.len() {
+                    errors.push(ModelError::Blockstates(definition.name.clone(), "some states match no variant"));
+                }
+                variants
+            }
+        };
+
+        let mut full_cube = true;
+        for (state, variant) in variants {
+            if let Some(model) = cached_model(&mut models, &variant.model, &mut load_model, &mut errors) {
+                full_cube &= model.is_full_cube();
+                appearances.insert(state, Appearance::of(model, variant.x, variant.y, definition.tint));
+            }
+        }
+        if !full_cube {
+            registry.properties_mut(id).transparent = true;
+        }
+    }
+    (appearances, errors)
+}
+
+// Quad de um modelo com a textura já no atlas
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelQuad {
+    pub positions: [[f32; 3]; 4],
+    pub uvs: [[f32; 2]; 4],
+    pub normal: [f32; 3],
+    pub tile: Tile,
+    pub cullface: Option<usize>,
+    pub tint: [f32; 3], // Cor multiplicada na textura; branco sem `tintindex`
+}
+
+// Aparência de cada estado para o mesher: cubos inteiros só precisam de um tile por face e entram
+// no greedy meshing; os outros modelos são desenhados quad a quad
+#[derive(Default)]
+pub struct BlockModels {
+    pub textures: BlockTextures,
+    shapes: HashMap<BlockId, Vec<ModelQuad>>,
+}
+
+impl BlockModels {
+    pub fn new(textures: BlockTextures) -> Self {
+        Self { textures, shapes: HashMap::new() }
+    }
+
+    // Troca os nomes de textura pelos tiles do atlas; devolve também os nomes que não estão em `tiles`
+    pub fn from_appearances(appearances: &HashMap<BlockId, Appearance>, tiles: &HashMap<String, Tile>, missing: Tile) -> (Self, Vec<String>) {
+        let mut block_models = Self::new(BlockTextures::new(missing));
+        let mut unknown = Vec::new();
+        let mut tile = |name: &str| match tiles.get(name) {
+            Some(&tile) => tile,
+            None => {
+                if !unknown.iter().any(|known| known == name) {
+                    unknown.push(name.to_string());
+                }
+                missing
+            }
+        };
+
+        for (&state, appearance) in appearances {
+            match appearance {
+                Appearance::Cube(names) => block_models.textures.insert(state, names.each_ref().map(|name| tile(name))),
+                Appearance::Quads { quads, tint } => {
+                    let quads = quads
+                        .iter()
+                        .map(|quad| ModelQuad {
+                            positions: quad.positions,
+                            uvs: quad.uvs,
+                            normal: quad.normal,
+                            tile: tile(&quad.texture),
+                            cullface: quad.cullface,
+                            tint: if quad.tint_index.is_some() { *tint } else { [1.0; 3] },
+                        })
+                        .collect();
+                    block_models.insert_shape(state, quads);
+                }
+            }
+        }
+        unknown.sort();
+        (block_models, unknown)
+    }
+
+    pub fn insert_shape(&mut self, block: BlockId, quads: Vec<ModelQuad>) {
+        self.shapes.insert(block, quads);
+    }
+
+    // Quads do modelo, para estados que não são cubos inteiros
+    pub fn shape(&self, block: BlockId) -> Option<&[ModelQuad]> {
+        self.shapes.get(&block).map(Vec::as_slice)
+    }
+
+    // Textura e cor para o ícone do item: a face sul, ou o primeiro quad de modelos sem ela
+    pub fn icon(&self, block: BlockId) -> (Tile, [f32; 3]) {
+        match self.shape(block) {
+            Some(quads) => quads
+                .iter()
+                .find(|quad| quad.normal == [0.0, 0.0, 1.0])
+                .or(quads.first())
+                .map_or((self.textures.get(block, 0), [1.0; 3]), |quad| (quad.tile, quad.tint)),
+            None => (self.textures.get(block, 0), [1.0; 3]),
+        }
+    }
+
+    // Caixa que envolve o modelo, em coordenadas do bloco (0 a 1); cubos inteiros ocupam tudo
+    pub fn bounds(&self, block: BlockId) -> (Vec3, Vec3) {
+        match self.shape(block) {
+            Some(quads) if !quads.is_empty() => quads
+                .iter()
+                .flat_map(|quad| quad.positions)
+                .map(Vec3::from)
+                .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), corner| (min.min(corner), max.max(corner))),
+            _ => (Vec3::ZERO, Vec3::ONE),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    const BLOCK: &str = r#"{ "ambientocclusion": true }"#;
+    const CUBE: &str = r##"{
+        "parent": "block/block",
+        "elements": [{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": {
+            "down":  { "texture": "#down", "cullface": "down" },
+            "up":    { "texture": "#up", "cullface": "up" },
+            "north": { "texture": "#north", "cullface": "north" },
+            "south": { "texture": "#south", "cullface": "south" },
+            "west":  { "texture": "#west", "cullface": "west" },
+            "east":  { "texture": "#east", "cullface": "east" }
+        }}]
+    }"##;
+    const CUBE_ALL: &str = r##"{
+        "parent": "block/cube",
+        "textures": { "particle": "#all", "down": "#all", "up": "#all", "north": "#all", "east": "#all", "south": "#all", "west": "#all" }
+    }"##;
+    const SLAB: &str = r##"{
+        "elements": [{ "from": [0, 0, 0], "to": [16, 8, 16], "faces": {
+            "down":  { "uv": [0, 0, 16, 16], "texture": "#bottom", "cullface": "down" },
+            "up":    { "uv": [0, 0, 16, 16], "texture": "#top" },
+            "north": { "texture": "#side", "cullface": "north" },
+            "south": { "texture": "#side", "cullface": "south" },
+            "west":  { "texture": "#side", "cullface": "west" },
+            "east":  { "texture": "#side", "cullface": "east", "tintindex": 0 }
+        }}]
+    }"##;
+    const CROSS: &str = r##"{
+        "elements": [{ "from": [0.8, 0, 8], "to": [15.2, 16, 8],
+            "rotation": { "origin": [8, 8, 8], "axis": "y", "angle": 45, "rescale": true },
+            "faces": { "north": { "uv": [0, 0, 16, 16], "texture": "#cross" } }
+        }]
+    }"##;
+
+    fn load(name: &str) -> Option<Json> {
+        let text = match name {
+            "minecraft:block/block" => BLOCK,
+            "minecraft:block/cube" => CUBE,
+            "minecraft:block/cube_all" => CUBE_ALL,
+            "minecraft:block/slab" => SLAB,
+            "minecraft:block/cross" => CROSS,
+            "minecraft:block/stone" => r#"{ "parent": "block/cube_all", "textures": { "all": "block/stone" } }"#,
+            "minecraft:block/stone_slab" => r#"{ "parent": "block/slab", "textures": { "bottom": "block/stone", "top": "block/stone", "side": "block/stone" } }"#,
+            "minecraft:block/poppy" => r#"{ "parent": "minecraft:block/cross", "textures": { "cross": "block/poppy" } }"#,
+            "minecraft:block/loop_a" => r#"{ "parent": "block/loop_b" }"#,
+            "minecraft:block/loop_b" => r#"{ "parent": "block/loop_a" }"#,
+            "minecraft:block/orphan" => r#"{ "parent": "block/nothing" }"#,
+            _ => return None,
+        };
+        Some(json::parse(text).unwrap())
+    }
+
+    fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4)
+    }
+
+    #[test]
+    fn parents_supply_elements_and_texture_variables() {
+        let stone = resolve_model("block/stone", load).unwrap();
+        assert_eq!(stone.elements.len(), 1);
+        assert_eq!(stone.texture("#north"), "minecraft:block/stone");
+        assert_eq!(stone.texture("#nothing"), MISSING_TEXTURE);
+        assert_eq!(stone.texture("minecraft:block/dirt"), "minecraft:block/dirt");
+        assert_eq!(stone.cube_textures(), Some([(); 6].map(|_| "minecraft:block/stone".to_string())));
+
+        // O slab não é um cubo: não entra no greedy meshing
+        assert!(resolve_model("block/stone_slab", load).unwrap().cube_textures().is_none());
+    }
+
+    #[test]
+    fn broken_inheritance_is_an_error() {
+        assert_eq!(resolve_model("block/loop_a", load).unwrap_err(), ModelError::Cycle("minecraft:block/loop_a".to_string()));
+        assert_eq!(resolve_model("block/orphan", load).unwrap_err(), ModelError::Missing("minecraft:block/nothing".to_string()));
+        let bad = |text: &str| parse_model(&json::parse(text).unwrap()).is_err();
+        assert!(bad(r#"{ "elements": [{ "from": [0, 0], "to": [16, 16, 16] }] }"#));
+        assert!(bad(r##"{ "elements": [{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": { "top": { "texture": "#a" } } }] }"##));
+        assert!(bad(r#"{ "elements": [{ "from": [0, 0, 0], "to": [16, 16, 16], "rotation": { "origin": [8, 8, 8], "axis": "y", "angle": 30 } }] }"#));
+        assert!(bad(r##"{ "elements": [{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": { "up": { "texture": "#a", "rotation": 45 } } }] }"##));
+    }
+
+    #[test]
+    fn slab_bakes_half_height_faces() {
+        let quads = resolve_model("block/stone_slab", load).unwrap().bake();
+        assert_eq!(quads.len(), 6);
+        assert!(quads.iter().all(|quad| quad.texture == "minecraft:block/stone"));
+
+        // A face de cima fica no meio do bloco e não é escondida por vizinhos
+        let up = quads.iter().find(|quad| quad.normal == [0.0, 1.0, 0.0]).unwrap();
+        assert!(up.positions.iter().all(|p| p[1] == 0.5));
+        assert_eq!(up.cullface, None);
+
+        // O lado pega a metade de baixo da textura: v de 0 a 0.5 com v para cima
+        let south = quads.iter().find(|quad| quad.normal == [0.0, 0.0, 1.0]).unwrap();
+        assert_eq!(south.positions, [[0.0, 0.5, 1.0], [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 0.5, 1.0]]);
+        assert_eq!(south.uvs, [[0.0, 0.5], [0.0, 0.0], [1.0, 0.0], [1.0, 0.5]]);
+        assert_eq!((south.cullface, south.tint_index), (Some(0), None));
+        let east = quads.iter().find(|quad| quad.normal == [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(east.tint_index, Some(0));
+    }
+
+    #[test]
+    fn faces_wind_counterclockwise_from_outside() {
+        for quad in resolve_model("block/stone", load).unwrap().bake() {
+            let [a, b, c, _] = quad.positions.map(Vec3::from);
+            assert!(close((b - a).cross(c - a).normalize().to_array(), quad.normal), "{:?}", quad);
+        }
+    }
+
+    #[test]
+    fn face_rotation_turns_the_texture() {
+        let model = |rotation: u32| {
+            let text = format!(
+                r##"{{ "elements": [{{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": {{ "up": {{ "texture": "#a", "rotation": {} }} }} }}] }}"##,
+                rotation
+            );
+            resolve_model("test", |_| json::parse(&text).ok()).unwrap().bake().remove(0).uvs
+        };
+        let straight = model(0);
+        assert_eq!(straight, [[0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]);
+        // Com 90 graus o canto superior esquerdo da textura vai para o superior direito da face
+        assert_eq!(model(90)[3], straight[0]);
+        assert_eq!(model(180)[2], straight[0]);
+    }
+
+    #[test]
+    fn rotated_elements_cross_the_block_diagonal() {
+        let poppy = resolve_model("block/poppy", load).unwrap();
+        assert!(poppy.cube_textures().is_none());
+        let quads = poppy.bake();
+        assert_eq!(quads[0].texture, "minecraft:block/poppy");
+
+        // Girado 45 graus em y e reescalado, o plano vai quase de um canto do bloco ao oposto
+        let corners = quads[0].positions;
+        assert!(close(corners[0], [0.95, 1.0, 0.05]), "{:?}", corners);
+        assert!(close(corners[2], [0.05, 0.0, 0.95]), "{:?}", corners);
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!(close(quads[0].normal, [-half, 0.0, -half]), "{:?}", quads[0].normal);
+    }
+
+    #[test]
+    fn variant_rotation_turns_the_whole_model() {
+        let horizontal = r##"{ "parent": "block/block", "textures": { "end": "block/log_top", "side": "block/log" },
+            "elements": [{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": {
+                "down": { "texture": "#end", "cullface": "down" }, "up": { "texture": "#end", "cullface": "up" },
+                "north": { "texture": "#side", "cullface": "north" }, "south": { "texture": "#side", "cullface": "south" },
+                "west": { "texture": "#side", "cullface": "west", "rotation": 270 },
+                "east": { "texture": "#side", "cullface": "east", "rotation": 90 }
+            }}]
+        }"##;
+        let model = resolve_model("log", |name| load(name).or_else(|| json::parse(horizontal).ok())).unwrap();
+        assert!(model.is_full_cube() && model.cube_textures().is_none());
+
+        // x = 90 leva as pontas de cima e de baixo para norte e sul (eixo z)
+        let quads = model.bake_rotated(90, 0);
+        let ends: Vec<_> = quads.iter().filter(|quad| quad.texture == "minecraft:block/log_top").collect();
+        assert_eq!(ends.len(), 2);
+        for end in ends {
+            assert!(close(end.normal, [0.0, 0.0, 1.0]) || close(end.normal, [0.0, 0.0, -1.0]), "{:?}", end.normal);
+            let face = end.cullface.unwrap();
+            assert!(close(FACE_NORMALS[face].to_array(), end.normal));
+            assert!(end.positions.iter().all(|p| p.iter().all(|&c| (-1e-5..=1.0 + 1e-5).contains(&c))));
+        }
+        // E y = 90 em seguida leva para leste e oeste (eixo x)
+        let ends = model.bake_rotated(90, 90).into_iter().filter(|quad| quad.texture == "minecraft:block/log_top");
+        assert!(ends.map(|quad| quad.normal[0].abs()).all(|x| (x - 1.0).abs() < 1e-5));
+    }
+
+    fn definition(text: &str) -> BlockDefinition {
+        crate::game::blocks::definitions::parse_definition(&json::parse(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn states_pick_their_appearance() {
+        let definitions = vec![
+            definition(r#"{ "id": "minecraft:dirt", "textures": { "all": "block/dirt" } }"#),
+            definition(r##"{ "id": "minecraft:stone_slab", "states": { "type": ["bottom", "double"] }, "tint": "#80FF00" }"##),
+            definition(r#"{ "id": "minecraft:poppy", "model": "block/poppy" }"#),
+            definition(r#"{ "id": "minecraft:mystery" }"#),
+        ];
+        let mut registry = BlockRegistry::from_definitions(&definitions);
+        let blockstates = |name: &str| {
+            let text = match name {
+                "minecraft:stone_slab" => r#"{ "variants": { "type=bottom": { "model": "block/stone_slab" }, "type=double": { "model": "block/stone" } } }"#,
+                _ => return None,
+            };
+            json::parse(text).ok()
+        };
+        let (appearances, errors) = resolve_appearances(&definitions, &mut registry, load, blockstates);
+        assert_eq!(errors, vec![ModelError::Blockstates("minecraft:mystery".to_string(), "missing blockstates file")]);
+
+        let dirt = registry.id("minecraft:dirt").unwrap();
+        assert!(matches!(&appearances[&dirt], Appearance::Cube(faces) if faces[2] == "minecraft:block/dirt"));
+        let slab = registry.id("minecraft:stone_slab").unwrap();
+        let double = registry.with_property(slab, "type", "double").unwrap();
+        assert!(matches!(&appearances[&slab], Appearance::Quads { quads, tint } if quads.len() == 6 && *tint == [128.0 / 255.0, 1.0, 0.0]));
+        assert!(matches!(&appearances[&double], Appearance::Cube(_)));
+        assert!(matches!(&appearances[&registry.id("minecraft:poppy").unwrap()], Appearance::Quads { .. }));
+
+        // Um estado que não é cubo basta para o bloco deixar os vizinhos aparecerem
+        assert!(registry.is_transparent(double) && !registry.is_transparent(dirt));
+        assert!(!appearances.contains_key(&registry.id("minecraft:mystery").unwrap()));
+    }
+
+    #[test]
+    fn appearances_map_textures_to_tiles() {
+        let stone_tile = Tile { rect: [0.0, 0.0, 0.5, 0.5], layer: 0 };
+        let missing = Tile { layer: 9, ..Tile::FULL };
+        let tiles = HashMap::from([("minecraft:block/stone".to_string(), stone_tile)]);
+        let slab = resolve_model("block/stone_slab", load).unwrap();
+        let mut appearances = HashMap::new();
+        appearances.insert(BlockId(1), Appearance::of(&resolve_model("block/stone", load).unwrap(), 0, 0, [1.0; 3]));
+        appearances.insert(BlockId(2), Appearance::of(&slab, 0, 0, [0.5; 3]));
+        let mut log = [(); 6].map(|_| "minecraft:block/oak_log".to_string());
+        log[2] = "minecraft:block/stone".to_string();
+        appearances.insert(BlockId(3), Appearance::Cube(log));
+
+        let (models, unknown) = BlockModels::from_appearances(&appearances, &tiles, missing);
+        assert_eq!(unknown, vec!["minecraft:block/oak_log".to_string()]);
+        assert!(models.shape(BlockId(1)).is_none());
+        assert_eq!(models.textures.get(BlockId(1), 0), stone_tile);
+        assert_eq!((models.textures.get(BlockId(3), 2), models.textures.get(BlockId(3), 0)), (stone_tile, missing));
+
+        let slab = models.shape(BlockId(2)).unwrap();
+        assert!(slab.iter().all(|quad| quad.tile == stone_tile));
+        // Só a face com `tintindex` recebe a cor da definição
+        let tinted: Vec<_> = slab.iter().filter(|quad| quad.tint != [1.0; 3]).map(|quad| quad.normal).collect();
+        assert_eq!(tinted, vec![[1.0, 0.0, 0.0]]);
+
+        assert_eq!(models.icon(BlockId(3)), (missing, [1.0; 3]));
+        assert_eq!(models.icon(BlockId(2)).0, stone_tile);
+        assert_eq!(models.bounds(BlockId(1)), (Vec3::ZERO, Vec3::ONE));
+        assert_eq!(models.bounds(BlockId(2)), (Vec3::ZERO, Vec3::new(1.0, 0.5, 1.0)));
+    }
+
+    #[test]
+    fn shipped_blocks_resolve_from_the_builtin_pack() {
+        use crate::game::resources::{ResourceManager, ResourcePack};
+        use std::path::Path;
+
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let resources = ResourceManager::new(vec![ResourcePack::open(&root.join("resources")).unwrap()]);
+        let definitions = crate::game::blocks::definitions::load_definitions(&root.join("blocks")).unwrap();
+        let mut registry = BlockRegistry::from_definitions(&definitions);
+        let (appearances, errors) =
+            resolve_appearances(&definitions, &mut registry, |name| resources.model(name), |name| resources.blockstate(name));
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        // Todo estado de todo bloco definido tem aparência
+        for definition in &definitions {
+            let block = registry.get(registry.id(&definition.name).unwrap());
+            assert!(block.state_ids().all(|state| appearances.contains_key(&state)), "{}", definition.name);
+        }
+        // Troncos em pé entram no greedy meshing; deitados são desenhados quad a quad mas escondem os vizinhos
+        let log = registry.id("minecraft:oak_log").unwrap();
+        assert!(matches!(appearances[&log], Appearance::Cube(_)));
+        let sideways = registry.with_property(log, "axis", "x").unwrap();
+        assert!(matches!(appearances[&sideways], Appearance::Quads { .. }));
+        assert!(!registry.is_transparent(log));
+        assert!(registry.is_transparent(registry.id("minecraft:stone_slab").unwrap()));
+    }
+}
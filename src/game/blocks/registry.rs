@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Id numérico compacto de um estado de bloco (tipo + valores das propriedades), como os ids de
+// estado do Minecraft. Blocos sem propriedades têm um único estado
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(pub u16);
+
+impl BlockId {
+    pub const AIR: BlockId = BlockId(0);
+}
+
+// Como as faces do bloco são desenhadas
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderLayer {
+    #[default]
+    Opaque,
+    Cutout,      // Pixels totalmente transparentes ou opacos (folhas)
+    Translucent, // Misturado com o que está atrás (água)
+}
+
+#[derive(Clone, Debug)]
+pub struct BlockProperties {
+    pub solid: bool,       // Colide com entidades
+    pub transparent: bool, // Não esconde as faces dos vizinhos
+    pub render_layer: RenderLayer,
+    pub fluid: bool,
+    pub gravity: bool,     // Cai quando não tem apoio (areia, cascalho)
+    pub hardness: f32,     // Negativo = indestrutível
+    pub light_emission: u8,
+}
+
+impl BlockProperties {
+    pub const fn solid(hardness: f32) -> Self {
+        Self {
+            solid: true,
+            transparent: false,
+            render_layer: RenderLayer::Opaque,
+            fluid: false,
+            gravity: false,
+            hardness,
+            light_emission: 0,
+        }
+    }
+}
+
+impl Default for BlockProperties {
+    fn default() -> Self {
+        Self::solid(1.0)
+    }
+}
+
+// Propriedade de estado com valores finitos, como "axis": x, y ou z
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateProperty {
+    pub name: String,
+    pub values: Vec<String>,
+    pub default: usize, // Índice em `values`
+}
+
+impl StateProperty {
+    pub fn new(name: &str, values: &[&str], default: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            values: values.iter().map(|value| value.to_string()).collect(),
+            default: values.iter().position(|&value| value == default).unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BlockType {
+    pub name: String,
+    pub properties: BlockProperties,
+    pub states: Vec<StateProperty>, // Em ordem de nome, como no vanilla
+    pub first_state: BlockId,
+    pub default_state: BlockId,
+}
+
+impl BlockType {
+    // Toda combinação de valores é um estado
+    pub fn state_count(&self) -> usize {
+        self.states.iter().map(|property| property.values.len()).product()
+    }
+
+    pub fn state_ids(&self) -> impl Iterator<Item = BlockId> {
+        let first = self.first_state.0;
+        (first..first + self.state_count() as u16).map(BlockId)
+    }
+
+    // Id do estado com os índices de valor dados, um por propriedade; a última varia mais rápido
+    fn state_id(&self, values: &[usize]) -> BlockId {
+        let index = self
+            .states
+            .iter()
+            .zip(values)
+            .fold(0, |index, (property, &value)| index * property.values.len() + value);
+        BlockId(self.first_state.0 + index as u16)
+    }
+
+    fn state_values(&self, id: BlockId) -> Vec<usize> {
+        let mut index = (id.0 - self.first_state.0) as usize;
+        let mut values = vec![0; self.states.len()];
+        for (value, property) in values.iter_mut().zip(&self.states).rev() {
+            *value = index % property.values.len();
+            index /= property.values.len();
+        }
+        values
+    }
+}
+
+// Mapeia ids com namespace ("minecraft:stone") para tipos e os estados de cada tipo para ids numéricos
+#[derive(Clone, Debug)]
+pub struct BlockRegistry {
+    types: Vec<BlockType>,
+    by_name: HashMap<String, usize>,
+    state_types: Vec<u16>, // Tipo de cada estado
+}
+
+impl BlockRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            types: Vec::new(),
+            by_name: HashMap::new(),
+            state_types: Vec::new(),
+        };
+        registry.register("minecraft:air", BlockProperties {
+            solid: false,
+            transparent: true,
+            hardness: 0.0,
+            ..BlockProperties::default()
+        });
+        registry
+    }
+
+    pub fn vanilla() -> Self {
+        let mut registry = Self::new();
+        registry.register("minecraft:stone", BlockProperties::solid(1.5));
+        registry.register("minecraft:dirt", BlockProperties::solid(0.5));
+        registry.register("minecraft:grass_block", BlockProperties::solid(0.6));
+        registry.register("minecraft:sand", BlockProperties {
+            gravity: true,
+            ..BlockProperties::solid(0.5)
+        });
+        registry.register("minecraft:bedrock", BlockProperties::solid(-1.0));
+        let levels: Vec<String> = (0..16).map(|level| level.to_string()).collect();
+        let levels: Vec<&str> = levels.iter().map(String::as_str).collect();
+        registry.register_states(
+            "minecraft:water",
+            BlockProperties {
+                solid: false,
+                transparent: true,
+                render_layer: RenderLayer::Translucent,
+                fluid: true,
+                hardness: 100.0,
+                ..BlockProperties::default()
+            },
+            vec![StateProperty::new("level", &levels, "0")],
+        );
+        registry.register_states(
+            "minecraft:oak_log",
+            BlockProperties::solid(2.0),
+            vec![StateProperty::new("axis", &["x", "y", "z"], "y")],
+        );
+        registry.register("minecraft:oak_leaves", BlockProperties {
+            transparent: true,
+            render_layer: RenderLayer::Cutout,
+            ..BlockProperties::solid(0.2)
+        });
+        registry
+    }
+
+    // Registra (ou substitui) um tipo de bloco sem propriedades de estado
+    pub fn register(&mut self, name: &str, properties: BlockProperties) -> BlockId {
+        self.register_states(name, properties, Vec::new())
+    }
+
+    // Registra um tipo com um estado para cada combinação de `states` e devolve o estado padrão.
+    // Um tipo já registrado só tem as propriedades substituídas; os estados ficam como estavam
+    pub fn register_states(&mut self, name: &str, properties: BlockProperties, mut states: Vec<StateProperty>) -> BlockId {
+        if let Some(&index) = self.by_name.get(name) {
+            let block = &mut self.types[index];
+            block.properties = properties;
+            return block.default_state;
+        }
+
+        states.sort_by(|a, b| a.name.cmp(&b.name));
+        let index = self.types.len();
+        let mut block = BlockType {
+            name: name.to_string(),
+            properties,
+            states,
+            first_state: BlockId(self.state_types.len() as u16),
+            default_state: BlockId::AIR,
+        };
+        let defaults: Vec<usize> = block.states.iter().map(|property| property.default).collect();
+        block.default_state = block.state_id(&defaults);
+        self.state_types.extend(std::iter::repeat_n(index as u16, block.state_count()));
+
+        let id = block.default_state;
+        self.types.push(block);
+        self.by_name.insert(name.to_string(), index);
+        id
+    }
+
+    // Estado padrão do bloco
+    pub fn id(&self, name: &str) -> Option<BlockId> {
+        self.by_name.get(name).map(|&index| self.types[index].default_state)
+    }
+
+    // Tipo do estado; ids desconhecidos caem em ar
+    pub fn get(&self, id: BlockId) -> &BlockType {
+        let index = self.state_types.get(id.0 as usize).copied().unwrap_or(0);
+        &self.types[index as usize]
+    }
+
+    // Estado com as propriedades dadas por nome; as que faltam ficam no padrão e valores desconhecidos são ignorados
+    pub fn state(&self, name: &str, values: &[(&str, &str)]) -> Option<BlockId> {
+        let block = &self.types[*self.by_name.get(name)?];
+        let mut state = block.default_state;
+        for &(property, value) in values {
+            state = self.with_property(state, property, value).unwrap_or(state);
+        }
+        Some(state)
+    }
+
+    // Valor de uma propriedade do estado, como "y" para "axis"
+    #[cfg(test)]
+    pub fn property(&self, id: BlockId, name: &str) -> Option<&str> {
+        let block = self.get(id);
+        let index = block.states.iter().position(|property| property.name == name)?;
+        let value = block.state_values(id)[index];
+        Some(&block.states[index].values[value])
+    }
+
+    // Todas as propriedades do estado, em ordem de nome
+    pub fn properties_of(&self, id: BlockId) -> Vec<(&str, &str)> {
+        let block = self.get(id);
+        block
+            .states
+            .iter()
+            .zip(block.state_values(id))
+            .map(|(property, value)| (property.name.as_str(), property.values[value].as_str()))
+            .collect()
+    }
+
+    // O mesmo bloco com outro valor numa propriedade; `None` se o tipo não tem esse valor
+    pub fn with_property(&self, id: BlockId, name: &str, value: &str) -> Option<BlockId> {
+        let block = self.get(id);
+        let index = block.states.iter().position(|property| property.name == name)?;
+        let mut values = block.state_values(id);
+        values[index] = block.states[index].values.iter().position(|v| v == value)?;
+        Some(block.state_id(&values))
+    }
+
+    pub fn properties(&self, id: BlockId) -> &BlockProperties {
+        &self.get(id).properties
+    }
+
+    // Propriedades do tipo do estado, compartilhadas por todos os estados dele
+    pub fn properties_mut(&mut self, id: BlockId) -> &mut BlockProperties {
+        let index = self.state_types.get(id.0 as usize).copied().unwrap_or(0);
+        &mut self.types[index as usize].properties
+    }
+
+    #[inline]
+    pub fn is_solid(&self, id: BlockId) -> bool {
+        self.properties(id).solid
+    }
+
+    #[inline]
+    pub fn is_transparent(&self, id: BlockId) -> bool {
+        self.properties(id).transparent
+    }
+
+    #[inline]
+    pub fn is_fluid(&self, id: BlockId) -> bool {
+        self.properties(id).fluid
+    }
+
+    #[inline]
+    pub fn render_layer(&self, id: BlockId) -> RenderLayer {
+        self.properties(id).render_layer
+    }
+
+    #[inline]
+    pub fn has_gravity(&self, id: BlockId) -> bool {
+        self.properties(id).gravity
+    }
+}
+
+impl Default for BlockRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+
+// Registro global; usa os blocos vanilla se nada foi instalado antes
+pub fn registry() -> &'static BlockRegistry {
+    REGISTRY.get_or_init(BlockRegistry::vanilla)
+}
+
+// Instala o registro global; só funciona antes do primeiro `registry()`, senão devolve o registro recusado
+pub fn install_registry(registry: BlockRegistry) -> Result<(), BlockRegistry> {
+    REGISTRY.set(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_map_to_dense_ids() {
+        let mut registry = BlockRegistry::new();
+        assert_eq!(registry.id("minecraft:air"), Some(BlockId::AIR));
+        let stone = registry.register("minecraft:stone", BlockProperties::solid(1.5));
+        let glass = registry.register("minecraft:glass", BlockProperties { transparent: true, ..BlockProperties::solid(0.3) });
+        assert_eq!((stone, glass), (BlockId(1), BlockId(2)));
+        assert_eq!(registry.id("minecraft:glass"), Some(glass));
+        assert_eq!(registry.id("minecraft:nope"), None);
+        assert_eq!(registry.get(glass).name, "minecraft:glass");
+        assert!(registry.is_solid(glass) && registry.is_transparent(glass));
+
+        // Registrar de novo troca as propriedades e mantém o id
+        assert_eq!(registry.register("minecraft:stone", BlockProperties::solid(3.0)), stone);
+        assert_eq!(registry.properties(stone).hardness, 3.0);
+        // Ids desconhecidos caem em ar
+        assert_eq!(registry.get(BlockId(99)).name, "minecraft:air");
+        assert!(!registry.is_solid(BlockId(99)));
+    }
+
+    #[test]
+    fn vanilla_blocks_have_their_properties() {
+        let registry = BlockRegistry::vanilla();
+        let id = |name| registry.id(name).unwrap();
+        assert!(registry.is_fluid(id("minecraft:water")) && !registry.is_solid(id("minecraft:water")));
+        assert!(registry.has_gravity(id("minecraft:sand")));
+        assert!(registry.is_transparent(id("minecraft:oak_leaves")));
+        assert!(registry.properties(id("minecraft:bedrock")).hardness < 0.0);
+        assert!(!registry.is_transparent(id("minecraft:stone")));
+    }
+
+    #[test]
+    fn every_combination_gets_a_state() {
+        let mut registry = BlockRegistry::new();
+        let stone = registry.register("minecraft:stone", BlockProperties::solid(1.5));
+        let slab = registry.register_states(
+            "minecraft:stone_slab",
+            BlockProperties::solid(2.0),
+            vec![
+                StateProperty::new("waterlogged", &["true", "false"], "false"),
+                StateProperty::new("type", &["top", "bottom", "double"], "bottom"),
+            ],
+        );
+        let dirt = registry.register("minecraft:dirt", BlockProperties::solid(0.5));
+
+        let slab_type = registry.get(slab);
+        assert_eq!(slab_type.state_count(), 6);
+        assert_eq!(registry.id("minecraft:dirt"), Some(BlockId(stone.0 + 7)));
+        assert_eq!(registry.get(dirt).name, "minecraft:dirt");
+        // Propriedades em ordem de nome, cada estado no mesmo tipo
+        assert_eq!(registry.properties_of(slab), vec![("type", "bottom"), ("waterlogged", "false")]);
+        assert!(slab_type.state_ids().all(|id| registry.get(id).name == "minecraft:stone_slab"));
+
+        let top = registry.with_property(slab, "type", "top").unwrap();
+        assert_ne!(top, slab);
+        assert_eq!(registry.property(top, "type"), Some("top"));
+        assert_eq!(registry.property(top, "waterlogged"), Some("false"));
+        assert_eq!(registry.state("minecraft:stone_slab", &[("type", "top"), ("color", "red")]), Some(top));
+        assert_eq!(registry.with_property(slab, "type", "triple"), None);
+        assert_eq!(registry.with_property(stone, "type", "top"), None);
+    }
+
+    #[test]
+    fn vanilla_logs_and_water_have_states() {
+        let registry = BlockRegistry::vanilla();
+        let log = registry.id("minecraft:oak_log").unwrap();
+        assert_eq!(registry.property(log, "axis"), Some("y"));
+        let water = registry.state("minecraft:water", &[("level", "7")]).unwrap();
+        assert!(registry.is_fluid(water));
+        assert_eq!(registry.get(water).state_count(), 16);
+    }
+}
@@ -0,0 +1,98 @@
+use std::f32::consts::SQRT_2;
+use glam::Vec3;
+use super::world::chunk::{CHUNK_SIZE, WORLD_HEIGHT};
+
+// Cor da água das planícies no vanilla (0x050533), escurecida à noite como o céu
+const WATER_FOG_COLOR: Vec3 = Vec3::new(5.0 / 255.0, 5.0 / 255.0, 51.0 / 255.0);
+const WATER_FOG_DENSITY: f32 = 0.08;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FogMode {
+    Linear,      // Some entre `start` e `end`
+    Exponential, // Cai com exp(-densidade × distância)
+}
+
+impl FogMode {
+    // Valor do uniform `fogMode` do shader dos blocos
+    pub fn id(self) -> i32 {
+        match self {
+            FogMode::Linear => 0,
+            FogMode::Exponential => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fog {
+    pub mode: FogMode,
+    pub start: f32,
+    pub end: f32,
+    pub density: f32,
+    pub color: Vec3,
+}
+
+impl Fog {
+    // Neblina do terreno: termina no limite dos chunks carregados para eles não surgirem do nada.
+    // Começa antes, como no vanilla (um décimo da distância, entre 4 e 64 blocos)
+    pub fn terrain(render_distance: i32, color: Vec3) -> Self {
+        let end = (render_distance * CHUNK_SIZE) as f32;
+        let start = end - (end / 10.0).clamp(4.0, 64.0);
+        Fog { mode: FogMode::Linear, start, end, density: 0.0, color }
+    }
+
+    // Com a câmera dentro d'água, bem mais densa e da cor da água
+    pub fn underwater(daylight: f32) -> Self {
+        Fog { mode: FogMode::Exponential, start: 0.0, end: 0.0, density: WATER_FOG_DENSITY, color: WATER_FOG_COLOR * daylight }
+    }
+
+    // Quanto da cor original sobra a `distance` blocos da câmera (1 sem neblina, 0 só neblina);
+    // a mesma conta do shader
+    #[cfg(test)]
+    pub fn visibility(&self, distance: f32) -> f32 {
+        match self.mode {
+            FogMode::Linear => ((self.end - distance) / (self.end - self.start)).clamp(0.0, 1.0),
+            FogMode::Exponential => (-self.density * distance).exp(),
+        }
+    }
+}
+
+// Plano de corte da projeção: alcança a quina mais distante dos chunks carregados, em qualquer altura
+pub fn far_plane(render_distance: i32) -> f32 {
+    let horizontal = ((render_distance + 1) * CHUNK_SIZE) as f32 * SQRT_2;
+    (horizontal * horizontal + (WORLD_HEIGHT * WORLD_HEIGHT) as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terrain_fog_ends_at_the_render_distance() {
+        let fog = Fog::terrain(8, Vec3::ONE);
+        assert_eq!((fog.start, fog.end), (115.2, 128.0));
+        assert_eq!(fog.visibility(100.0), 1.0);
+        assert_eq!(fog.visibility(128.0), 0.0);
+        assert!((fog.visibility(121.6) - 0.5).abs() < 1e-4);
+        // Distâncias curtas ainda deixam uns blocos de transição
+        let near = Fog::terrain(2, Vec3::ONE);
+        assert_eq!((near.start, near.end), (28.0, 32.0));
+    }
+
+    #[test]
+    fn underwater_fog_is_dense() {
+        let fog = Fog::underwater(1.0);
+        assert_eq!(fog.mode, FogMode::Exponential);
+        assert!(fog.visibility(2.0) > 0.8);
+        assert!(fog.visibility(30.0) < 0.1);
+        assert_eq!(Fog::underwater(0.5).color, WATER_FOG_COLOR * 0.5);
+    }
+
+    #[test]
+    fn far_plane_reaches_the_farthest_loaded_corner() {
+        for distance in [2, 8, 32] {
+            let corner = ((distance + 1) * CHUNK_SIZE) as f32 * SQRT_2;
+            assert!(far_plane(distance) >= corner);
+            assert!(far_plane(distance) > Fog::terrain(distance, Vec3::ZERO).end);
+        }
+    }
+}
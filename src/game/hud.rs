@@ -0,0 +1,109 @@
+use super::inventory::HOTBAR_SIZE;
+
+pub const GUI_SCALE: f32 = 2.0; // Pixels da tela por pixel das texturas da GUI
+
+// Medidas em pixels da GUI, como no vanilla: hotbar de 182x22 com slots a cada 20, seleção de 24x23
+// e ícones de 16 a 3 pixels da borda
+const HOTBAR: (f32, f32) = (182.0, 22.0);
+const SELECTION: (f32, f32) = (24.0, 23.0);
+const SLOT_STRIDE: f32 = 20.0;
+const ICON_SIZE: f32 = 16.0;
+const ICON_MARGIN: f32 = 3.0;
+const HEART_SIZE: f32 = 9.0;
+const HEART_STRIDE: f32 = 8.0; // Corações se sobrepõem em um pixel
+const HEARTS_Y: f32 = 30.0;    // Base dos corações, acima da hotbar
+
+// Retângulo em coordenadas normalizadas da tela (-1 a 1): canto inferior esquerdo e tamanho
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heart {
+    Empty,
+    Half,
+    Full,
+}
+
+pub struct HotbarLayout {
+    pub background: Rect,
+    pub selection: Rect,
+    pub icons: [Rect; HOTBAR_SIZE],
+}
+
+// Converte pixels da GUI, contados da base da tela e do centro em x, para uma tela de `screen` pixels
+fn gui_rect(screen: (f32, f32), x: f32, y: f32, width: f32, height: f32) -> Rect {
+    Rect {
+        x: x * GUI_SCALE * 2.0 / screen.0,
+        y: -1.0 + y * GUI_SCALE * 2.0 / screen.1,
+        width: width * GUI_SCALE * 2.0 / screen.0,
+        height: height * GUI_SCALE * 2.0 / screen.1,
+    }
+}
+
+// Hotbar centralizada na base da tela
+pub fn hotbar_layout(screen: (f32, f32), selected: usize) -> HotbarLayout {
+    let rect = |x, y, width, height| gui_rect(screen, x, y, width, height);
+    let left = -HOTBAR.0 / 2.0;
+    HotbarLayout {
+        background: rect(left, 0.0, HOTBAR.0, HOTBAR.1),
+        selection: rect(left - 1.0 + selected as f32 * SLOT_STRIDE, 0.0, SELECTION.0, SELECTION.1),
+        icons: std::array::from_fn(|slot| {
+            rect(left + ICON_MARGIN + slot as f32 * SLOT_STRIDE, ICON_MARGIN, ICON_SIZE, ICON_SIZE)
+        }),
+    }
+}
+
+// Dez corações alinhados à esquerda da hotbar, cada um valendo 2 de vida
+pub fn hearts(screen: (f32, f32), health: f32) -> [(Rect, Heart); 10] {
+    let half_hearts = health.ceil().max(0.0) as usize;
+    std::array::from_fn(|index| {
+        let rect = gui_rect(screen, -HOTBAR.0 / 2.0 + index as f32 * HEART_STRIDE, HEARTS_Y, HEART_SIZE, HEART_SIZE);
+        let heart = match half_hearts.saturating_sub(index * 2) {
+            0 => Heart::Empty,
+            1 => Heart::Half,
+            _ => Heart::Full,
+        };
+        (rect, heart)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn hotbar_sits_centred_on_the_bottom_edge() {
+        let layout = hotbar_layout((800.0, 600.0), 8);
+        let background = layout.background;
+        assert!(close(background.x, -0.455) && close(background.width, 0.91));
+        assert!(close(background.y, -1.0) && close(background.height, 44.0 / 300.0));
+
+        // A seleção passa um pixel para cada lado do slot
+        assert!(close(layout.selection.x, (-91.0 - 1.0 + 160.0) * 2.0 / 400.0));
+        assert!(close(layout.selection.y, -1.0));
+
+        let first = layout.icons[0];
+        assert!(close(first.x, -0.455 + 6.0 / 400.0) && close(first.y, -1.0 + 6.0 / 300.0));
+        assert!(close(layout.icons[1].x - first.x, 40.0 / 400.0));
+        assert!(close(layout.icons[8].x + layout.icons[8].width, background.x + background.width - 6.0 / 400.0));
+    }
+
+    #[test]
+    fn hearts_show_half_points() {
+        let hearts = hearts((800.0, 600.0), 13.0);
+        let kinds: Vec<_> = hearts.iter().map(|&(_, heart)| heart).collect();
+        assert_eq!(&kinds[5..8], [Heart::Full, Heart::Half, Heart::Empty]);
+        assert!(kinds[..6].iter().all(|&heart| heart == Heart::Full));
+        assert!(close(hearts[0].0.x, -0.455) && close(hearts[1].0.x - hearts[0].0.x, 16.0 / 400.0));
+        assert!(close(hearts[0].0.y, -1.0 + 60.0 / 300.0));
+    }
+}
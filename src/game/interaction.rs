@@ -0,0 +1,141 @@
+use glam::Vec3;
+use super::Player;
+use super::world::raycast::RayHit;
+
+pub const DESTROY_STAGES: u8 = 10;  // Texturas destroy_stage_0 a destroy_stage_9
+const BREAK_COOLDOWN: f32 = 0.3;    // Pausa entre blocos quebrados segurando o botão, como no vanilla
+
+// Tempo para quebrar com a mão, em segundos (1.5 × dureza, como no vanilla sem ferramenta);
+// None para blocos indestrutíveis
+pub fn break_time(hardness: f32) -> Option<f32> {
+    (hardness >= 0.0).then_some(hardness * 1.5)
+}
+
+// Progresso da quebra do bloco mirado enquanto o botão esquerdo está pressionado
+#[derive(Debug, Default)]
+pub struct Mining {
+    block: Option<(i32, i32, i32)>,
+    elapsed: f32,
+    duration: f32,
+    cooldown: f32,
+}
+
+impl Mining {
+    // Avança a quebra de `target` (posição e dureza). Soltar o botão ou mudar de alvo recomeça do zero.
+    // Retorna o bloco que terminou de quebrar neste quadro
+    pub fn update(&mut self, target: Option<((i32, i32, i32), f32)>, held: bool, delta_time: f32) -> Option<(i32, i32, i32)> {
+        self.cooldown = (self.cooldown - delta_time).max(0.0);
+        let Some((block, duration)) = target
+            .filter(|_| held)
+            .and_then(|(block, hardness)| Some((block, break_time(hardness)?)))
+        else {
+            self.block = None;
+            return None;
+        };
+        if self.block != Some(block) {
+            *self = Mining { block: Some(block), elapsed: 0.0, duration, cooldown: self.cooldown };
+        }
+        if self.cooldown > 0.0 {
+            return None;
+        }
+
+        self.elapsed += delta_time;
+        if self.elapsed < self.duration {
+            return None;
+        }
+        *self = Mining { cooldown: BREAK_COOLDOWN, ..Mining::default() };
+        Some(block)
+    }
+
+    // Bloco sendo quebrado e o estágio da rachadura, de 0 a DESTROY_STAGES - 1
+    pub fn stage(&self) -> Option<((i32, i32, i32), u8)> {
+        let block = self.block?;
+        if self.elapsed <= 0.0 {
+            return None;
+        }
+        let progress = self.elapsed / self.duration;
+        Some((block, ((progress * DESTROY_STAGES as f32) as u8).min(DESTROY_STAGES - 1)))
+    }
+}
+
+// Onde um bloco colocado contra a face mirada vai parar; mirar de dentro de um bloco não tem face
+pub fn placement(hit: &RayHit) -> Option<(i32, i32, i32)> {
+    let (x, y, z) = hit.block;
+    let (nx, ny, nz) = hit.normal;
+    (hit.normal != (0, 0, 0)).then_some((x + nx, y + ny, z + nz))
+}
+
+// Um bloco sólido na posição prenderia o jogador? Usa as mesmas caixas da colisão
+pub fn blocks_player(player: &Player, (x, y, z): (i32, i32, i32)) -> bool {
+    let half_size = player.size * 0.5;
+    let block_min = Vec3::new(x as f32, y as f32, z as f32);
+    Player::aabb_intersects(player.position - half_size, player.position + half_size, block_min, block_min + Vec3::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STONE: ((i32, i32, i32), f32) = ((1, 2, 3), 1.5);
+
+    #[test]
+    fn breaking_takes_hardness_based_time() {
+        assert_eq!(break_time(1.5), Some(2.25));
+        assert_eq!(break_time(0.0), Some(0.0));
+        assert_eq!(break_time(-1.0), None);
+
+        let mut mining = Mining::default();
+        assert_eq!(mining.update(Some(STONE), true, 1.0), None);
+        assert_eq!(mining.stage(), Some(((1, 2, 3), 4)));
+        assert_eq!(mining.update(Some(STONE), true, 1.0), None);
+        assert_eq!(mining.stage(), Some(((1, 2, 3), 8)));
+        assert_eq!(mining.update(Some(STONE), true, 0.5), Some((1, 2, 3)));
+        assert_eq!(mining.stage(), None);
+
+        // Indestrutível nunca quebra nem racha
+        let bedrock = ((0, 0, 0), -1.0);
+        assert_eq!(mining.update(Some(bedrock), true, 100.0), None);
+        assert_eq!(mining.stage(), None);
+    }
+
+    #[test]
+    fn releasing_or_changing_target_restarts() {
+        let mut mining = Mining::default();
+        mining.update(Some(STONE), true, 2.0);
+        mining.update(Some(STONE), false, 0.1);
+        assert_eq!(mining.stage(), None);
+        assert_eq!(mining.update(Some(STONE), true, 2.0), None);
+
+        assert_eq!(mining.update(Some(((1, 3, 3), 1.5)), true, 0.5), None);
+        assert_eq!(mining.stage(), Some(((1, 3, 3), 2)));
+    }
+
+    #[test]
+    fn instant_blocks_wait_for_the_cooldown() {
+        let grass = ((0, 1, 0), 0.0);
+        let mut mining = Mining::default();
+        assert_eq!(mining.update(Some(grass), true, 0.016), Some((0, 1, 0)));
+        let next = ((0, 2, 0), 0.0);
+        assert_eq!(mining.update(Some(next), true, 0.1), None);
+        assert_eq!(mining.update(Some(next), true, 0.25), Some((0, 2, 0)));
+    }
+
+    #[test]
+    fn placement_goes_against_the_hit_face() {
+        let hit = |normal| RayHit { block: (4, 5, 6), normal, point: Vec3::ZERO, distance: 1.0 };
+        assert_eq!(placement(&hit((0, 1, 0))), Some((4, 6, 6)));
+        assert_eq!(placement(&hit((-1, 0, 0))), Some((3, 5, 6)));
+        assert_eq!(placement(&hit((0, 0, 0))), None);
+    }
+
+    #[test]
+    fn blocks_inside_the_player_are_refused() {
+        let mut player = Player::new();
+        player.position = Vec3::new(0.5, 10.9, 0.5); // AABB de (0.2, 10.0, 0.2) a (0.8, 11.8, 0.8)
+        assert!(blocks_player(&player, (0, 10, 0)));
+        assert!(blocks_player(&player, (0, 11, 0)));
+        assert!(!blocks_player(&player, (0, 9, 0)));
+        assert!(!blocks_player(&player, (1, 10, 0)));
+        assert!(!blocks_player(&player, (0, 12, 0)));
+    }
+}
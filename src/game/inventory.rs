@@ -0,0 +1,273 @@
+use crate::nbt::Tag;
+
+pub const INVENTORY_SIZE: usize = 36; // Slots 0 a 8 são a hotbar, como no vanilla
+pub const HOTBAR_SIZE: usize = 9;
+pub const ARMOR_SLOTS: usize = 4;     // Pés, pernas, peito e cabeça
+
+// Números de slot na lista "Inventory" do level.dat
+const ARMOR_SLOT_BASE: i64 = 100;
+const OFFHAND_SLOT: i64 = -106;
+
+// Itens que não empilham (ferramentas, armaduras, baldes cheios) ou só até 16, como no vanilla;
+// o resto vai até 64
+const UNSTACKABLE_SUFFIXES: [&str; 11] = [
+    "_sword", "_pickaxe", "_axe", "_shovel", "_hoe", "_helmet", "_chestplate", "_leggings", "_boots", "_bucket", "_bed",
+];
+const STACKS_OF_16: [&str; 9] = [
+    "minecraft:bucket",
+    "minecraft:egg",
+    "minecraft:ender_pearl",
+    "minecraft:snowball",
+    "minecraft:armor_stand",
+    "minecraft:honey_bottle",
+    "minecraft:oak_sign",
+    "minecraft:spruce_sign",
+    "minecraft:birch_sign",
+];
+
+pub fn max_stack_size(item: &str) -> u8 {
+    if UNSTACKABLE_SUFFIXES.iter().any(|suffix| item.ends_with(suffix)) {
+        1
+    } else if STACKS_OF_16.contains(&item) {
+        16
+    } else {
+        64
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemStack {
+    pub item: String,     // Id do item, ex.: "minecraft:stone"; blocos usam o nome do tipo
+    pub count: u8,
+    pub nbt: Option<Tag>, // Dados extras (nome, encantamentos); só empilha com NBT igual
+}
+
+impl ItemStack {
+    pub fn new(item: &str, count: u8) -> Self {
+        Self { item: item.to_string(), count, nbt: None }
+    }
+
+    pub fn max_stack_size(&self) -> u8 {
+        max_stack_size(&self.item)
+    }
+
+    pub fn stacks_with(&self, other: &ItemStack) -> bool {
+        self.item == other.item && self.nbt == other.nbt
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inventory {
+    pub slots: [Option<ItemStack>; INVENTORY_SIZE],
+    pub armor: [Option<ItemStack>; ARMOR_SLOTS],
+    pub offhand: Option<ItemStack>,
+    selected: usize, // Slot da hotbar na mão
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            slots: std::array::from_fn(|_| None),
+            armor: std::array::from_fn(|_| None),
+            offhand: None,
+            selected: 0,
+        }
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    // Teclas 1 a 9; índices fora da hotbar são ignorados
+    pub fn select(&mut self, slot: usize) {
+        if slot < HOTBAR_SIZE {
+            self.selected = slot;
+        }
+    }
+
+    // Rolar para baixo (passos positivos) anda para a direita, dando a volta nas pontas
+    pub fn scroll(&mut self, steps: i32) {
+        self.selected = (self.selected as i32 + steps).rem_euclid(HOTBAR_SIZE as i32) as usize;
+    }
+
+    pub fn selected_stack(&self) -> Option<&ItemStack> {
+        self.slots[self.selected].as_ref()
+    }
+
+    // Completa primeiro as pilhas do mesmo item, depois ocupa slots vazios, sempre na ordem dos
+    // slots (hotbar antes). Retorna o que não coube
+    pub fn add(&mut self, mut stack: ItemStack) -> Option<ItemStack> {
+        let max = stack.max_stack_size();
+        for slot in self.slots.iter_mut().flatten() {
+            if stack.count == 0 {
+                break;
+            }
+            if slot.stacks_with(&stack) && slot.count < max {
+                let moved = stack.count.min(max - slot.count);
+                slot.count += moved;
+                stack.count -= moved;
+            }
+        }
+        for slot in self.slots.iter_mut().filter(|slot| slot.is_none()) {
+            if stack.count == 0 {
+                break;
+            }
+            let moved = stack.count.min(max);
+            *slot = Some(ItemStack { count: moved, ..stack.clone() });
+            stack.count -= moved;
+        }
+        (stack.count > 0).then_some(stack)
+    }
+
+    // Tira até `count` itens da pilha na mão; o slot esvazia quando acaba
+    pub fn take_selected(&mut self, count: u8) -> Option<ItemStack> {
+        let slot = &mut self.slots[self.selected];
+        let stack = slot.as_mut()?;
+        let taken = count.min(stack.count);
+        stack.count -= taken;
+        let result = ItemStack { count: taken, ..stack.clone() };
+        if stack.count == 0 {
+            *slot = None;
+        }
+        (taken > 0).then_some(result)
+    }
+
+    // Lista "Inventory" do jogador no level.dat: só os slots ocupados, cada um com "Slot"
+    pub fn to_nbt(&self) -> Tag {
+        let main = self.slots.iter().enumerate().map(|(slot, stack)| (slot as i64, stack));
+        let armor = self.armor.iter().enumerate().map(|(slot, stack)| (ARMOR_SLOT_BASE + slot as i64, stack));
+        let offhand = std::iter::once((OFFHAND_SLOT, &self.offhand));
+        let stacks = main
+            .chain(armor)
+            .chain(offhand)
+            .filter_map(|(slot, stack)| {
+                let stack = stack.as_ref()?;
+                let mut tag = Tag::compound();
+                tag.insert("Slot", slot as i8)
+                    .insert("id", stack.item.as_str())
+                    .insert("Count", stack.count as i8);
+                if let Some(nbt) = &stack.nbt {
+                    tag.insert("tag", nbt.clone());
+                }
+                Some(tag)
+            })
+            .collect();
+        Tag::List(stacks)
+    }
+
+    // Entradas inválidas ou em slots desconhecidos são ignoradas
+    pub fn from_nbt(list: &Tag) -> Self {
+        let mut inventory = Self::new();
+        for entry in list.as_list().unwrap_or_default() {
+            let (Some(slot), Some(item), Some(count)) = (
+                entry.get("Slot").and_then(Tag::as_i64),
+                entry.get("id").and_then(Tag::as_str),
+                entry.get("Count").and_then(Tag::as_i64),
+            ) else {
+                continue;
+            };
+            if count <= 0 {
+                continue;
+            }
+            let stack = Some(ItemStack { item: item.to_string(), count: count as u8, nbt: entry.get("tag").cloned() });
+            match slot {
+                OFFHAND_SLOT => inventory.offhand = stack,
+                0..=35 => inventory.slots[slot as usize] = stack,
+                100..=103 => inventory.armor[(slot - ARMOR_SLOT_BASE) as usize] = stack,
+                _ => {}
+            }
+        }
+        inventory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_sizes_follow_the_item() {
+        assert_eq!(max_stack_size("minecraft:stone"), 64);
+        assert_eq!(max_stack_size("minecraft:ender_pearl"), 16);
+        assert_eq!(max_stack_size("minecraft:diamond_pickaxe"), 1);
+        assert_eq!(max_stack_size("minecraft:water_bucket"), 1);
+        assert_eq!(max_stack_size("minecraft:bucket"), 16);
+    }
+
+    #[test]
+    fn adding_fills_existing_stacks_before_empty_slots() {
+        let mut inventory = Inventory::new();
+        inventory.slots[3] = Some(ItemStack::new("minecraft:stone", 60));
+        inventory.slots[0] = Some(ItemStack::new("minecraft:dirt", 1));
+
+        assert_eq!(inventory.add(ItemStack::new("minecraft:stone", 10)), None);
+        assert_eq!(inventory.slots[3].as_ref().map(|s| s.count), Some(64));
+        assert_eq!(inventory.slots[1], Some(ItemStack::new("minecraft:stone", 6)));
+
+        // NBT diferente não empilha
+        let mut named = ItemStack::new("minecraft:stone", 1);
+        named.nbt = Some(Tag::compound());
+        assert_eq!(inventory.add(named.clone()), None);
+        assert_eq!(inventory.slots[2], Some(named));
+
+        // Itens que não empilham ocupam um slot cada
+        assert_eq!(inventory.add(ItemStack::new("minecraft:iron_sword", 2)), None);
+        assert_eq!(inventory.slots[4].as_ref().map(|s| s.count), Some(1));
+        assert_eq!(inventory.slots[5].as_ref().map(|s| s.count), Some(1));
+    }
+
+    #[test]
+    fn full_inventory_returns_the_leftover() {
+        let mut inventory = Inventory::new();
+        for slot in &mut inventory.slots {
+            *slot = Some(ItemStack::new("minecraft:dirt", 63));
+        }
+        let leftover = inventory.add(ItemStack::new("minecraft:dirt", 40));
+        assert_eq!(leftover, Some(ItemStack::new("minecraft:dirt", 4)));
+        assert_eq!(inventory.add(ItemStack::new("minecraft:sand", 1)), Some(ItemStack::new("minecraft:sand", 1)));
+    }
+
+    #[test]
+    fn inventory_round_trips_through_nbt() {
+        let mut inventory = Inventory::new();
+        inventory.slots[0] = Some(ItemStack::new("minecraft:stone", 64));
+        inventory.slots[35] = Some(ItemStack::new("minecraft:dirt", 3));
+        inventory.armor[3] = Some(ItemStack::new("minecraft:iron_helmet", 1));
+        let mut shield = ItemStack::new("minecraft:shield", 1);
+        let mut display = Tag::compound();
+        display.insert("Name", "Escudo");
+        shield.nbt = Some(display);
+        inventory.offhand = Some(shield);
+
+        let tag = inventory.to_nbt();
+        let slots: Vec<_> = tag.as_list().unwrap().iter().filter_map(|entry| entry.get("Slot")?.as_i64()).collect();
+        assert_eq!(slots, vec![0, 35, 103, -106]);
+        // A seleção da hotbar não é salva
+        assert_eq!(Inventory::from_nbt(&tag), inventory);
+        assert_eq!(Inventory::from_nbt(&Tag::compound()), Inventory::new());
+    }
+
+    #[test]
+    fn hotbar_selection_wraps_and_takes_from_the_hand() {
+        let mut inventory = Inventory::new();
+        inventory.scroll(-1);
+        assert_eq!(inventory.selected(), 8);
+        inventory.scroll(2);
+        assert_eq!(inventory.selected(), 1);
+        inventory.select(9);
+        assert_eq!(inventory.selected(), 1);
+
+        inventory.slots[1] = Some(ItemStack::new("minecraft:sand", 2));
+        assert_eq!(inventory.take_selected(1), Some(ItemStack::new("minecraft:sand", 1)));
+        assert_eq!(inventory.selected_stack().map(|s| s.count), Some(1));
+        assert_eq!(inventory.take_selected(5), Some(ItemStack::new("minecraft:sand", 1)));
+        assert_eq!(inventory.selected_stack(), None);
+        assert_eq!(inventory.take_selected(1), None);
+    }
+}
@@ -1,8 +1,18 @@
 use gl::types::*;
-use blocks::Block;
+use world::World;
+use inventory::Inventory;
+use world::level::GameMode;
 use glam::{Vec3, vec3};
 
+pub mod atlas;
 pub mod blocks;
+pub mod fog;
+pub mod hud;
+pub mod interaction;
+pub mod inventory;
+pub mod options;
+pub mod resources;
+pub mod sky;
 pub mod world;
 
 #[derive(Clone)]
@@ -16,9 +26,19 @@ pub struct Player {
     pub front: Vec3,      // Direção da câmera
     pub up: Vec3,         // Vetor "cima"
     pub speed: f32,       // Velocidade base de movimento (blocos/s)
+    pub inventory: Inventory,
+    pub game_mode: GameMode,
+    pub flying: bool,     // Voo do criativo; o espectador sempre voa
+    pub health: f32,      // Em meios corações, de 0 a MAX_HEALTH
+    fall_distance: f32,   // Queda acumulada desde o último apoio, para o dano
+    jump_held: bool,      // Estado do pulo no quadro anterior, para achar o toque
+    jump_tap_timer: f32,  // Janela restante para o segundo toque do pulo
 }
 
 impl Player {
+    pub const REACH: f32 = 4.5; // Alcance para mirar blocos
+    pub const MAX_HEALTH: f32 = 20.0;
+
     pub fn new() -> Self {
         Self {
             position: vec3(16.0, 70.0, 16.0),
@@ -30,21 +50,81 @@ impl Player {
             front: vec3(0.0, 0.0, -1.0),
             up: Vec3::Y,
             speed: 4.317,
+            inventory: Inventory::new(),
+            game_mode: GameMode::Survival,
+            flying: false,
+            health: Self::MAX_HEALTH,
+            fall_distance: 0.0,
+            jump_held: false,
+            jump_tap_timer: 0.0,
         }
     }
 
-    pub fn update(&mut self, blocks: &[Block], delta_time: f32, input: &PlayerInput) {
-        const GRAVITY: f32 = -32.174; // Gravidade do Minecraft
-        const JUMP_SPEED: f32 = 8.0;  // Velocidade de pulo
+    // Troca o modo de jogo; só o criativo mantém o voo que já estava ligado
+    pub fn set_game_mode(&mut self, game_mode: GameMode) {
+        self.game_mode = game_mode;
+        self.flying = match game_mode {
+            GameMode::Creative => self.flying,
+            GameMode::Spectator => true,
+            _ => false,
+        };
+        self.fall_distance = 0.0;
+    }
 
-        // Atualizar direção da câmera (front)
-        let yaw_rad = self.yaw.to_radians();
-        let pitch_rad = self.pitch.to_radians();
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    // Volta ao ponto de spawn com a vida cheia
+    pub fn respawn(&mut self, position: Vec3) {
+        self.position = position;
+        self.velocity = Vec3::ZERO;
+        self.health = Self::MAX_HEALTH;
+        self.fall_distance = 0.0;
+    }
+
+    // Olhos a 80% da altura do AABB
+    pub fn eye_position(&self) -> Vec3 {
+        self.position + vec3(0.0, self.size.y * 0.8, 0.0)
+    }
+
+    // Define yaw/pitch em graus e recalcula a direção da câmera
+    pub fn set_rotation(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = yaw;
+        self.pitch = pitch;
+        let yaw_rad = yaw.to_radians();
+        let pitch_rad = pitch.to_radians();
         self.front = vec3(
             yaw_rad.cos() * pitch_rad.cos(),
             pitch_rad.sin(),
             yaw_rad.sin() * pitch_rad.cos(),
         ).normalize();
+    }
+
+    pub fn update(&mut self, world: &World, delta_time: f32, input: &PlayerInput) {
+        const GRAVITY: f32 = -32.174; // Gravidade do Minecraft
+        const JUMP_SPEED: f32 = 8.0;  // Velocidade de pulo
+        const FLY_SPEED: f32 = 10.92; // Voo do criativo, como no vanilla
+        const FLY_VERTICAL_SPEED: f32 = 7.5;
+        const DOUBLE_TAP_WINDOW: f32 = 0.35; // 7 ticks
+        const SAFE_FALL: f32 = 3.0;   // Quedas até 3 blocos não machucam
+
+        // Atualizar direção da câmera (front)
+        self.set_rotation(self.yaw, self.pitch);
+
+        // Dois toques rápidos no pulo ligam ou desligam o voo do criativo
+        let jump_tapped = input.jump && !self.jump_held;
+        self.jump_held = input.jump;
+        self.jump_tap_timer = (self.jump_tap_timer - delta_time).max(0.0);
+        if jump_tapped && self.game_mode == GameMode::Creative {
+            if self.jump_tap_timer > 0.0 {
+                self.flying = !self.flying;
+                self.velocity.y = 0.0;
+                self.jump_tap_timer = 0.0;
+            } else {
+                self.jump_tap_timer = DOUBLE_TAP_WINDOW;
+            }
+        }
 
         // Resetar velocidade horizontal
         self.velocity.x = 0.0;
@@ -63,17 +143,23 @@ impl Player {
         }
 
         // Aplicar velocidade horizontal
-        self.velocity.x = move_dir.x * self.speed;
-        self.velocity.z = move_dir.z * self.speed;
+        let speed = if self.flying { FLY_SPEED } else { self.speed };
+        self.velocity.x = move_dir.x * speed;
+        self.velocity.z = move_dir.z * speed;
 
-        // Aplicar pulo
-        if input.jump && self.on_ground {
-            self.velocity.y = JUMP_SPEED;
-            self.on_ground = false;
-        }
+        if self.flying {
+            // Sem gravidade: pulo sobe, agachar desce
+            self.velocity.y = (input.jump as i32 - input.sneak as i32) as f32 * FLY_VERTICAL_SPEED;
+        } else {
+            // Aplicar pulo
+            if input.jump && self.on_ground {
+                self.velocity.y = JUMP_SPEED;
+                self.on_ground = false;
+            }
 
-        // Aplicar gravidade
-        self.velocity.y += GRAVITY * delta_time;
+            // Aplicar gravidade
+            self.velocity.y += GRAVITY * delta_time;
+        }
 
         // Atualizar posição
         let mut new_position = self.position;
@@ -81,13 +167,33 @@ impl Player {
         new_position.y += self.velocity.y * delta_time;
         new_position.z += self.velocity.z * delta_time;
 
-        // Resolver colisões
-        self.handle_collisions(blocks, &mut new_position);
+        // Resolver colisões; o espectador atravessa os blocos. Pousou se a colisão parou a descida
+        let descending = self.velocity.y < 0.0;
+        if self.game_mode != GameMode::Spectator {
+            self.handle_collisions(world, &mut new_position);
+        }
+        let landed = descending && self.velocity.y == 0.0;
 
         // Atualizar estado
-        self.on_ground = self.is_on_ground(blocks);
+        let fallen = self.position.y - new_position.y;
+        self.on_ground = self.game_mode != GameMode::Spectator && self.is_on_ground(world);
         self.position = new_position;
 
+        // Dano de queda ao pousar; voar zera a queda
+        if self.flying || !self.game_mode.is_survival_like() {
+            self.fall_distance = 0.0;
+        } else if landed {
+            self.health = (self.health - (self.fall_distance + fallen - SAFE_FALL).ceil().max(0.0)).max(0.0);
+            self.fall_distance = 0.0;
+        } else if fallen > 0.0 {
+            self.fall_distance += fallen;
+        }
+
+        // Pousar encerra o voo do criativo, como no vanilla
+        if landed && self.game_mode == GameMode::Creative {
+            self.flying = false;
+        }
+
         // Debug
         println!("Input: {:?}", input);
         println!("Front: {:?}", self.front);
@@ -95,18 +201,17 @@ impl Player {
         println!("Position: {:?}", self.position);
     }
 
-    fn handle_collisions(&mut self, blocks: &[Block], new_pos: &mut Vec3) {
+    fn handle_collisions(&mut self, world: &World, new_pos: &mut Vec3) {
         let half_size = self.size * 0.5;
         let old_pos = self.position;
 
         // Resolver colisão por eixo, ajustando a posição diretamente
         // X
         if self.velocity.x != 0.0 {
-            let mut aabb_min = vec3(new_pos.x - half_size.x, old_pos.y - half_size.y, old_pos.z - half_size.z);
-            let mut aabb_max = vec3(new_pos.x + half_size.x, old_pos.y + half_size.y, old_pos.z + half_size.z);
+            let aabb_min = vec3(new_pos.x - half_size.x, old_pos.y - half_size.y, old_pos.z - half_size.z);
+            let aabb_max = vec3(new_pos.x + half_size.x, old_pos.y + half_size.y, old_pos.z + half_size.z);
 
-            for block in blocks.iter().filter(|b| b.id != "minecraft:water") {
-                let block_min = vec3(block.x as f32, block.y as f32, block.z as f32);
+            for block_min in Self::solid_blocks_in(world, aabb_min, aabb_max) {
                 let block_max = block_min + Vec3::ONE;
 
                 if Self::aabb_intersects(aabb_min, aabb_max, block_min, block_max) {
@@ -123,11 +228,10 @@ impl Player {
 
         // Y
         if self.velocity.y != 0.0 {
-            let mut aabb_min = vec3(new_pos.x - half_size.x, new_pos.y - half_size.y, old_pos.z - half_size.z);
-            let mut aabb_max = vec3(new_pos.x + half_size.x, new_pos.y + half_size.y, old_pos.z + half_size.z);
+            let aabb_min = vec3(new_pos.x - half_size.x, new_pos.y - half_size.y, old_pos.z - half_size.z);
+            let aabb_max = vec3(new_pos.x + half_size.x, new_pos.y + half_size.y, old_pos.z + half_size.z);
 
-            for block in blocks.iter().filter(|b| b.id != "minecraft:water") {
-                let block_min = vec3(block.x as f32, block.y as f32, block.z as f32);
+            for block_min in Self::solid_blocks_in(world, aabb_min, aabb_max) {
                 let block_max = block_min + Vec3::ONE;
 
                 if Self::aabb_intersects(aabb_min, aabb_max, block_min, block_max) {
@@ -145,11 +249,10 @@ impl Player {
 
         // Z
         if self.velocity.z != 0.0 {
-            let mut aabb_min = vec3(new_pos.x - half_size.x, new_pos.y - half_size.y, new_pos.z - half_size.z);
-            let mut aabb_max = vec3(new_pos.x + half_size.x, new_pos.y + half_size.y, new_pos.z + half_size.z);
+            let aabb_min = vec3(new_pos.x - half_size.x, new_pos.y - half_size.y, new_pos.z - half_size.z);
+            let aabb_max = vec3(new_pos.x + half_size.x, new_pos.y + half_size.y, new_pos.z + half_size.z);
 
-            for block in blocks.iter().filter(|b| b.id != "minecraft:water") {
-                let block_min = vec3(block.x as f32, block.y as f32, block.z as f32);
+            for block_min in Self::solid_blocks_in(world, aabb_min, aabb_max) {
                 let block_max = block_min + Vec3::ONE;
 
                 if Self::aabb_intersects(aabb_min, aabb_max, block_min, block_max) {
@@ -171,19 +274,30 @@ impl Player {
         min1.z < max2.z && max1.z > min2.z
     }
 
-    fn is_on_ground(&self, blocks: &[Block]) -> bool {
+    // Cantos mínimos dos blocos sólidos que tocam o AABB (consulta O(1) por célula)
+    fn solid_blocks_in(world: &World, min: Vec3, max: Vec3) -> impl Iterator<Item = Vec3> + '_ {
+        let (x0, y0, z0) = (min.x.floor() as i32, min.y.floor() as i32, min.z.floor() as i32);
+        let (x1, y1, z1) = (max.x.floor() as i32, max.y.floor() as i32, max.z.floor() as i32);
+
+        (x0..=x1).flat_map(move |x| {
+            (y0..=y1).flat_map(move |y| {
+                (z0..=z1)
+                    .filter(move |&z| world.is_solid_block(x, y, z))
+                    .map(move |z| vec3(x as f32, y as f32, z as f32))
+            })
+        })
+    }
+
+    fn is_on_ground(&self, world: &World) -> bool {
         let half_size = self.size * 0.5;
         let feet_pos = self.position - vec3(0.0, half_size.y + 0.001, 0.0);
         let player_min = feet_pos - half_size;
         let player_max = feet_pos + half_size;
 
-        blocks.iter()
-            .filter(|b| b.id != "minecraft:water")
-            .any(|b| {
-                let block_min = vec3(b.x as f32, b.y as f32, b.z as f32);
-                let block_max = block_min + Vec3::ONE;
-                Self::aabb_intersects(player_min, player_max, block_min, block_max)
-            })
+        Self::solid_blocks_in(world, player_min, player_max).any(|block_min| {
+            let block_max = block_min + Vec3::ONE;
+            Self::aabb_intersects(player_min, player_max, block_min, block_max)
+        })
     }
 }
 
@@ -194,12 +308,12 @@ pub struct PlayerInput {
     pub left: bool,
     pub right: bool,
     pub jump: bool,
+    pub sneak: bool,
 }
 
-// ... (load_texture permanece igual)
-
+// Envia uma imagem já invertida (ver `ResourceManager::image`); sem imagem, usa um pixel magenta
 #[inline]
-pub fn load_texture(path: &str) -> GLuint {
+pub fn load_texture(image: Option<&image::RgbaImage>) -> GLuint {
     let mut texture_id = 0;
     unsafe {
         gl::GenTextures(1, &mut texture_id);
@@ -210,8 +324,7 @@ pub fn load_texture(path: &str) -> GLuint {
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
 
-        if let Ok(img) = image::open(path) {
-            let data = img.flipv().to_rgba8();
+        if let Some(data) = image {
             gl::TexImage2D(
                 gl::TEXTURE_2D,
                 0,
@@ -239,4 +352,87 @@ pub fn load_texture(path: &str) -> GLuint {
         }
     }
     texture_id
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use world::chunk::Chunk;
+    use blocks::registry;
+
+    // Chão de pedra em y = 60; de pé, o centro do jogador fica em 61.9
+    fn flat_world() -> World {
+        let stone = registry().id("minecraft:stone").unwrap();
+        let mut chunk = Chunk::new(0, 0);
+        for x in 0..16 {
+            for z in 0..16 {
+                chunk.set_block(x, 60, z, stone);
+            }
+        }
+        let mut world = World::new();
+        world.insert_chunk(chunk);
+        world
+    }
+
+    fn player_at(y: f32, game_mode: GameMode) -> Player {
+        let mut player = Player { position: vec3(8.5, y, 8.5), ..Player::new() };
+        player.set_game_mode(game_mode);
+        player
+    }
+
+    fn run(player: &mut Player, world: &World, input: &PlayerInput, frames: usize) {
+        for _ in 0..frames {
+            player.update(world, 0.05, input);
+        }
+    }
+
+    #[test]
+    fn falls_hurt_only_in_survival() {
+        let world = flat_world();
+        let mut player = player_at(71.9, GameMode::Survival);
+        run(&mut player, &world, &PlayerInput::default(), 40);
+        assert!((player.position.y - 61.9).abs() < 0.01);
+        assert_eq!(player.health, 13.0); // 10 blocos: 7 de dano
+
+        let mut player = player_at(71.9, GameMode::Creative);
+        run(&mut player, &world, &PlayerInput::default(), 40);
+        assert_eq!(player.health, Player::MAX_HEALTH);
+        assert!(!player.is_dead());
+    }
+
+    #[test]
+    fn double_jump_tap_toggles_creative_flight() {
+        let world = flat_world();
+        let mut player = player_at(61.901, GameMode::Creative);
+        let jump = PlayerInput { jump: true, ..PlayerInput::default() };
+        run(&mut player, &world, &jump, 1);
+        run(&mut player, &world, &PlayerInput::default(), 1);
+        run(&mut player, &world, &jump, 1);
+        assert!(player.flying);
+
+        // Parado no ar, sem gravidade
+        let height = player.position.y;
+        run(&mut player, &world, &PlayerInput::default(), 10);
+        assert_eq!(player.position.y, height);
+
+        // Descer até o chão encerra o voo
+        run(&mut player, &world, &PlayerInput { sneak: true, ..PlayerInput::default() }, 20);
+        assert!(!player.flying);
+
+        // No sobrevivência o toque duplo não faz nada
+        let mut player = player_at(61.901, GameMode::Survival);
+        run(&mut player, &world, &jump, 1);
+        run(&mut player, &world, &PlayerInput::default(), 1);
+        run(&mut player, &world, &jump, 1);
+        assert!(!player.flying);
+    }
+
+    #[test]
+    fn spectators_fly_through_blocks() {
+        let world = flat_world();
+        let mut player = player_at(61.901, GameMode::Spectator);
+        assert!(player.flying);
+        run(&mut player, &world, &PlayerInput { sneak: true, ..PlayerInput::default() }, 10);
+        assert!(player.position.y < 59.0);
+        assert!(!player.on_ground);
+    }
+}
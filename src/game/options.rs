@@ -0,0 +1,107 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use super::world::streaming::DEFAULT_RENDER_DISTANCE;
+
+pub const MIN_RENDER_DISTANCE: i32 = 2;
+pub const MAX_RENDER_DISTANCE: i32 = 32;
+// Distâncias que F8 percorre
+pub const RENDER_DISTANCE_STEPS: [i32; 5] = [2, 4, 8, 12, 16];
+
+// Configurações do jogador em options.txt, no formato `chave:valor` do vanilla. Chaves que não
+// usamos são mantidas para não apagar as do Minecraft ao salvar
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub render_distance: i32, // Em chunks; controla o carregamento, a neblina e o plano de corte
+    other: Vec<(String, String)>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { render_distance: DEFAULT_RENDER_DISTANCE, other: Vec::new() }
+    }
+}
+
+impl Options {
+    // Arquivo ausente usa os padrões; linhas inválidas são ignoradas
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut options = Self::default();
+        for (key, value) in text.lines().filter_map(|line| line.split_once(':')) {
+            match key.trim() {
+                "renderDistance" => {
+                    if let Ok(distance) = value.trim().parse::<i32>() {
+                        options.render_distance = distance.clamp(MIN_RENDER_DISTANCE, MAX_RENDER_DISTANCE);
+                    }
+                }
+                key => options.other.push((key.to_string(), value.to_string())),
+            }
+        }
+        options
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("renderDistance:{}\n", self.render_distance);
+        for (key, value) in &self.other {
+            text.push_str(&format!("{}:{}\n", key, value));
+        }
+        text
+    }
+
+    // Próximo passo de RENDER_DISTANCE_STEPS, voltando ao menor depois do maior
+    pub fn cycle_render_distance(&mut self) {
+        self.render_distance = RENDER_DISTANCE_STEPS
+            .into_iter()
+            .find(|&distance| distance > self.render_distance)
+            .unwrap_or(RENDER_DISTANCE_STEPS[0]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_keep_unknown_keys_and_clamp_the_distance() {
+        let options = Options::parse("version:3465\nrenderDistance:64\nfov:0.0\nnot an option\n");
+        assert_eq!(options.render_distance, MAX_RENDER_DISTANCE);
+        assert_eq!(options.to_text(), "renderDistance:32\nversion:3465\nfov:0.0\n");
+        assert_eq!(Options::parse(&options.to_text()), options);
+
+        assert_eq!(Options::parse("renderDistance:abc").render_distance, DEFAULT_RENDER_DISTANCE);
+        assert_eq!(Options::parse("renderDistance:0").render_distance, MIN_RENDER_DISTANCE);
+    }
+
+    #[test]
+    fn render_distance_cycles_through_the_steps() {
+        let mut options = Options::parse("renderDistance:5");
+        options.cycle_render_distance();
+        assert_eq!(options.render_distance, 8);
+        options.render_distance = 16;
+        options.cycle_render_distance();
+        assert_eq!(options.render_distance, 2);
+    }
+
+    #[test]
+    fn missing_file_uses_defaults() {
+        let path = std::env::temp_dir().join(format!("betacraft-options-{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert_eq!(Options::load(&path).unwrap(), Options::default());
+
+        let options = Options { render_distance: 12, ..Options::default() };
+        options.save(&path).unwrap();
+        assert_eq!(Options::load(&path).unwrap(), options);
+        fs::remove_file(&path).unwrap();
+    }
+}
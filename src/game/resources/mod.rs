@@ -0,0 +1,393 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use image::imageops;
+use image::RgbaImage;
+use crate::json::{self, Json, JsonError};
+use zip::{ZipArchive, ZipError};
+
+pub mod zip;
+
+#[derive(Debug)]
+pub enum PackError {
+    Io(io::Error),
+    Zip(ZipError),
+    Json(JsonError),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::Io(err) => write!(f, "resource pack I/O error: {}", err),
+            PackError::Zip(err) => write!(f, "{}", err),
+            PackError::Json(err) => write!(f, "{}", err),
+            PackError::Invalid(reason) => write!(f, "invalid resource pack: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+impl From<io::Error> for PackError {
+    fn from(err: io::Error) -> Self {
+        PackError::Io(err)
+    }
+}
+
+impl From<ZipError> for PackError {
+    fn from(err: ZipError) -> Self {
+        PackError::Zip(err)
+    }
+}
+
+impl From<JsonError> for PackError {
+    fn from(err: JsonError) -> Self {
+        PackError::Json(err)
+    }
+}
+
+// Campos de `pack` no pack.mcmeta
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackMeta {
+    pub pack_format: i32,
+    pub description: String, // Só descrições em texto simples; componentes de chat viram ""
+}
+
+enum PackSource {
+    Directory(PathBuf),
+    Zip(ZipArchive),
+}
+
+// Resource pack no layout vanilla (pack.mcmeta, assets/<namespace>/...), numa pasta ou num zip
+pub struct ResourcePack {
+    pub name: String,
+    pub meta: PackMeta,
+    source: PackSource,
+}
+
+// Caminhos de asset são sempre relativos, com "/" e sem "..", para não sair da pasta do pack
+fn is_valid_asset_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.contains('\\')
+        && path.split('/').all(|part| !part.is_empty() && part != "." && part != "..")
+}
+
+impl ResourcePack {
+    pub fn open(path: &Path) -> Result<Self, PackError> {
+        let name = path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let source = if path.is_dir() {
+            PackSource::Directory(path.to_path_buf())
+        } else {
+            PackSource::Zip(ZipArchive::new(fs::read(path)?)?)
+        };
+        Self::from_source(name, source)
+    }
+
+    #[cfg(test)]
+    pub fn from_zip(name: &str, bytes: Vec<u8>) -> Result<Self, PackError> {
+        Self::from_source(name.to_string(), PackSource::Zip(ZipArchive::new(bytes)?))
+    }
+
+    fn from_source(name: String, source: PackSource) -> Result<Self, PackError> {
+        let mut pack = Self {
+            name,
+            meta: PackMeta { pack_format: 0, description: String::new() },
+            source,
+        };
+        let mcmeta = pack.read("pack.mcmeta")?.ok_or(PackError::Invalid("missing pack.mcmeta"))?;
+        let mcmeta = json::parse(std::str::from_utf8(&mcmeta).map_err(|_| PackError::Invalid("pack.mcmeta is not UTF-8"))?)?;
+        let section = mcmeta.get("pack").ok_or(PackError::Invalid("pack.mcmeta without pack section"))?;
+        pack.meta = PackMeta {
+            pack_format: section
+                .get("pack_format")
+                .and_then(Json::as_f64)
+                .ok_or(PackError::Invalid("pack.mcmeta without pack_format"))? as i32,
+            description: section.get("description").and_then(Json::as_str).unwrap_or_default().to_string(),
+        };
+        Ok(pack)
+    }
+
+    // `None` se o pack não tem o asset
+    pub fn read(&self, asset: &str) -> Result<Option<Vec<u8>>, PackError> {
+        if !is_valid_asset_path(asset) {
+            return Ok(None);
+        }
+        match &self.source {
+            PackSource::Directory(dir) => match fs::read(dir.join(asset)) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+            PackSource::Zip(archive) => Ok(archive.read(asset)?),
+        }
+    }
+
+    // Caminhos de todos os arquivos dentro de `dir` (recursivo), como "assets/minecraft/textures/block/stone.png"
+    pub fn list(&self, dir: &str) -> Vec<String> {
+        let prefix = format!("{}/", dir.trim_end_matches('/'));
+        match &self.source {
+            PackSource::Directory(root) => {
+                let mut files = Vec::new();
+                let mut pending = vec![root.join(&prefix)];
+                while let Some(current) = pending.pop() {
+                    let Ok(entries) = fs::read_dir(&current) else { continue };
+                    for path in entries.flatten().map(|entry| entry.path()) {
+                        if path.is_dir() {
+                            pending.push(path);
+                        } else if let Ok(relative) = path.strip_prefix(root) {
+                            files.push(relative.to_string_lossy().replace('\\', "/"));
+                        }
+                    }
+                }
+                files
+            }
+            PackSource::Zip(archive) => archive
+                .names()
+                .filter(|name| name.starts_with(&prefix))
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+// Normaliza um id de recurso: sem namespace é "minecraft", como no vanilla ("block/stone" → "minecraft:block/stone")
+pub fn resource_location(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{}", name)
+    }
+}
+
+// Packs empilhados: o primeiro que tem um asset é o que vale
+pub struct ResourceManager {
+    packs: Vec<ResourcePack>, // Da maior para a menor prioridade
+}
+
+impl ResourceManager {
+    pub fn new(packs: Vec<ResourcePack>) -> Self {
+        Self { packs }
+    }
+
+    // Packs de `packs_dir` (pastas ou .zip, em ordem alfabética de prioridade) sobre o pack embutido `builtin`.
+    // Packs inválidos são ignorados com um aviso; só o embutido é obrigatório
+    pub fn load(packs_dir: &Path, builtin: &Path) -> Result<Self, PackError> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(packs_dir)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        paths.sort();
+
+        let mut packs = Vec::new();
+        for path in paths {
+            if !path.is_dir() && path.extension().is_none_or(|ext| ext != "zip") {
+                continue;
+            }
+            match ResourcePack::open(&path) {
+                Ok(pack) => packs.push(pack),
+                Err(err) => eprintln!("Skipping resource pack {}: {}", path.display(), err),
+            }
+        }
+        packs.push(ResourcePack::open(builtin)?);
+        Ok(Self::new(packs))
+    }
+
+    pub fn packs(&self) -> &[ResourcePack] {
+        &self.packs
+    }
+
+    pub fn read(&self, asset: &str) -> Option<Vec<u8>> {
+        self.packs.iter().find_map(|pack| match pack.read(asset) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                eprintln!("Failed to read {} from resource pack {}: {}", asset, pack.name, err);
+                None
+            }
+        })
+    }
+
+    pub fn json(&self, asset: &str) -> Option<Json> {
+        let bytes = self.read(asset)?;
+        match std::str::from_utf8(&bytes).map_err(|_| PackError::Invalid("not UTF-8")).and_then(|text| Ok(json::parse(text)?)) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                eprintln!("Invalid JSON in {}: {}", asset, err);
+                None
+            }
+        }
+    }
+
+    // Imagem já invertida na vertical, com a primeira linha embaixo como o OpenGL espera
+    pub fn image(&self, asset: &str) -> Option<RgbaImage> {
+        let bytes = self.read(asset)?;
+        match image::load_from_memory(&bytes) {
+            Ok(image) => Some(image.flipv().to_rgba8()),
+            Err(err) => {
+                eprintln!("Invalid image {}: {}", asset, err);
+                None
+            }
+        }
+    }
+
+    // União dos arquivos de `dir` em todos os packs
+    pub fn list(&self, dir: &str) -> BTreeSet<String> {
+        self.packs.iter().flat_map(|pack| pack.list(dir)).collect()
+    }
+
+    // Texturas de `assets/<namespace>/textures/<kind>/`, pelo id de recurso: "minecraft:block/stone"
+    pub fn textures(&self, kind: &str) -> Vec<(String, RgbaImage)> {
+        let namespaces: BTreeSet<String> = self
+            .list("assets")
+            .iter()
+            .filter_map(|path| path.split('/').nth(1).map(str::to_string))
+            .collect();
+
+        let mut textures = Vec::new();
+        for namespace in namespaces {
+            let dir = format!("assets/{}/textures/{}", namespace, kind);
+            for path in self.list(&dir) {
+                let Some(name) = path.strip_suffix(".png") else { continue };
+                if let Some(mut image) = self.image(&path) {
+                    // Texturas animadas empilham os quadros na vertical; fica só o primeiro
+                    let animated = self.json(&format!("{}.mcmeta", path)).is_some_and(|meta| meta.get("animation").is_some());
+                    if animated {
+                        image = first_frame(&image);
+                    }
+                    let name = &name[dir.len() + 1..];
+                    textures.push((format!("{}:{}/{}", namespace, kind, name), image));
+                }
+            }
+        }
+        textures
+    }
+
+    // assets/<namespace>/blockstates/<name>.json, pelo id do bloco
+    pub fn blockstate(&self, block: &str) -> Option<Json> {
+        let location = resource_location(block);
+        let (namespace, name) = location.split_once(':')?;
+        self.json(&format!("assets/{}/blockstates/{}.json", namespace, name))
+    }
+
+    // assets/<namespace>/models/<name>.json, ex.: "minecraft:block/cube_all"
+    pub fn model(&self, model: &str) -> Option<Json> {
+        let location = resource_location(model);
+        let (namespace, name) = location.split_once(':')?;
+        self.json(&format!("assets/{}/models/{}.json", namespace, name))
+    }
+}
+
+// Quadro quadrado do topo do arquivo, que fica embaixo depois de `ResourceManager::image` inverter a imagem
+fn first_frame(image: &RgbaImage) -> RgbaImage {
+    let size = image.width().min(image.height());
+    imageops::crop_imm(image, 0, image.height() - size, size, size).to_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const MCMETA: &[u8] = br#"{ "pack": { "pack_format": 15, "description": "Teste" } }"#;
+
+    fn png(value: u8) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(2, 2, Rgba([value, 0, 0, 255]));
+        let mut bytes = io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageOutputFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    fn zip_pack(name: &str, files: &[(&str, &[u8])]) -> ResourcePack {
+        let files: Vec<_> = files.iter().map(|&(path, bytes)| (path, bytes, true)).collect();
+        ResourcePack::from_zip(name, zip::build(&files)).unwrap()
+    }
+
+    #[test]
+    fn first_pack_with_an_asset_wins() {
+        let (red, blue) = (png(200), png(50));
+        let high = zip_pack("high", &[
+            ("pack.mcmeta", MCMETA),
+            ("assets/minecraft/textures/block/stone.png", &red),
+        ]);
+        let low = zip_pack("low", &[
+            ("pack.mcmeta", MCMETA),
+            ("assets/minecraft/textures/block/stone.png", &blue),
+            ("assets/minecraft/textures/block/dirt.png", &blue),
+            ("assets/minecraft/models/block/dirt.json", br#"{ "parent": "block/cube_all" }"#),
+        ]);
+        assert_eq!(high.meta, PackMeta { pack_format: 15, description: "Teste".to_string() });
+
+        let resources = ResourceManager::new(vec![high, low]);
+        let textures = resources.textures("block");
+        let names: Vec<_> = textures.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["minecraft:block/dirt", "minecraft:block/stone"]);
+        assert_eq!(textures[1].1.get_pixel(0, 0)[0], 200);
+        assert_eq!(textures[0].1.get_pixel(0, 0)[0], 50);
+
+        let model = resources.model("block/dirt").unwrap();
+        assert_eq!(model.get("parent").and_then(Json::as_str), Some("block/cube_all"));
+        assert!(resources.blockstate("minecraft:dirt").is_none());
+    }
+
+    #[test]
+    fn animated_textures_keep_their_first_frame() {
+        // Dois quadros 2x2 um sobre o outro: vermelho em cima, azul embaixo
+        let mut strip = RgbaImage::from_pixel(2, 4, Rgba([0, 0, 200, 255]));
+        for x in 0..2 {
+            for y in 0..2 {
+                strip.put_pixel(x, y, Rgba([200, 0, 0, 255]));
+            }
+        }
+        let mut bytes = io::Cursor::new(Vec::new());
+        strip.write_to(&mut bytes, image::ImageOutputFormat::Png).unwrap();
+        let strip = bytes.into_inner();
+        let pack = zip_pack("animated", &[
+            ("pack.mcmeta", MCMETA),
+            ("assets/minecraft/textures/block/water_still.png", &strip),
+            ("assets/minecraft/textures/block/water_still.png.mcmeta", br#"{ "animation": { "frametime": 2 } }"#),
+            ("assets/minecraft/textures/block/tall.png", &strip),
+        ]);
+
+        let textures = ResourceManager::new(vec![pack]).textures("block");
+        let names: Vec<_> = textures.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["minecraft:block/tall", "minecraft:block/water_still"]);
+        assert_eq!(textures[1].1.dimensions(), (2, 2));
+        assert!(textures[1].1.pixels().all(|pixel| pixel[0] == 200));
+        // Sem .mcmeta a imagem fica inteira
+        assert_eq!(textures[0].1.dimensions(), (2, 4));
+    }
+
+    #[test]
+    fn directory_packs_read_like_zips() {
+        let dir = std::env::temp_dir().join(format!("betacraft-pack-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("assets/minecraft/blockstates")).unwrap();
+        fs::write(dir.join("pack.mcmeta"), MCMETA).unwrap();
+        fs::write(dir.join("assets/minecraft/blockstates/stone.json"), r#"{ "variants": {} }"#).unwrap();
+
+        let pack = ResourcePack::open(&dir).unwrap();
+        assert_eq!(pack.list("assets/minecraft"), vec!["assets/minecraft/blockstates/stone.json".to_string()]);
+        // Caminhos que sairiam da pasta do pack não são lidos
+        assert_eq!(pack.read("../pack.mcmeta").unwrap(), None);
+
+        let resources = ResourceManager::new(vec![pack]);
+        assert!(resources.blockstate("stone").unwrap().get("variants").is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn packs_need_a_valid_mcmeta() {
+        let missing = zip::build(&[("assets/minecraft/textures/block/stone.png", b"", false)]);
+        assert!(matches!(ResourcePack::from_zip("a", missing), Err(PackError::Invalid(_))));
+        let broken = zip::build(&[("pack.mcmeta", b"{ pack", false)]);
+        assert!(matches!(ResourcePack::from_zip("b", broken), Err(PackError::Json(_))));
+    }
+
+    #[test]
+    fn locations_default_to_minecraft() {
+        assert_eq!(resource_location("block/stone"), "minecraft:block/stone");
+        assert_eq!(resource_location("mod:block/ore"), "mod:block/ore");
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+use flate2::read::DeflateDecoder;
+use flate2::Crc;
+
+const END_OF_DIRECTORY: u32 = 0x0605_4B50;
+const DIRECTORY_ENTRY: u32 = 0x0201_4B50;
+const LOCAL_HEADER: u32 = 0x0403_4B50;
+const END_OF_DIRECTORY_SIZE: usize = 22;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+
+// Reserva inicial máxima ao descomprimir; o tamanho declarado no diretório pode ser mentira
+const MAX_PREALLOC: usize = 1 << 20;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ZipError {
+    Invalid(&'static str),
+    Unsupported(&'static str),
+    UnsupportedMethod(u16),
+    Corrupt(String), // Nome da entrada cujo conteúdo não bate com o CRC ou o tamanho
+}
+
+impl fmt::Display for ZipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZipError::Invalid(reason) => write!(f, "invalid zip: {}", reason),
+            ZipError::Unsupported(feature) => write!(f, "unsupported zip feature: {}", feature),
+            ZipError::UnsupportedMethod(method) => write!(f, "unsupported zip compression method {}", method),
+            ZipError::Corrupt(name) => write!(f, "corrupt zip entry {}", name),
+        }
+    }
+}
+
+impl std::error::Error for ZipError {}
+
+struct Entry {
+    method: u16,
+    crc: u32,
+    compressed_size: usize,
+    size: usize,
+    local_offset: usize,
+}
+
+// Leitor de zip em memória: só o diretório central é lido na abertura; cada entrada é
+// descomprimida quando pedida. Suporta entradas sem compressão e deflate, sem zip64 nem criptografia
+pub struct ZipArchive {
+    data: Vec<u8>,
+    entries: BTreeMap<String, Entry>,
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, ZipError> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or(ZipError::Invalid("truncated"))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, ZipError> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(ZipError::Invalid("truncated"))
+}
+
+impl ZipArchive {
+    pub fn new(data: Vec<u8>) -> Result<Self, ZipError> {
+        // O fim do diretório fica nos últimos 22 bytes, seguido de um comentário de até 64 KiB
+        let search_start = data.len().saturating_sub(END_OF_DIRECTORY_SIZE + u16::MAX as usize);
+        let end = (search_start..=data.len().saturating_sub(END_OF_DIRECTORY_SIZE))
+            .rev()
+            .find(|&offset| u32_at(&data, offset) == Ok(END_OF_DIRECTORY))
+            .ok_or(ZipError::Invalid("missing end of central directory"))?;
+
+        let count = u16_at(&data, end + 10)?;
+        let directory_offset = u32_at(&data, end + 16)?;
+        if count == u16::MAX || directory_offset == u32::MAX {
+            return Err(ZipError::Unsupported("zip64"));
+        }
+
+        let mut entries = BTreeMap::new();
+        let mut offset = directory_offset as usize;
+        for _ in 0..count {
+            if u32_at(&data, offset)? != DIRECTORY_ENTRY {
+                return Err(ZipError::Invalid("bad central directory entry"));
+            }
+            let flags = u16_at(&data, offset + 8)?;
+            let name_len = u16_at(&data, offset + 28)? as usize;
+            let extra_len = u16_at(&data, offset + 30)? as usize;
+            let comment_len = u16_at(&data, offset + 32)? as usize;
+            let name = data
+                .get(offset + 46..offset + 46 + name_len)
+                .ok_or(ZipError::Invalid("truncated"))?;
+            let name = String::from_utf8_lossy(name).into_owned();
+            if flags & 1 != 0 {
+                return Err(ZipError::Unsupported("encryption"));
+            }
+
+            // Diretórios não têm conteúdo
+            if !name.ends_with('/') {
+                entries.insert(name, Entry {
+                    method: u16_at(&data, offset + 10)?,
+                    crc: u32_at(&data, offset + 16)?,
+                    compressed_size: u32_at(&data, offset + 20)? as usize,
+                    size: u32_at(&data, offset + 24)? as usize,
+                    local_offset: u32_at(&data, offset + 42)? as usize,
+                });
+            }
+            offset += 46 + name_len + extra_len + comment_len;
+        }
+        Ok(Self { data, entries })
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    // `None` se a entrada não existe
+    pub fn read(&self, name: &str) -> Result<Option<Vec<u8>>, ZipError> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+        let header = entry.local_offset;
+        if u32_at(&self.data, header)? != LOCAL_HEADER {
+            return Err(ZipError::Invalid("bad local header"));
+        }
+        // O cabeçalho local pode ter um campo extra diferente do diretório central
+        let start = header + 30 + u16_at(&self.data, header + 26)? as usize + u16_at(&self.data, header + 28)? as usize;
+        let compressed = self
+            .data
+            .get(start..start + entry.compressed_size)
+            .ok_or(ZipError::Invalid("truncated"))?;
+
+        let contents = match entry.method {
+            METHOD_STORED => compressed.to_vec(),
+            METHOD_DEFLATE => {
+                // Lê um byte além do tamanho declarado para detectar entradas que expandem mais que o anunciado
+                let mut out = Vec::with_capacity(entry.size.min(MAX_PREALLOC));
+                DeflateDecoder::new(compressed)
+                    .take(entry.size as u64 + 1)
+                    .read_to_end(&mut out)
+                    .map_err(|_| ZipError::Corrupt(name.to_string()))?;
+                out
+            }
+            method => return Err(ZipError::UnsupportedMethod(method)),
+        };
+
+        let mut crc = Crc::new();
+        crc.update(&contents);
+        if contents.len() != entry.size || crc.sum() != entry.crc {
+            return Err(ZipError::Corrupt(name.to_string()));
+        }
+        Ok(Some(contents))
+    }
+}
+
+// Monta um zip com entradas (nome, conteúdo, comprimir com deflate), para os testes
+#[cfg(test)]
+pub fn build(files: &[(&str, &[u8], bool)]) -> Vec<u8> {
+    use std::io::Write;
+    use flate2::write::DeflateEncoder;
+
+    let mut out = Vec::new();
+    let mut directory = Vec::new();
+    for &(name, contents, deflate) in files {
+        let data = if deflate {
+            let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(contents).unwrap();
+            encoder.finish().unwrap()
+        } else {
+            contents.to_vec()
+        };
+        let mut crc = Crc::new();
+        crc.update(contents);
+        let method = if deflate { METHOD_DEFLATE } else { METHOD_STORED };
+
+        // Campos comuns aos dois cabeçalhos: versão, flags, método, hora, data, crc, tamanhos, nome, extra
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&method.to_le_bytes());
+        common.extend_from_slice(&[0; 4]);
+        common.extend_from_slice(&crc.sum().to_le_bytes());
+        common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+
+        directory.extend_from_slice(&DIRECTORY_ENTRY.to_le_bytes());
+        directory.extend_from_slice(&20u16.to_le_bytes());
+        directory.extend_from_slice(&common);
+        directory.extend_from_slice(&[0; 10]); // Comentário, disco, atributos
+        directory.extend_from_slice(&(out.len() as u32).to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+
+        out.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        out.extend_from_slice(&common);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&data);
+    }
+
+    let directory_offset = out.len() as u32;
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(&END_OF_DIRECTORY.to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    out.extend_from_slice(&directory_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_stored_and_deflated_entries() {
+        let text = b"texto repetido ".repeat(50);
+        let archive = ZipArchive::new(build(&[
+            ("pack.mcmeta", b"{}", false),
+            ("assets/minecraft/lang/pt_br.txt", &text, true),
+            ("assets/", b"", false),
+        ]))
+        .unwrap();
+
+        assert_eq!(archive.read("pack.mcmeta").unwrap(), Some(b"{}".to_vec()));
+        assert_eq!(archive.read("assets/minecraft/lang/pt_br.txt").unwrap(), Some(text));
+        assert_eq!(archive.read("missing.png").unwrap(), None);
+        // Diretórios não aparecem como entradas
+        assert_eq!(archive.names().collect::<Vec<_>>(), vec!["assets/minecraft/lang/pt_br.txt", "pack.mcmeta"]);
+    }
+
+    #[test]
+    fn corrupt_contents_are_detected() {
+        let mut bytes = build(&[("a.txt", b"hello", false)]);
+        // Troca um byte do conteúdo, que fica logo após o cabeçalho local de 30 bytes e o nome
+        bytes[30 + "a.txt".len()] ^= 0xFF;
+        let archive = ZipArchive::new(bytes).unwrap();
+        assert_eq!(archive.read("a.txt"), Err(ZipError::Corrupt("a.txt".to_string())));
+    }
+
+    #[test]
+    fn lying_sizes_are_corrupt_without_huge_allocations() {
+        let mut bytes = build(&[("a.txt", &b"hello ".repeat(20), true)]);
+        // Tamanho descomprimido no diretório central: 24 bytes depois do início da entrada
+        let directory = u32::from_le_bytes(bytes[bytes.len() - 6..bytes.len() - 2].try_into().unwrap()) as usize;
+        bytes[directory + 24..directory + 28].copy_from_slice(&u32::MAX.to_le_bytes());
+        let archive = ZipArchive::new(bytes).unwrap();
+        assert_eq!(archive.read("a.txt"), Err(ZipError::Corrupt("a.txt".to_string())));
+    }
+
+    #[test]
+    fn invalid_archives_are_errors() {
+        assert!(ZipArchive::new(Vec::new()).is_err());
+        assert!(ZipArchive::new(b"not a zip file at all, just text".to_vec()).is_err());
+
+        let bytes = build(&[("a.txt", b"hello", true)]);
+        // Corta o diretório central: o fim do diretório some junto
+        assert!(ZipArchive::new(bytes[..bytes.len() - 30].to_vec()).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use std::f32::consts::PI;
+use glam::{Vec3, Vec4};
+use super::world::level::DAY_LENGTH;
+
+pub const MOON_PHASES: i64 = 8; // Uma fase por dia, começando pela lua cheia
+
+// Cores base das planícies no vanilla
+const SKY_COLOR: Vec3 = Vec3::new(120.0 / 255.0, 167.0 / 255.0, 1.0);
+const FOG_COLOR: Vec3 = Vec3::new(192.0 / 255.0, 216.0 / 255.0, 1.0);
+
+// Fração da volta do sol, como no vanilla: 0 ao meio-dia (tick 6000), 0.5 à meia-noite (18000).
+// O sol anda mais devagar no alto, então dia e noite duram mais que o nascer e o pôr do sol
+pub fn celestial_angle(day_time: i64) -> f32 {
+    let day = (day_time.rem_euclid(DAY_LENGTH) as f32 / DAY_LENGTH as f32 - 0.25).rem_euclid(1.0);
+    let eased = 0.5 - (day * PI).cos() / 2.0;
+    (day * 2.0 + eased) / 3.0
+}
+
+// Direção do sol: nasce no leste (+X), passa pelo alto e se põe no oeste; a lua fica do lado oposto
+pub fn sun_direction(day_time: i64) -> Vec3 {
+    let angle = celestial_angle(day_time) * 2.0 * PI;
+    Vec3::new(-angle.sin(), angle.cos(), 0.0)
+}
+
+// Altura do sol de -1 a 1, saturada em dobro como no vanilla para o céu clarear antes do nascer
+fn sun_height(day_time: i64) -> f32 {
+    ((celestial_angle(day_time) * 2.0 * PI).cos() * 2.0 + 0.5).clamp(0.0, 1.0)
+}
+
+// Multiplicador da luz do céu nos blocos: 1 de dia, 0.2 à noite
+pub fn daylight(day_time: i64) -> f32 {
+    let darkness = (1.0 - ((celestial_angle(day_time) * 2.0 * PI).cos() * 2.0 + 0.2)).clamp(0.0, 1.0);
+    (1.0 - darkness) * 0.8 + 0.2
+}
+
+// Cor do céu no alto
+pub fn sky_color(day_time: i64) -> Vec3 {
+    SKY_COLOR * sun_height(day_time)
+}
+
+// Cor do horizonte (e da neblina), que nunca fica totalmente preta
+pub fn fog_color(day_time: i64) -> Vec3 {
+    let height = sun_height(day_time);
+    FOG_COLOR * Vec3::new(height * 0.94 + 0.06, height * 0.94 + 0.06, height * 0.91 + 0.09)
+}
+
+// Brilho alaranjado do horizonte do lado do sol no nascer e no pôr; alfa é a intensidade.
+// None longe desses momentos
+pub fn sunrise_color(day_time: i64) -> Option<Vec4> {
+    let height = (celestial_angle(day_time) * 2.0 * PI).cos();
+    if height.abs() > 0.4 {
+        return None;
+    }
+    let progress = height / 0.4 * 0.5 + 0.5;
+    let strength = 1.0 - (1.0 - (progress * PI).sin()) * 0.99;
+    Some(Vec4::new(progress * 0.3 + 0.7, progress * progress * 0.7 + 0.2, 0.2, strength * strength))
+}
+
+pub fn moon_phase(day_time: i64) -> i64 {
+    day_time.div_euclid(DAY_LENGTH).rem_euclid(MOON_PHASES)
+}
+
+// Recorte da fase em moon_phases.png, uma grade de 4x2 fases
+pub fn moon_phase_uv(phase: i64) -> [f32; 4] {
+    [(phase % 4) as f32 / 4.0, (phase / 4) as f32 / 2.0, 0.25, 0.5]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn sun_crosses_the_sky_once_a_day() {
+        assert!(close(celestial_angle(6000), 0.0));
+        assert!(close(celestial_angle(18000), 0.5));
+        assert!(close(celestial_angle(6000 + DAY_LENGTH * 3), 0.0));
+        assert!(sun_direction(6000).abs_diff_eq(Vec3::Y, 1e-4));
+        assert!(sun_direction(18000).abs_diff_eq(-Vec3::Y, 1e-4));
+
+        // Pouco acima do horizonte leste logo ao amanhecer, oeste no fim da tarde
+        let dawn = sun_direction(0);
+        assert!(dawn.x > 0.9 && dawn.y > 0.0);
+        let dusk = sun_direction(12000);
+        assert!(dusk.x < -0.9 && dusk.y > 0.0);
+    }
+
+    #[test]
+    fn daylight_and_sky_follow_the_sun() {
+        assert!(close(daylight(6000), 1.0));
+        assert!(close(daylight(18000), 0.2));
+        assert!(daylight(0) > 0.2 && daylight(0) < 1.0);
+
+        assert_eq!(sky_color(6000), SKY_COLOR);
+        assert_eq!(sky_color(18000), Vec3::ZERO);
+        assert!(fog_color(18000).min_element() > 0.0);
+    }
+
+    #[test]
+    fn sunrise_glows_only_near_the_horizon() {
+        assert!(sunrise_color(6000).is_none());
+        assert!(sunrise_color(18000).is_none());
+        let dawn = sunrise_color(23500).unwrap();
+        assert!(dawn.w > 0.5 && dawn.x > dawn.z);
+        assert!(sunrise_color(12500).is_some());
+    }
+
+    #[test]
+    fn moon_changes_phase_every_day() {
+        assert_eq!(moon_phase(0), 0);
+        assert_eq!(moon_phase(DAY_LENGTH * 3 + 100), 3);
+        assert_eq!(moon_phase(DAY_LENGTH * 9), 1);
+        assert_eq!(moon_phase_uv(0), [0.0, 0.0, 0.25, 0.5]);
+        assert_eq!(moon_phase_uv(6), [0.5, 0.5, 0.25, 0.5]);
+    }
+}
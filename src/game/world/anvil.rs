@@ -0,0 +1,599 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::game::blocks::{registry, BlockId};
+use crate::nbt::compression::{compress, decompress};
+use crate::nbt::{self, Compression, NbtError, Tag};
+use super::chunk::{Chunk, Section, CHUNK_SIZE, SECTION_COUNT, WORLD_HEIGHT};
+use super::palette::{longs_for, PalettedContainer};
+
+pub const REGION_SIZE: i32 = 32;      // Chunks por lado de um r.X.Z.mca
+pub const DATA_VERSION: i32 = 3465;   // Minecraft Java 1.20.1
+const SECTOR_SIZE: usize = 4096;
+const HEADER_SECTORS: usize = 2;      // Tabela de offsets + timestamps
+const MAX_CHUNK_SECTORS: usize = 255; // Limite do campo de 1 byte da tabela
+// Fundo do mundo no vanilla 1.18+. Nossas seções 0 a 15 ocupam y 0 a 255 lá, mas os heightmaps
+// do vanilla contam a altura a partir daqui
+const VANILLA_MIN_Y: i32 = -64;
+
+const COMPRESSION_GZIP: u8 = 1;
+const COMPRESSION_ZLIB: u8 = 2;
+const COMPRESSION_NONE: u8 = 3;
+
+pub type SharedStorage = Arc<Mutex<RegionStorage>>;
+
+#[derive(Debug)]
+pub enum AnvilError {
+    Io(io::Error),
+    Nbt(NbtError),
+    UnsupportedCompression(u8),
+    ChunkTooLarge(usize),        // Em setores, acima de MAX_CHUNK_SECTORS
+    InvalidChunk(&'static str),
+    UnknownBlock(String),        // `Name` de paleta que não está no registro
+}
+
+impl fmt::Display for AnvilError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnvilError::Io(err) => write!(f, "region I/O error: {}", err),
+            AnvilError::Nbt(err) => write!(f, "invalid chunk NBT: {}", err),
+            AnvilError::UnsupportedCompression(id) => write!(f, "unsupported chunk compression {}", id),
+            AnvilError::ChunkTooLarge(sectors) => write!(f, "chunk needs {} sectors, limit is {}", sectors, MAX_CHUNK_SECTORS),
+            AnvilError::InvalidChunk(reason) => write!(f, "invalid chunk: {}", reason),
+            AnvilError::UnknownBlock(name) => write!(f, "chunk uses unknown block {}, it will not be overwritten", name),
+        }
+    }
+}
+
+impl std::error::Error for AnvilError {}
+
+impl From<io::Error> for AnvilError {
+    fn from(err: io::Error) -> Self {
+        AnvilError::Io(err)
+    }
+}
+
+impl From<NbtError> for AnvilError {
+    fn from(err: NbtError) -> Self {
+        AnvilError::Nbt(err)
+    }
+}
+
+// Um arquivo r.X.Z.mca: cabeçalho com 1024 offsets/timestamps seguido dos chunks em setores de 4 KiB
+pub struct RegionFile {
+    file: File,
+    locations: Vec<u32>,  // offset em setores << 8 | quantidade de setores
+    timestamps: Vec<u32>, // Segundos desde a época da última gravação
+    used: Vec<bool>,      // Setores ocupados, incluindo o cabeçalho
+}
+
+impl RegionFile {
+    pub fn open(path: &Path) -> Result<Self, AnvilError> {
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let mut header = vec![0u8; HEADER_SECTORS * SECTOR_SIZE];
+        let len = file.metadata()?.len() as usize;
+        if len < header.len() {
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(&header)?;
+        } else {
+            file.seek(SeekFrom::Start(0))?;
+            file.read_exact(&mut header)?;
+        }
+
+        let entry = |i: usize| u32::from_be_bytes(header[i * 4..i * 4 + 4].try_into().unwrap());
+        let mut locations: Vec<u32> = (0..1024).map(entry).collect();
+        let timestamps = (1024..2048).map(entry).collect();
+
+        let total_sectors = len.max(header.len()).div_ceil(SECTOR_SIZE);
+        let mut used = vec![false; total_sectors];
+        used[..HEADER_SECTORS].fill(true);
+        // Entradas que apontam para o cabeçalho, para fora do arquivo ou sobre outro chunk são descartadas
+        for location in locations.iter_mut().filter(|l| **l != 0) {
+            let (offset, count) = split_location(*location);
+            let range = offset..offset + count;
+            if count == 0 || offset < HEADER_SECTORS || range.end > total_sectors || used[range.clone()].contains(&true) {
+                *location = 0;
+                continue;
+            }
+            used[range].fill(true);
+        }
+
+        Ok(Self { file, locations, timestamps, used })
+    }
+
+    // Índice no cabeçalho; aceita coordenadas absolutas de chunk
+    fn index(x: i32, z: i32) -> usize {
+        (x.rem_euclid(REGION_SIZE) + z.rem_euclid(REGION_SIZE) * REGION_SIZE) as usize
+    }
+
+    #[cfg(test)]
+    fn has_chunk(&self, x: i32, z: i32) -> bool {
+        self.locations[Self::index(x, z)] != 0
+    }
+
+    pub fn read_chunk(&mut self, x: i32, z: i32) -> Result<Option<Tag>, AnvilError> {
+        let location = self.locations[Self::index(x, z)];
+        if location == 0 {
+            return Ok(None);
+        }
+
+        let (offset, count) = split_location(location);
+        let mut sectors = vec![0u8; count * SECTOR_SIZE];
+        self.file.seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))?;
+        self.file.read_exact(&mut sectors)?;
+
+        // Tamanho inclui o byte de compressão
+        let length = u32::from_be_bytes(sectors[..4].try_into().unwrap()) as usize;
+        if length == 0 || length > sectors.len() - 4 {
+            return Err(AnvilError::InvalidChunk("chunk length exceeds its sectors"));
+        }
+        let compression = sectors[4];
+        let data = &sectors[5..4 + length];
+
+        let compression = match compression {
+            COMPRESSION_GZIP => Compression::Gzip,
+            COMPRESSION_ZLIB => Compression::Zlib,
+            COMPRESSION_NONE => Compression::None,
+            // Inclui chunks externos (.mcc, bit 0x80), que nunca gravamos
+            other => return Err(AnvilError::UnsupportedCompression(other)),
+        };
+
+        let (_, tag) = nbt::read(&decompress(data, compression)?)?;
+        Ok(Some(tag))
+    }
+
+    pub fn write_chunk(&mut self, x: i32, z: i32, tag: &Tag) -> Result<(), AnvilError> {
+        let compressed = compress(&nbt::write("", tag)?, Compression::Zlib);
+
+        let mut payload = Vec::with_capacity(compressed.len() + 5);
+        payload.extend_from_slice(&(compressed.len() as u32 + 1).to_be_bytes());
+        payload.push(COMPRESSION_ZLIB);
+        payload.extend_from_slice(&compressed);
+        let count = payload.len().div_ceil(SECTOR_SIZE);
+        if count > MAX_CHUNK_SECTORS {
+            return Err(AnvilError::ChunkTooLarge(count));
+        }
+        payload.resize(count * SECTOR_SIZE, 0);
+
+        // Libera os setores antigos antes de procurar espaço, permitindo reaproveitá-los
+        let index = Self::index(x, z);
+        let (old_offset, old_count) = split_location(self.locations[index]);
+        if self.locations[index] != 0 {
+            self.used[old_offset..old_offset + old_count].fill(false);
+        }
+        let offset = self.allocate(count);
+
+        self.file.seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))?;
+        self.file.write_all(&payload)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as u32);
+        self.locations[index] = (offset as u32) << 8 | count as u32;
+        self.timestamps[index] = timestamp;
+        self.file.seek(SeekFrom::Start((index * 4) as u64))?;
+        self.file.write_all(&self.locations[index].to_be_bytes())?;
+        self.file.seek(SeekFrom::Start((SECTOR_SIZE + index * 4) as u64))?;
+        self.file.write_all(&timestamp.to_be_bytes())?;
+        Ok(())
+    }
+
+    // Primeiro intervalo livre que comporta `count` setores, ou o fim do arquivo
+    fn allocate(&mut self, count: usize) -> usize {
+        let mut run = 0;
+        let mut offset = self.used.len();
+        for (sector, &used) in self.used.iter().enumerate() {
+            run = if used { 0 } else { run + 1 };
+            if run == count {
+                offset = sector + 1 - count;
+                break;
+            }
+        }
+
+        if offset + count > self.used.len() {
+            self.used.resize(offset + count, false);
+        }
+        self.used[offset..offset + count].fill(true);
+        offset
+    }
+}
+
+fn split_location(location: u32) -> (usize, usize) {
+    ((location >> 8) as usize, (location & 0xFF) as usize)
+}
+
+// Pasta `region` de um mundo, abrindo os arquivos de região conforme são usados
+pub struct RegionStorage {
+    dir: PathBuf,
+    regions: HashMap<(i32, i32), RegionFile>,
+    // Chunks com blocos desconhecidos; regravá-los perderia os originais, então nunca são salvos
+    unknown: HashSet<(i32, i32)>,
+}
+
+impl RegionStorage {
+    pub fn new(world_dir: &Path) -> io::Result<Self> {
+        let dir = world_dir.join("region");
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, regions: HashMap::new(), unknown: HashSet::new() })
+    }
+
+    pub fn shared(self) -> SharedStorage {
+        Arc::new(Mutex::new(self))
+    }
+
+    fn region(&mut self, chunk_x: i32, chunk_z: i32) -> Result<&mut RegionFile, AnvilError> {
+        let key = (chunk_x.div_euclid(REGION_SIZE), chunk_z.div_euclid(REGION_SIZE));
+        if !self.regions.contains_key(&key) {
+            let path = self.dir.join(format!("r.{}.{}.mca", key.0, key.1));
+            self.regions.insert(key, RegionFile::open(&path)?);
+        }
+        Ok(self.regions.get_mut(&key).unwrap())
+    }
+
+    pub fn load_chunk(&mut self, x: i32, z: i32) -> Result<Option<Chunk>, AnvilError> {
+        match self.region(x, z)?.read_chunk(x, z)? {
+            Some(tag) => chunk_from_nbt(&tag).map(Some).inspect_err(|err| {
+                if let AnvilError::UnknownBlock(_) = err {
+                    self.unknown.insert((x, z));
+                }
+            }),
+            None => Ok(None),
+        }
+    }
+
+    // Chunks recusados por `load_chunk` com UnknownBlock ficam como estão no disco; o erro já foi
+    // devolvido ao carregar
+    pub fn save_chunk(&mut self, chunk: &Chunk) -> Result<(), AnvilError> {
+        if self.unknown.contains(&(chunk.x, chunk.z)) {
+            return Ok(());
+        }
+        let tag = chunk_to_nbt(chunk);
+        self.region(chunk.x, chunk.z)?.write_chunk(chunk.x, chunk.z, &tag)
+    }
+}
+
+// Chunk no formato de disco do 1.18+: seções com paletas `block_states` e `Heightmaps`
+pub fn chunk_to_nbt(chunk: &Chunk) -> Tag {
+    let registry = registry();
+    let sections = (0..SECTION_COUNT)
+        .filter_map(|y| chunk.section(y).map(|section| (y, section)))
+        .map(|(y, section)| {
+            let (palette, data) = section.blocks().to_disk();
+            let palette = palette
+                .iter()
+                .map(|&id| {
+                    let mut entry = Tag::compound();
+                    entry.insert("Name", registry.get(id).name.as_str());
+                    let properties = registry.properties_of(id);
+                    if !properties.is_empty() {
+                        let mut values = Tag::compound();
+                        for (name, value) in properties {
+                            values.insert(name, value);
+                        }
+                        entry.insert("Properties", values);
+                    }
+                    entry
+                })
+                .collect();
+
+            let mut block_states = Tag::compound();
+            block_states.insert("palette", Tag::List(palette));
+            if !data.is_empty() {
+                block_states.insert("data", data.into_iter().map(|v| v as i64).collect::<Vec<_>>());
+            }
+            // Bioma único; o jogo vanilla exige a paleta mesmo sem usá-la
+            let mut biomes = Tag::compound();
+            biomes.insert("palette", Tag::List(vec!["minecraft:plains".into()]));
+
+            let mut tag = Tag::compound();
+            tag.insert("Y", y as i8)
+                .insert("block_states", block_states)
+                .insert("biomes", biomes);
+            tag
+        })
+        .collect();
+
+    let mut heightmaps = Tag::compound();
+    heightmaps
+        .insert("MOTION_BLOCKING", heightmap(chunk, |id| registry.is_solid(id) || registry.is_fluid(id)))
+        .insert("WORLD_SURFACE", heightmap(chunk, |id| id != BlockId::AIR));
+
+    let mut root = Tag::compound();
+    root.insert("DataVersion", DATA_VERSION)
+        .insert("xPos", chunk.x)
+        .insert("zPos", chunk.z)
+        .insert("yPos", 0i32)
+        .insert("Status", "minecraft:full")
+        .insert("LastUpdate", 0i64)
+        .insert("sections", Tag::List(sections))
+        .insert("Heightmaps", heightmaps);
+    root
+}
+
+pub fn chunk_from_nbt(tag: &Tag) -> Result<Chunk, AnvilError> {
+    let coord = |key| {
+        tag.get(key)
+            .and_then(Tag::as_i64)
+            .map(|v| v as i32)
+            .ok_or(AnvilError::InvalidChunk("missing chunk position"))
+    };
+    let mut chunk = Chunk::new(coord("xPos")?, coord("zPos")?);
+
+    let registry = registry();
+    let sections = tag.get("sections").and_then(Tag::as_list).unwrap_or_default();
+    for section in sections {
+        let y = section
+            .get("Y")
+            .and_then(Tag::as_i64)
+            .ok_or(AnvilError::InvalidChunk("section without Y"))?;
+        // Seções fora da nossa altura (ex.: abaixo de y=0 no vanilla) são ignoradas
+        if !(0..SECTION_COUNT as i64).contains(&y) {
+            continue;
+        }
+        let Some(states) = section.get("block_states") else {
+            continue;
+        };
+
+        let palette = states
+            .get("palette")
+            .and_then(Tag::as_list)
+            .ok_or(AnvilError::InvalidChunk("block_states without palette"))?
+            .iter()
+            .map(|entry| -> Result<BlockId, AnvilError> {
+                // Blocos desconhecidos recusam o chunk; propriedades desconhecidas ficam no padrão
+                let name = entry
+                    .get("Name")
+                    .and_then(Tag::as_str)
+                    .ok_or(AnvilError::InvalidChunk("palette entry without Name"))?;
+                let block = registry.id(name).ok_or_else(|| AnvilError::UnknownBlock(name.to_string()))?;
+                let properties = entry.get("Properties");
+                let values: Vec<(&str, &str)> = registry
+                    .get(block)
+                    .states
+                    .iter()
+                    .filter_map(|property| {
+                        let value = properties?.get(&property.name)?.as_str()?;
+                        Some((property.name.as_str(), value))
+                    })
+                    .collect();
+                Ok(registry.state(name, &values).unwrap_or(block))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let data: Vec<u64> = states
+            .get("data")
+            .and_then(Tag::as_long_array)
+            .unwrap_or_default()
+            .iter()
+            .map(|&v| v as u64)
+            .collect();
+
+        let blocks = PalettedContainer::from_disk(&palette, &data)
+            .ok_or(AnvilError::InvalidChunk("block_states data does not match palette"))?;
+        chunk.set_section(y as usize, Section::from_blocks(blocks));
+    }
+    Ok(chunk)
+}
+
+// Altura do bloco mais alto que satisfaz `counts` em cada coluna, como o vanilla grava: y + 1
+// contado a partir de VANILLA_MIN_Y, e 0 numa coluna sem nenhum. 9 bits por entrada
+fn heightmap(chunk: &Chunk, counts: impl Fn(BlockId) -> bool) -> Vec<i64> {
+    const BITS: usize = 9;
+    let per_long = 64 / BITS;
+    let mut data = vec![0i64; longs_for(BITS as u8, (CHUNK_SIZE * CHUNK_SIZE) as usize)];
+    for z in 0..CHUNK_SIZE as usize {
+        for x in 0..CHUNK_SIZE as usize {
+            let height = (0..WORLD_HEIGHT as usize)
+                .rev()
+                .find(|&y| counts(chunk.get_block(x, y, z)))
+                .map_or(0, |y| y as i32 + 1 - VANILLA_MIN_Y);
+            let index = z * CHUNK_SIZE as usize + x;
+            data[index / per_long] |= (height as i64) << ((index % per_long) * BITS);
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::world::generation::WorldGenerator;
+
+    // Pasta temporária própria de cada teste, removida no fim
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("betacraft-anvil-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn blocks(chunk: &Chunk) -> Vec<(i32, i32, i32, BlockId)> {
+        chunk.blocks().map(|b| (b.x, b.y, b.z, b.id)).collect()
+    }
+
+    #[test]
+    fn generated_chunks_round_trip_through_region_files() {
+        let fixture = Fixture::new("round-trip");
+        let generator = WorldGenerator::new(11);
+        // Inclui coordenadas negativas e chunks de duas regiões diferentes
+        let coords = [(0, 0), (31, 31), (-1, 0), (-33, 5)];
+
+        let mut storage = RegionStorage::new(&fixture.0).unwrap();
+        for &(x, z) in &coords {
+            storage.save_chunk(&generator.generate_chunk(x, z)).unwrap();
+        }
+        assert!(fixture.0.join("region/r.0.0.mca").exists());
+        assert!(fixture.0.join("region/r.-2.0.mca").exists());
+
+        // Reabre do disco para não depender dos arquivos já abertos
+        let mut storage = RegionStorage::new(&fixture.0).unwrap();
+        for &(x, z) in &coords {
+            let loaded = storage.load_chunk(x, z).unwrap().unwrap();
+            assert_eq!((loaded.x, loaded.z), (x, z));
+            assert_eq!(blocks(&loaded), blocks(&generator.generate_chunk(x, z)));
+        }
+        assert!(storage.load_chunk(4, 4).unwrap().is_none());
+    }
+
+    #[test]
+    fn rewriting_a_chunk_reuses_or_moves_sectors() {
+        let fixture = Fixture::new("rewrite");
+        let path = fixture.0.join("r.0.0.mca");
+        let stone = registry().id("minecraft:stone").unwrap();
+
+        let mut region = RegionFile::open(&path).unwrap();
+        let mut chunk = Chunk::new(1, 2);
+        chunk.set_block(0, 0, 0, stone);
+        region.write_chunk(1, 2, &chunk_to_nbt(&chunk)).unwrap();
+        region.write_chunk(3, 4, &chunk_to_nbt(&Chunk::new(3, 4))).unwrap();
+
+        // Conteúdo variado o bastante para ocupar mais setores que a primeira versão
+        for y in 0..WORLD_HEIGHT as usize {
+            for i in 0..CHUNK_SIZE as usize * CHUNK_SIZE as usize {
+                let id = BlockId(((i * 7 + y * 13) % 9) as u16);
+                chunk.set_block(i % 16, y, i / 16, id);
+            }
+        }
+        region.write_chunk(1, 2, &chunk_to_nbt(&chunk)).unwrap();
+        assert!(region.timestamps[RegionFile::index(1, 2)] > 0);
+
+        let mut region = RegionFile::open(&path).unwrap();
+        let loaded = chunk_from_nbt(&region.read_chunk(1, 2).unwrap().unwrap()).unwrap();
+        assert_eq!(blocks(&loaded), blocks(&chunk));
+        assert!(region.read_chunk(3, 4).unwrap().is_some());
+        assert_eq!(fs::metadata(&path).unwrap().len() as usize % SECTOR_SIZE, 0);
+    }
+
+    #[test]
+    fn reads_uncompressed_and_gzip_fixture_chunks() {
+        let fixture = Fixture::new("fixture");
+        let path = fixture.0.join("r.0.0.mca");
+        let mut chunk = Chunk::new(0, 1);
+        chunk.set_block(5, 70, 5, registry().id("minecraft:dirt").unwrap());
+        let bytes = nbt::write("", &chunk_to_nbt(&chunk)).unwrap();
+
+        let gzip = compress(&bytes, Compression::Gzip);
+
+        // Região montada à mão: chunk (0, 1) sem compressão no setor 2, (1, 1) em gzip no seguinte
+        let mut file = vec![0u8; HEADER_SECTORS * SECTOR_SIZE];
+        for (index, compression, data) in [(32, COMPRESSION_NONE, &bytes), (33, COMPRESSION_GZIP, &gzip)] {
+            let offset = file.len() / SECTOR_SIZE;
+            let count = (data.len() + 5).div_ceil(SECTOR_SIZE);
+            file[index * 4..index * 4 + 4].copy_from_slice(&((offset as u32) << 8 | count as u32).to_be_bytes());
+            file.extend_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+            file.push(compression);
+            file.extend_from_slice(data);
+            file.resize((offset + count) * SECTOR_SIZE, 0);
+        }
+        fs::write(&path, &file).unwrap();
+
+        let mut region = RegionFile::open(&path).unwrap();
+        for x in [0, 1] {
+            let loaded = chunk_from_nbt(&region.read_chunk(x, 1).unwrap().unwrap()).unwrap();
+            assert_eq!(blocks(&loaded), blocks(&chunk));
+        }
+    }
+
+    #[test]
+    fn corrupt_headers_and_chunks_are_errors_not_panics() {
+        let fixture = Fixture::new("corrupt");
+        let path = fixture.0.join("r.0.0.mca");
+        let mut file = vec![0u8; 4 * SECTOR_SIZE];
+        // Entrada 0 aponta para fora do arquivo, entrada 1 para o cabeçalho
+        file[..4].copy_from_slice(&(50u32 << 8 | 1).to_be_bytes());
+        file[4..8].copy_from_slice(&1u32.to_be_bytes()); // Offset 0, um setor
+        // Entrada 2 tem tamanho maior que o setor; entrada 3 usa compressão desconhecida
+        file[8..12].copy_from_slice(&(2u32 << 8 | 1).to_be_bytes());
+        file[2 * SECTOR_SIZE..2 * SECTOR_SIZE + 4].copy_from_slice(&9000u32.to_be_bytes());
+        file[12..16].copy_from_slice(&(3u32 << 8 | 1).to_be_bytes());
+        file[3 * SECTOR_SIZE..3 * SECTOR_SIZE + 5].copy_from_slice(&[0, 0, 0, 1, 0x82]);
+        fs::write(&path, &file).unwrap();
+
+        let mut region = RegionFile::open(&path).unwrap();
+        assert!(!region.has_chunk(0, 0));
+        assert!(!region.has_chunk(1, 0));
+        assert!(matches!(region.read_chunk(2, 0), Err(AnvilError::InvalidChunk(_))));
+        assert!(matches!(region.read_chunk(3, 0), Err(AnvilError::UnsupportedCompression(0x82))));
+
+        let mut bad = Tag::compound();
+        bad.insert("xPos", 0i32);
+        assert!(chunk_from_nbt(&bad).is_err());
+    }
+
+    #[test]
+    fn heightmaps_use_highest_block() {
+        let mut chunk = Chunk::new(0, 0);
+        chunk.set_block(0, 64, 0, registry().id("minecraft:stone").unwrap());
+        chunk.set_block(1, 10, 0, registry().id("minecraft:oak_leaves").unwrap());
+
+        let tag = chunk_to_nbt(&chunk);
+        let heightmaps = tag.get("Heightmaps").unwrap();
+        let surface = heightmaps.get("WORLD_SURFACE").and_then(Tag::as_long_array).unwrap();
+        assert_eq!(surface.len(), 37);
+        // Pedra em y = 64 fica em y = 64 no vanilla, 128 blocos acima do fundo (-64): o vanilla
+        // lê 129 como "o primeiro bloco livre está em y = 65"
+        assert_eq!(surface[0] & 0x1FF, 129);
+        assert_eq!(surface[0] >> 9 & 0x1FF, 75);
+        assert_eq!(surface[0] >> 18 & 0x1FF, 0);
+        let motion_blocking = heightmaps.get("MOTION_BLOCKING").and_then(Tag::as_long_array).unwrap();
+        assert_eq!(motion_blocking[0] & 0x1FF, (64 + 1 - VANILLA_MIN_Y) as i64);
+    }
+
+    #[test]
+    fn block_states_keep_their_properties() {
+        let registry = registry();
+        let sideways = registry.state("minecraft:oak_log", &[("axis", "x")]).unwrap();
+        let mut chunk = Chunk::new(0, 0);
+        chunk.set_block(3, 5, 7, sideways);
+        chunk.set_block(4, 5, 7, registry.id("minecraft:oak_log").unwrap());
+
+        let tag = chunk_to_nbt(&chunk);
+        let section = &tag.get("sections").and_then(Tag::as_list).unwrap()[0];
+        let palette = section.get("block_states").and_then(|states| states.get("palette")).and_then(Tag::as_list).unwrap();
+        let axes: Vec<_> = palette
+            .iter()
+            .filter_map(|entry| entry.get("Properties")?.get("axis")?.as_str())
+            .collect();
+        assert_eq!(axes, vec!["x", "y"]);
+        // Blocos sem estado não gravam Properties, como no vanilla
+        assert!(palette[0].get("Properties").is_none());
+
+        assert_eq!(blocks(&chunk_from_nbt(&tag).unwrap()), blocks(&chunk));
+    }
+
+    #[test]
+    fn chunks_with_unknown_blocks_are_refused_and_kept_on_disk() {
+        let fixture = Fixture::new("unknown");
+        let mut chunk = Chunk::new(2, -3);
+        chunk.set_block(1, 1, 1, registry().id("minecraft:stone").unwrap());
+        // A pedra vira um bloco de mod que o registro não conhece
+        let text = nbt::snbt::to_snbt(&chunk_to_nbt(&chunk)).replace("minecraft:stone", "somemod:strange_ore");
+        let tag = nbt::snbt::parse(&text).unwrap();
+
+        let mut storage = RegionStorage::new(&fixture.0).unwrap();
+        storage.region(2, -3).unwrap().write_chunk(2, -3, &tag).unwrap();
+        match storage.load_chunk(2, -3) {
+            Err(AnvilError::UnknownBlock(name)) => assert_eq!(name, "somemod:strange_ore"),
+            other => panic!("esperava UnknownBlock, veio {:?}", other.map(|c| c.is_some())),
+        }
+
+        // O chunk regerado no lugar não sobrescreve o original
+        storage.save_chunk(&Chunk::new(2, -3)).unwrap();
+        assert_eq!(storage.region(2, -3).unwrap().read_chunk(2, -3).unwrap(), Some(tag));
+        assert!(storage.save_chunk(&Chunk::new(3, -3)).is_ok());
+        assert!(storage.load_chunk(3, -3).unwrap().is_some());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::game::blocks::{Block, BlockId};
+use super::light::{LightKind, LightSection};
+use super::palette::{PalettedContainer, CONTAINER_SIZE};
+
+pub const CHUNK_SIZE: i32 = 16;     // Largura/profundidade de uma coluna de chunk
+pub const SECTION_HEIGHT: i32 = 16; // Altura de cada seção
+pub const SECTION_COUNT: usize = 16;
+pub const WORLD_HEIGHT: i32 = SECTION_HEIGHT * SECTION_COUNT as i32;
+
+// Seção 16x16x16 guardada como paleta + bits empacotados
+#[derive(Clone)]
+pub struct Section {
+    blocks: PalettedContainer,
+    block_count: usize, // Blocos que não são ar
+}
+
+impl Section {
+    pub fn new() -> Self {
+        Self {
+            blocks: PalettedContainer::new(BlockId::AIR),
+            block_count: 0,
+        }
+    }
+
+    #[inline]
+    fn index(x: usize, y: usize, z: usize) -> usize {
+        (y * CHUNK_SIZE as usize + z) * CHUNK_SIZE as usize + x
+    }
+
+    // Seção a partir de uma paleta já carregada (ex.: de um arquivo de região)
+    pub fn from_blocks(blocks: PalettedContainer) -> Self {
+        let block_count = (0..CONTAINER_SIZE).filter(|&i| blocks.get(i) != BlockId::AIR).count();
+        Self { blocks, block_count }
+    }
+
+    pub fn blocks(&self) -> &PalettedContainer {
+        &self.blocks
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> BlockId {
+        self.blocks.get(Self::index(x, y, z))
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, id: BlockId) -> BlockId {
+        let old = self.blocks.set(Self::index(x, y, z), id);
+        if old != BlockId::AIR {
+            self.block_count -= 1;
+        }
+        if id != BlockId::AIR {
+            self.block_count += 1;
+        }
+        old
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.block_count == 0
+    }
+
+    pub fn compact(&mut self) {
+        self.blocks.compact();
+    }
+}
+
+impl Default for Section {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Coluna 16x16 dividida em seções de 16 de altura (alocadas sob demanda)
+#[derive(Clone)]
+pub struct Chunk {
+    pub x: i32,
+    pub z: i32,
+    sections: Vec<Option<Section>>,
+    light: Vec<Option<LightSection>>, // Seções sem luz alocada estão sob o céu aberto (céu 15, blocos 0)
+}
+
+impl Chunk {
+    pub fn new(x: i32, z: i32) -> Self {
+        Self {
+            x,
+            z,
+            sections: vec![None; SECTION_COUNT],
+            light: vec![None; SECTION_COUNT],
+        }
+    }
+
+    // Coordenadas locais: x/z em 0..16, y em 0..WORLD_HEIGHT
+    pub fn get_block(&self, x: usize, y: usize, z: usize) -> BlockId {
+        match self.sections.get(y / SECTION_HEIGHT as usize) {
+            Some(Some(section)) => section.get(x, y % SECTION_HEIGHT as usize, z),
+            _ => BlockId::AIR,
+        }
+    }
+
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, id: BlockId) -> BlockId {
+        let slot = &mut self.sections[y / SECTION_HEIGHT as usize];
+        if slot.is_none() {
+            if id == BlockId::AIR {
+                return BlockId::AIR;
+            }
+            *slot = Some(Section::new());
+        }
+
+        let section = slot.as_mut().unwrap();
+        let old = section.set(x, y % SECTION_HEIGHT as usize, z, id);
+        if section.is_empty() {
+            *slot = None;
+        }
+        old
+    }
+
+    // Nível de luz (0 a 15) em coordenadas locais
+    pub fn light(&self, x: usize, y: usize, z: usize, kind: LightKind) -> u8 {
+        let index = Section::index(x, y % SECTION_HEIGHT as usize, z);
+        match self.light.get(y / SECTION_HEIGHT as usize) {
+            Some(Some(light)) => light.get(index, kind),
+            _ => LightSection::open_sky(kind),
+        }
+    }
+
+    pub fn set_light(&mut self, x: usize, y: usize, z: usize, kind: LightKind, level: u8) {
+        let slot = &mut self.light[y / SECTION_HEIGHT as usize];
+        if slot.is_none() && level == LightSection::open_sky(kind) {
+            return;
+        }
+        let index = Section::index(x, y % SECTION_HEIGHT as usize, z);
+        slot.get_or_insert_with(LightSection::new).set(index, kind, level);
+    }
+
+    pub fn section(&self, index: usize) -> Option<&Section> {
+        self.sections.get(index)?.as_ref()
+    }
+
+    // Seções vazias não ficam alocadas
+    pub fn set_section(&mut self, index: usize, section: Section) {
+        self.sections[index] = (!section.is_empty()).then_some(section);
+    }
+
+    // Reduz as paletas depois de muitas edições (ex.: fim da geração)
+    pub fn compact(&mut self) {
+        for section in self.sections.iter_mut().flatten() {
+            section.compact();
+        }
+    }
+
+    // Blocos que não são ar, com coordenadas absolutas
+    pub fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        let (base_x, base_z) = (self.x * CHUNK_SIZE, self.z * CHUNK_SIZE);
+        self.sections
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.as_ref().map(|s| (i, s)))
+            .flat_map(move |(i, section)| {
+                (0..SECTION_HEIGHT as usize).flat_map(move |y| {
+                    (0..CHUNK_SIZE as usize).flat_map(move |z| {
+                        (0..CHUNK_SIZE as usize).filter_map(move |x| {
+                            let id = section.get(x, y, z);
+                            (id != BlockId::AIR).then(|| {
+                                let world_y = (i * SECTION_HEIGHT as usize + y) as i32;
+                                Block::new(id, base_x + x as i32, world_y, base_z + z as i32)
+                            })
+                        })
+                    })
+                })
+            })
+    }
+}
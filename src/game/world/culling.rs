@@ -0,0 +1,261 @@
+use std::collections::{HashSet, VecDeque};
+use glam::{Mat4, Vec3, Vec4};
+use crate::game::blocks::registry;
+use super::chunk::{CHUNK_SIZE, SECTION_COUNT, SECTION_HEIGHT};
+use super::{World, FACE_OFFSETS};
+
+const SIZE: usize = CHUNK_SIZE as usize; // Seções são cubos de 16
+
+// Os seis planos do volume de visão, com a normal para dentro
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    // Planos tirados das linhas de `projection * view` (Gribb & Hartmann), com z de -w a w do OpenGL
+    pub fn from_matrix(view_projection: Mat4) -> Self {
+        let [x, y, z, w] = [0, 1, 2, 3].map(|i| view_projection.row(i));
+        let planes = [w + x, w - x, w + y, w - y, w + z, w - z].map(|plane| plane / plane.truncate().length());
+        Self { planes }
+    }
+
+    // Teste conservador: só descarta a caixa inteira atrás de algum plano, usando o canto mais
+    // para dentro de cada um
+    pub fn intersects(&self, min: Vec3, max: Vec3) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            let inner = Vec3::select(normal.cmpge(Vec3::ZERO), max, min);
+            normal.dot(inner) + plane.w >= 0.0
+        })
+    }
+}
+
+// Caixa de uma seção nas coordenadas de desenho, onde o bloco b ocupa [b - 0.5, b + 0.5]
+pub fn section_bounds(chunk_x: i32, chunk_z: i32, section: usize) -> (Vec3, Vec3) {
+    let min = Vec3::new(
+        (chunk_x * CHUNK_SIZE) as f32,
+        (section as i32 * SECTION_HEIGHT) as f32,
+        (chunk_z * CHUNK_SIZE) as f32,
+    ) - Vec3::splat(0.5);
+    (min, min + Vec3::new(CHUNK_SIZE as f32, SECTION_HEIGHT as f32, CHUNK_SIZE as f32))
+}
+
+// Quais pares de faces de uma seção se ligam por blocos não opacos (o "VisGraph" do vanilla):
+// bit `a * 6 + b` para as faces `a` e `b`, na ordem de FACE_OFFSETS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SectionVisibility(u64);
+
+impl SectionVisibility {
+    pub const ALL: SectionVisibility = SectionVisibility((1 << 36) - 1);
+    pub const NONE: SectionVisibility = SectionVisibility(0);
+
+    pub fn connected(self, a: usize, b: usize) -> bool {
+        self.0 & (1 << (a * 6 + b)) != 0
+    }
+
+    fn connect(&mut self, faces: u8) {
+        for a in (0..6).filter(|a| faces & (1 << a) != 0) {
+            for b in (0..6).filter(|b| faces & (1 << b) != 0) {
+                self.0 |= 1 << (a * 6 + b);
+            }
+        }
+    }
+
+    // Flood fill pelos blocos não opacos da seção, juntando as faces que cada região toca
+    pub fn compute(world: &World, chunk_x: i32, chunk_z: i32, section: usize) -> Self {
+        if world.chunk(chunk_x, chunk_z).and_then(|chunk| chunk.section(section)).is_none() {
+            return Self::ALL; // Só ar
+        }
+        let registry = registry();
+        let base = [chunk_x * CHUNK_SIZE, section as i32 * SECTION_HEIGHT, chunk_z * CHUNK_SIZE];
+        let index = |[x, y, z]: [usize; 3]| (y * SIZE + z) * SIZE + x;
+        let mut blocked = [false; SIZE * SIZE * SIZE];
+        for y in 0..SIZE {
+            for z in 0..SIZE {
+                for x in 0..SIZE {
+                    let id = world.get_block(base[0] + x as i32, base[1] + y as i32, base[2] + z as i32);
+                    blocked[index([x, y, z])] = !registry.is_transparent(id);
+                }
+            }
+        }
+
+        let mut visibility = Self::NONE;
+        let mut queue = Vec::new();
+        for start in 0..blocked.len() {
+            if blocked[start] {
+                continue;
+            }
+            blocked[start] = true;
+            queue.push([start % SIZE, start / (SIZE * SIZE), (start / SIZE) % SIZE]);
+            let mut faces = 0u8;
+            while let Some(cell) = queue.pop() {
+                for (face, &(dx, dy, dz)) in FACE_OFFSETS.iter().enumerate() {
+                    let next = [cell[0] as i32 + dx, cell[1] as i32 + dy, cell[2] as i32 + dz];
+                    if next.iter().any(|&c| !(0..SIZE as i32).contains(&c)) {
+                        faces |= 1 << face; // Saiu pela face
+                        continue;
+                    }
+                    let next = next.map(|c| c as usize);
+                    if !blocked[index(next)] {
+                        blocked[index(next)] = true;
+                        queue.push(next);
+                    }
+                }
+            }
+            visibility.connect(faces);
+        }
+        visibility
+    }
+}
+
+// Seções do último quadro, para o título com F3
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CullingStats {
+    pub total: usize,   // Seções com malha
+    pub frustum: usize, // Fora do volume de visão
+    pub caves: usize,   // Escondidas pelo cave culling
+    pub drawn: usize,
+}
+
+// Cave culling do vanilla: busca em largura a partir da seção da câmera, passando de uma seção
+// para a vizinha só pelas faces ligadas à face por onde entrou e sem nunca voltar na direção da
+// câmera. `visibility` dá None para seções fora dos chunks carregados, onde a busca para.
+// Retorna as seções alcançadas, que são as que podem aparecer
+pub fn visible_sections(
+    camera: (i32, i32, i32),
+    visibility: impl Fn((i32, i32, usize)) -> Option<SectionVisibility>,
+) -> HashSet<(i32, i32, usize)> {
+    let mut visible = HashSet::new();
+    if !(0..SECTION_COUNT as i32).contains(&camera.1) {
+        return visible;
+    }
+    let start = (camera.0, camera.2, camera.1 as usize);
+    visible.insert(start);
+    // Seção, face por onde entrou e direções já andadas desde a câmera
+    let mut queue = VecDeque::from([(start, None::<usize>, 0u8)]);
+    while let Some(((x, z, y), entered, directions)) = queue.pop_front() {
+        let graph = if entered.is_some() { visibility((x, z, y)) } else { Some(SectionVisibility::ALL) };
+        let Some(graph) = graph else { continue };
+        for (face, &(dx, dy, dz)) in FACE_OFFSETS.iter().enumerate() {
+            let opposite = face ^ 1;
+            if directions & (1 << opposite) != 0 || entered.is_some_and(|from| !graph.connected(from, face)) {
+                continue;
+            }
+            let neighbor_y = y as i32 + dy;
+            if !(0..SECTION_COUNT as i32).contains(&neighbor_y) {
+                continue;
+            }
+            let neighbor = (x + dx, z + dz, neighbor_y as usize);
+            if visibility(neighbor).is_none() || !visible.insert(neighbor) {
+                continue;
+            }
+            queue.push_back((neighbor, Some(opposite), directions | (1 << face)));
+        }
+    }
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::blocks::BlockId;
+    use crate::game::world::chunk::Chunk;
+
+    fn stone() -> BlockId {
+        registry().id("minecraft:stone").unwrap()
+    }
+
+    // Seção 0 do chunk (0, 0) com pedra onde `solid` diz
+    fn section_with(solid: impl Fn(usize, usize, usize) -> bool) -> SectionVisibility {
+        let mut chunk = Chunk::new(0, 0);
+        for y in 0..SIZE {
+            for z in 0..SIZE {
+                for x in 0..SIZE {
+                    if solid(x, y, z) {
+                        chunk.set_block(x, y, z, stone());
+                    }
+                }
+            }
+        }
+        let mut world = World::new();
+        world.insert_chunk(chunk);
+        SectionVisibility::compute(&world, 0, 0, 0)
+    }
+
+    #[test]
+    fn sections_connect_faces_through_open_blocks() {
+        assert_eq!(section_with(|_, _, _| false), SectionVisibility::ALL);
+        assert_eq!(section_with(|_, _, _| true), SectionVisibility::NONE);
+
+        // Parede em x = 8: os lados -X e +X não se veem, o resto se liga pelos dois lados
+        let wall = section_with(|x, _, _| x == 8);
+        let (pos_z, pos_y, neg_y, neg_x, pos_x) = (0, 2, 3, 4, 5);
+        assert!(!wall.connected(neg_x, pos_x));
+        assert!(wall.connected(neg_x, pos_y) && wall.connected(pos_x, neg_y));
+        assert!(wall.connected(pos_y, neg_y) && wall.connected(pos_z, pos_z));
+
+        // Um túnel fechado no meio da pedra não toca face nenhuma
+        let pocket = section_with(|x, y, z| !(6..9).contains(&x) || !(6..9).contains(&y) || !(6..9).contains(&z));
+        assert_eq!(pocket, SectionVisibility::NONE);
+    }
+
+    #[test]
+    fn frustum_keeps_only_what_is_in_view() {
+        // Câmera na origem olhando para -Z
+        let projection = Mat4::perspective_rh_gl(45.0_f32.to_radians(), 4.0 / 3.0, 0.1, 100.0);
+        let view = Mat4::look_at_rh(Vec3::ZERO, -Vec3::Z, Vec3::Y);
+        let frustum = Frustum::from_matrix(projection * view);
+        let cube = |center: Vec3| frustum.intersects(center - Vec3::ONE, center + Vec3::ONE);
+
+        assert!(cube(Vec3::new(0.0, 0.0, -10.0)));
+        assert!(!cube(Vec3::new(0.0, 0.0, 10.0))); // Atrás
+        assert!(!cube(Vec3::new(0.0, 0.0, -150.0))); // Depois do plano de corte
+        assert!(!cube(Vec3::new(30.0, 0.0, -10.0))); // Fora do campo de visão
+        assert!(cube(Vec3::new(4.0, 0.0, -10.0)));
+        // Caixa enorme em volta da câmera sempre aparece
+        assert!(frustum.intersects(Vec3::splat(-500.0), Vec3::splat(500.0)));
+
+        let (min, max) = section_bounds(-1, 2, 3);
+        assert_eq!((min, max), (Vec3::new(-16.5, 47.5, 31.5), Vec3::new(-0.5, 63.5, 47.5)));
+    }
+
+    #[test]
+    fn cave_culling_stops_at_sealed_sections() {
+        // Chunks 0 a 3 em x; as seções do chunk 1 são pedra maciça
+        let loaded = |(x, z, _): (i32, i32, usize)| (0..4).contains(&x) && z == 0;
+        let open = visible_sections((0, 5, 0), |key| loaded(key).then_some(SectionVisibility::ALL));
+        assert_eq!(open.len(), 4 * SECTION_COUNT);
+
+        let sealed = visible_sections((0, 5, 0), |key| {
+            let (x, _, _) = key;
+            loaded(key).then_some(if x == 1 { SectionVisibility::NONE } else { SectionVisibility::ALL })
+        });
+        // As seções de pedra aparecem (a face delas está à vista), o que está atrás não
+        assert!(sealed.contains(&(1, 0, 5)) && sealed.contains(&(1, 0, 0)));
+        assert!(!sealed.iter().any(|&(x, _, _)| x >= 2));
+
+        // Câmera acima do mundo: a busca não começa
+        assert!(visible_sections((0, 16, 0), |_| Some(SectionVisibility::ALL)).is_empty());
+    }
+
+    #[test]
+    fn cave_culling_never_turns_back_toward_the_camera() {
+        // Corredor em L: só se liga -X com +Z. Entrando pela -X a busca vira para +Z, e dali não
+        // pode voltar para -Z nem para -X
+        let (pos_z, neg_x) = (0, 4);
+        let mut corner = SectionVisibility::NONE;
+        corner.connect((1 << neg_x) | (1 << pos_z));
+        let visible = visible_sections((0, 0, 0), |(x, z, y)| {
+            ((0..3).contains(&x) && (-2..3).contains(&z) && y == 0).then_some(if (x, z) == (1, 0) {
+                corner
+            } else {
+                SectionVisibility::ALL
+            })
+        });
+        assert!(visible.contains(&(1, 1, 0)) && visible.contains(&(1, 2, 0)));
+        assert!(!visible.contains(&(2, 0, 0)));
+        // (1, -1) é alcançável direto da câmera, mas (2, -1) só voltando: fica de fora
+        assert!(visible.contains(&(1, -1, 0)));
+    }
+}
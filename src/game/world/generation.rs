@@ -1,12 +1,47 @@
-use noise::{Fbm, MultiFractal, NoiseFn, Perlin, Seedable};
-use rand::{Rng, thread_rng};
-use crate::game::blocks::Block;
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::game::blocks::{registry, BlockId};
+use crate::game::world::chunk::{Chunk, CHUNK_SIZE, WORLD_HEIGHT};
 
 const SEA_LEVEL: i32 = 62;
 const TERRAIN_SCALE: f64 = 300.0;
 const MOUNTAIN_SCALE: f64 = 1000.0;
 const TREE_CHANCE: f64 = 0.015;
 
+// Separam os fluxos aleatórios derivados do mesmo seed
+const FEATURE_SALT: u64 = 0x7472_6565; // "tree"
+const SPAWN_SALT: u64 = 0x7370_6177;   // "spaw"
+
+// Ids resolvidos uma vez por geração, evitando buscas por nome nos laços
+struct TerrainBlocks {
+    stone: BlockId,
+    dirt: BlockId,
+    grass: BlockId,
+    sand: BlockId,
+    water: BlockId,
+    bedrock: BlockId,
+    oak_log: BlockId,
+    oak_leaves: BlockId,
+}
+
+impl TerrainBlocks {
+    fn resolve() -> Self {
+        let registry = registry();
+        let id = |name: &str| registry.id(name).unwrap_or(BlockId::AIR);
+        Self {
+            stone: id("minecraft:stone"),
+            dirt: id("minecraft:dirt"),
+            grass: id("minecraft:grass_block"),
+            sand: id("minecraft:sand"),
+            water: id("minecraft:water"),
+            bedrock: id("minecraft:bedrock"),
+            oak_log: id("minecraft:oak_log"),
+            oak_leaves: id("minecraft:oak_leaves"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum Biome {
     Desert,
@@ -15,38 +50,127 @@ enum Biome {
     Ocean,
 }
 
-// Retorna os blocos e a coordenada de spawn (x, y, z)
-pub fn assembly(size_x: i32, size_z: i32) -> (Vec<Block>, (i32, i32, i32)) {
-    let mut blocks = Vec::with_capacity((size_x * size_z * 128) as usize);
-    let rng = thread_rng();
-    
-    let (terrain_noise, mountain_noise, biome_noise) = init_noise_generators(rng);
-    let noise_maps = precompute_noise(size_x, size_z, &terrain_noise, &mountain_noise, &biome_noise);
-    
-    // Mapa temporário para rastrear alturas da superfície
-    let mut surface_map = vec![None; (size_x * size_z) as usize];
-    
-    for x in 0..size_x {
-        for z in 0..size_z {
-            let idx = (x * size_z + z) as usize;
-            let (base_height, mountain_height, biome_value) = noise_maps[idx];
-            
-            let (height, biome) = determine_biome_and_height(base_height, mountain_height, biome_value);
-            generate_column(x, z, height, biome.clone(), &mut blocks);
-            
-            // Registra a altura da superfície para spawn
+// Gerador determinístico: toda escolha aleatória deriva do seed. O ChaCha8 tem algoritmo fixo,
+// ao contrário do StdRng, então o mesmo seed gera o mesmo mundo em qualquer versão do rand
+pub struct WorldGenerator {
+    seed: u64,
+    ids: TerrainBlocks,
+    terrain_noise: Fbm<Perlin>,
+    mountain_noise: Fbm<Perlin>,
+    biome_noise: Perlin,
+}
+
+impl WorldGenerator {
+    pub fn new(seed: u64) -> Self {
+        let (terrain_noise, mountain_noise, biome_noise) = init_noise_generators(ChaCha8Rng::seed_from_u64(seed));
+        Self {
+            seed,
+            ids: TerrainBlocks::resolve(),
+            terrain_noise,
+            mountain_noise,
+            biome_noise,
+        }
+    }
+
+    // RNG próprio de cada chunk: o resultado não depende da ordem de geração
+    fn chunk_rng(&self, chunk_x: i32, chunk_z: i32, salt: u64) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(mix_seed(self.seed, chunk_x, chunk_z, salt))
+    }
+
+    // Altura e bioma de uma coluna em coordenadas absolutas
+    fn column(&self, x: i32, z: i32) -> (i32, Biome) {
+        let (base_height, mountain_height, biome_value) =
+            sample_noise(x, z, &self.terrain_noise, &self.mountain_noise, &self.biome_noise);
+        determine_biome_and_height(base_height, mountain_height, biome_value)
+    }
+
+    // Gera um chunk sozinho, sem depender de nenhum outro já gerado
+    pub fn generate_chunk(&self, chunk_x: i32, chunk_z: i32) -> Chunk {
+        let mut chunk = Chunk::new(chunk_x, chunk_z);
+        let noise_maps = precompute_noise(chunk_x, chunk_z, &self.terrain_noise, &self.mountain_noise, &self.biome_noise);
+
+        for lx in 0..CHUNK_SIZE {
+            for lz in 0..CHUNK_SIZE {
+                let (base_height, mountain_height, biome_value) = noise_maps[(lx * CHUNK_SIZE + lz) as usize];
+                let (height, biome) = determine_biome_and_height(base_height, mountain_height, biome_value);
+                let (x, z) = (chunk_x * CHUNK_SIZE + lx, chunk_z * CHUNK_SIZE + lz);
+                generate_column(x, z, height, biome, &self.ids, &mut chunk);
+            }
+        }
+
+        self.generate_features(&mut chunk);
+        chunk.compact();
+        chunk
+    }
+
+    fn generate_features(&self, chunk: &mut Chunk) {
+        // Copas de árvores dos chunks vizinhos podem invadir este chunk,
+        // então as decisões deles são refeitas e só os blocos internos são colocados
+        for chunk_x in chunk.x - 1..=chunk.x + 1 {
+            for chunk_z in chunk.z - 1..=chunk.z + 1 {
+                let mut rng = self.chunk_rng(chunk_x, chunk_z, FEATURE_SALT);
+
+                for lx in 0..CHUNK_SIZE {
+                    for lz in 0..CHUNK_SIZE {
+                        if !rng.gen_bool(TREE_CHANCE) {
+                            continue;
+                        }
+                        // Cada árvore tem seu próprio seed, então o consumo do RNG do chunk é fixo
+                        let mut tree_rng = ChaCha8Rng::seed_from_u64(rng.gen());
+
+                        let (x, z) = (chunk_x * CHUNK_SIZE + lx, chunk_z * CHUNK_SIZE + lz);
+                        let (surface_y, _) = self.column(x, z);
+                        generate_tree(x, z, surface_y, &self.ids, chunk, &mut tree_rng);
+                    }
+                }
+            }
+        }
+    }
+
+    // Procura um ponto de spawn seguro no chunk de origem
+    pub fn find_spawn_point(&self) -> (i32, i32, i32) {
+        let mut rng = self.chunk_rng(0, 0, SPAWN_SALT);
+        let chunk = self.generate_chunk(0, 0);
+
+        // Tenta encontrar um ponto em Plains ou Desert com espaço vazio acima
+        for _ in 0..100 { // Limite de tentativas para evitar loop infinito
+            let x = rng.gen_range(0..CHUNK_SIZE);
+            let z = rng.gen_range(0..CHUNK_SIZE);
+            let (surface_height, biome) = self.column(x, z);
+
             if matches!(biome, Biome::Plains | Biome::Desert) {
-                surface_map[idx] = Some(height);
+                // Verifica se o espaço acima da superfície está 100% vazio (2 blocos de altura para o jogador)
+                let spawn_y = surface_height + 1; // Um bloco acima da superfície
+                let is_space_clear = (spawn_y..=spawn_y + 1)
+                    .all(|y| !registry().is_solid(chunk.get_block(x as usize, y as usize, z as usize))); // Considera apenas blocos sólidos como obstáculos
+
+                if is_space_clear {
+                    // Spawn dois blocos acima da superfície para garantir que o jogador esteja acima da última camada
+                    return (x, spawn_y + 1, z); // +1 para ficar acima do chão, totalizando 2 blocos acima da superfície
+                }
             }
         }
+
+        // Fallback: centro do chunk, garantindo espaço vazio
+        let fallback = CHUNK_SIZE / 2;
+        let (surface_height, _) = self.column(fallback, fallback);
+        (fallback, surface_height.max(SEA_LEVEL) + 2, fallback) // 2 blocos acima como fallback
     }
-    
-    generate_features(size_x, size_z, &mut blocks);
-    
-    // Escolhe uma coordenada de spawn segura
-    let spawn_point = find_spawn_point(&surface_map, size_x, size_z, &blocks);
-    
-    (blocks, spawn_point)
+}
+
+// Mistura seed e coordenadas (finalizador do SplitMix64)
+fn mix_seed(seed: u64, x: i32, z: i32, salt: u64) -> u64 {
+    let mut h = seed ^ salt.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    h = splitmix64(h ^ (x as u32 as u64).wrapping_mul(0xBF58_476D_1CE4_E5B9));
+    splitmix64(h ^ (z as u32 as u64).wrapping_mul(0x94D0_49BB_1331_11EB))
+}
+
+#[inline]
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
 }
 
 fn init_noise_generators(mut rng: impl Rng) -> (Fbm<Perlin>, Fbm<Perlin>, Perlin) {
@@ -63,22 +187,33 @@ fn init_noise_generators(mut rng: impl Rng) -> (Fbm<Perlin>, Fbm<Perlin>, Perlin
     )
 }
 
-fn precompute_noise(size_x: i32, size_z: i32, 
+#[inline]
+fn sample_noise(x: i32, z: i32,
+    terrain: &Fbm<Perlin>,
+    mountain: &Fbm<Perlin>,
+    biome: &Perlin) -> (i32, i32, f64) {
+
+    let (nx, nz) = (x as f64, z as f64);
+    (
+        (terrain.get([nx, nz]) * 32.0 + SEA_LEVEL as f64) as i32,
+        (mountain.get([nx, nz]).abs() * 80.0) as i32,
+        biome.get([nx, nz])
+    )
+}
+
+// Ruído das 16x16 colunas de um chunk, em coordenadas absolutas
+fn precompute_noise(chunk_x: i32, chunk_z: i32, 
     terrain: &Fbm<Perlin>, 
     mountain: &Fbm<Perlin>, 
     biome: &Perlin) -> Vec<(i32, i32, f64)> {
     
-    let mut noise_values = Vec::with_capacity((size_x * size_z) as usize);
+    let mut noise_values = Vec::with_capacity((CHUNK_SIZE * CHUNK_SIZE) as usize);
     
-    for x in 0..size_x {
-        let nx = x as f64;
-        for z in 0..size_z {
-            let nz = z as f64;
-            noise_values.push((
-                (terrain.get([nx, nz]) * 32.0 + SEA_LEVEL as f64) as i32,
-                (mountain.get([nx, nz]).abs() * 80.0) as i32,
-                biome.get([nx, nz])
-            ));
+    for lx in 0..CHUNK_SIZE {
+        let x = chunk_x * CHUNK_SIZE + lx;
+        for lz in 0..CHUNK_SIZE {
+            let z = chunk_z * CHUNK_SIZE + lz;
+            noise_values.push(sample_noise(x, z, terrain, mountain, biome));
         }
     }
     noise_values
@@ -94,13 +229,27 @@ fn determine_biome_and_height(base: i32, mountain: i32, biome_value: f64) -> (i3
     }
 }
 
+// Coloca um bloco dado em coordenadas absolutas; ignora o que cai fora do chunk
+#[inline]
+fn place_block(chunk: &mut Chunk, x: i32, y: i32, z: i32, id: BlockId, replace: bool) {
+    let (lx, lz) = (x - chunk.x * CHUNK_SIZE, z - chunk.z * CHUNK_SIZE);
+    if !(0..CHUNK_SIZE).contains(&lx) || !(0..CHUNK_SIZE).contains(&lz) || !(0..WORLD_HEIGHT).contains(&y) {
+        return;
+    }
+
+    let (lx, y, lz) = (lx as usize, y as usize, lz as usize);
+    if replace || chunk.get_block(lx, y, lz) == BlockId::AIR {
+        chunk.set_block(lx, y, lz, id);
+    }
+}
+
 #[inline]
-fn generate_column(x: i32, z: i32, height: i32, biome: Biome, blocks: &mut Vec<Block>) {
+fn generate_column(x: i32, z: i32, height: i32, biome: Biome, ids: &TerrainBlocks, chunk: &mut Chunk) {
     let (surface, subsurface) = match biome {
-        Biome::Desert => ("minecraft:sand", "minecraft:sand"),
-        Biome::Plains => ("minecraft:grass_block", "minecraft:dirt"),
-        Biome::Mountains => ("minecraft:stone", "minecraft:stone"),
-        Biome::Ocean => ("minecraft:sand", "minecraft:dirt"),
+        Biome::Desert => (ids.sand, ids.sand),
+        Biome::Plains => (ids.grass, ids.dirt),
+        Biome::Mountains => (ids.stone, ids.stone),
+        Biome::Ocean => (ids.sand, ids.dirt),
     };
 
     for y in 1..=height {
@@ -109,91 +258,144 @@ fn generate_column(x: i32, z: i32, height: i32, biome: Biome, blocks: &mut Vec<B
         } else if y > height - 3 {
             subsurface
         } else {
-            "minecraft:stone"
+            ids.stone
         };
-        blocks.push(Block::new(block_type, x, y, z));
+        place_block(chunk, x, y, z, block_type, true);
     }
 
     if biome == Biome::Ocean && height < SEA_LEVEL {
-        blocks.extend((height + 1..=SEA_LEVEL).map(|y| 
-            Block::new("minecraft:water", x, y, z)
-        ));
-    }
-    
-    blocks.push(Block::new("minecraft:bedrock", x, 0, z));
-}
-
-fn generate_features(size_x: i32, size_z: i32, blocks: &mut Vec<Block>) {
-    let mut rng = thread_rng();
-    let mut surface_map = vec![None; (size_x * size_z) as usize];
-
-    for block in blocks.iter() {
-        if block.id == "minecraft:grass_block" || block.id == "minecraft:sand" || block.id == "minecraft:stone" {
-            let idx = (block.x * size_z + block.z) as usize;
-            surface_map[idx] = Some(block.y);
-        }
-    }
-
-    for x in 0..size_x {
-        for z in 0..size_z {
-            if rng.gen_bool(TREE_CHANCE) {
-                if let Some(surface_y) = surface_map[(x * size_z + z) as usize] {
-                    generate_tree(x, z, surface_y, blocks, &mut rng);
-                }
-            }
+        for y in height + 1..=SEA_LEVEL {
+            place_block(chunk, x, y, z, ids.water, true);
         }
     }
+    
+    place_block(chunk, x, 0, z, ids.bedrock, true);
 }
 
 #[inline]
-fn generate_tree(x: i32, z: i32, surface_y: i32, blocks: &mut Vec<Block>, rng: &mut impl Rng) {
+fn generate_tree(x: i32, z: i32, surface_y: i32, ids: &TerrainBlocks, chunk: &mut Chunk, rng: &mut impl Rng) {
     let trunk_height = rng.gen_range(4..7);
-    blocks.extend((surface_y + 1..=surface_y + trunk_height)
-        .map(|y| Block::new("minecraft:oak_log", x, y, z)));
+    for y in surface_y + 1..=surface_y + trunk_height {
+        place_block(chunk, x, y, z, ids.oak_log, true);
+    }
 
     let crown_y = surface_y + trunk_height;
     for dx in -2i32..=2i32 {
         for dz in -2i32..=2i32 {
             for dy in -1i32..=1i32 {
+                // O RNG é consumido mesmo fora do chunk para manter a árvore igual nos vizinhos;
+                // folhas não substituem o tronco
                 if (dx.abs() != 2 || dz.abs() != 2) && rng.gen_bool(0.7) {
-                    blocks.push(Block::new("minecraft:oak_leaves", 
-                        x + dx, 
-                        crown_y + dy, 
-                        z + dz));
+                    place_block(chunk, x + dx, crown_y + dy, z + dz, ids.oak_leaves, false);
                 }
             }
         }
     }
 }
 
-// Função para encontrar um ponto de spawn seguro
-fn find_spawn_point(surface_map: &[Option<i32>], size_x: i32, size_z: i32, blocks: &[Block]) -> (i32, i32, i32) {
-    let mut rng = thread_rng();
-    
-    // Tenta encontrar um ponto em Plains ou Desert com espaço vazio acima
-    for _ in 0..100 { // Limite de tentativas para evitar loop infinito
-        let x = rng.gen_range(0..size_x);
-        let z = rng.gen_range(0..size_z);
-        let idx = (x * size_z + z) as usize;
-        
-        if let Some(surface_height) = surface_map[idx] {
-            // Verifica se o espaço acima da superfície está 100% vazio (2 blocos de altura para o jogador)
-            let spawn_y = surface_height + 1; // Um bloco acima da superfície
-            let is_space_clear = blocks.iter()
-                .filter(|b| b.x == x && b.z == z && b.y >= spawn_y && b.y <= spawn_y + 1)
-                .all(|b| b.id == "minecraft:water" || b.id == "minecraft:air"); // Considera apenas blocos sólidos como obstáculos
-
-            if is_space_clear {
-                // Spawn dois blocos acima da superfície para garantir que o jogador esteja acima da última camada
-                return (x, spawn_y + 1, z); // +1 para ficar acima do chão, totalizando 2 blocos acima da superfície
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(chunk: &Chunk) -> Vec<(i32, i32, i32, BlockId)> {
+        chunk.blocks().map(|b| (b.x, b.y, b.z, b.id)).collect()
+    }
+
+    #[test]
+    fn same_seed_generates_same_chunks_in_any_order() {
+        let coords = [(0, 0), (1, 0), (-1, 2), (3, -4)];
+
+        let generator = WorldGenerator::new(42);
+        let forward: Vec<_> = coords.iter().map(|&(x, z)| snapshot(&generator.generate_chunk(x, z))).collect();
+
+        let generator = WorldGenerator::new(42);
+        let mut backward: Vec<_> = coords.iter().rev().map(|&(x, z)| snapshot(&generator.generate_chunk(x, z))).collect();
+        backward.reverse();
+
+        assert_eq!(forward, backward);
+        assert_eq!(generator.find_spawn_point(), WorldGenerator::new(42).find_spawn_point());
+    }
+
+    #[test]
+    fn chunk_rng_depends_only_on_coordinates() {
+        let generator = WorldGenerator::new(7);
+        let first: u64 = generator.chunk_rng(3, -2, FEATURE_SALT).gen();
+        // Gera outros chunks antes para garantir que a ordem não importa
+        let _: u64 = generator.chunk_rng(0, 0, FEATURE_SALT).gen();
+        let again: u64 = generator.chunk_rng(3, -2, FEATURE_SALT).gen();
+        assert_eq!(first, again);
+        assert_ne!(first, generator.chunk_rng(-2, 3, FEATURE_SALT).gen::<u64>());
+
+        // Vizinhos, outro seed ou outro salt não repetem o fluxo
+        let stream = |generator: &WorldGenerator, x, z, salt| -> Vec<u64> {
+            let mut rng = generator.chunk_rng(x, z, salt);
+            (0..8).map(|_| rng.gen()).collect()
+        };
+        let center = stream(&generator, 3, -2, FEATURE_SALT);
+        for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1)] {
+            let neighbor = stream(&generator, 3 + dx, -2 + dz, FEATURE_SALT);
+            assert!(center.iter().all(|value| !neighbor.contains(value)));
         }
+        assert_ne!(center, stream(&WorldGenerator::new(8), 3, -2, FEATURE_SALT));
+        assert_ne!(center, stream(&generator, 3, -2, SPAWN_SALT));
     }
-    
-    // Fallback: centro do mundo, altura média, garantindo espaço vazio
-    let fallback_x = size_x / 2;
-    let fallback_z = size_z / 2;
-    let idx = (fallback_x * size_z + fallback_z) as usize;
-    let surface_height = surface_map[idx].unwrap_or(SEA_LEVEL);
-    (fallback_x, surface_height + 2, fallback_z) // 2 blocos acima como fallback
-}
\ No newline at end of file
+
+    #[test]
+    fn different_seeds_generate_different_worlds() {
+        let first = WorldGenerator::new(7);
+        let second = WorldGenerator::new(8);
+        assert_ne!(snapshot(&first.generate_chunk(0, 0)), snapshot(&second.generate_chunk(0, 0)));
+
+        // As árvores também mudam de lugar, e chunks vizinhos não repetem o mesmo padrão
+        let oak_log = registry().id("minecraft:oak_log").unwrap();
+        let trees = |generator: &WorldGenerator| -> Vec<Vec<(i32, i32)>> {
+            (0..8)
+                .map(|chunk_x| {
+                    let mut columns: Vec<_> = generator
+                        .generate_chunk(chunk_x, 0)
+                        .blocks()
+                        .filter(|b| b.id == oak_log)
+                        .map(|b| (b.x.rem_euclid(CHUNK_SIZE), b.z))
+                        .collect();
+                    columns.sort_unstable();
+                    columns.dedup();
+                    columns
+                })
+                .collect()
+        };
+        let strip = trees(&first);
+        assert!(strip.iter().any(|columns| !columns.is_empty()));
+        assert_ne!(strip, trees(&second));
+        for pair in strip.windows(2).filter(|pair| !pair[0].is_empty()) {
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn chunks_use_absolute_coordinates() {
+        let generator = WorldGenerator::new(1);
+        let chunk = generator.generate_chunk(-3, 5);
+        assert!(chunk.blocks().all(|b| (-48..-32).contains(&b.x) && (80..96).contains(&b.z)));
+        // Toda coluna tem bedrock na base
+        assert_eq!(chunk.blocks().filter(|b| b.y == 0).count(), 256);
+    }
+
+    // Valores fixos do seed 42: mudar o RNG ou a geração muda mundos já salvos, e isso tem que
+    // aparecer aqui
+    #[test]
+    fn seed_generates_known_blocks() {
+        let generator = WorldGenerator::new(42);
+        assert_eq!(generator.chunk_rng(0, 0, FEATURE_SALT).gen::<u64>(), 0xf960_0507_f534_dd76);
+        assert_eq!(generator.find_spawn_point(), (7, 73, 4));
+
+        let chunk = generator.generate_chunk(0, 0);
+        let name = |x, y, z| registry().get(chunk.get_block(x, y, z)).name.as_str();
+        assert_eq!(name(0, 0, 0), "minecraft:bedrock");
+        assert_eq!(name(0, 72, 0), "minecraft:grass_block");
+        assert_eq!(name(0, 73, 0), "minecraft:air");
+        assert_eq!(name(5, 70, 9), "minecraft:grass_block");
+        assert_eq!(name(8, 70, 8), "minecraft:grass_block");
+        assert_eq!(name(15, 77, 3), "minecraft:oak_leaves");
+        assert_eq!(name(3, 71, 13), "minecraft:oak_log");
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use glam::Vec3;
+use crate::game::inventory::Inventory;
+use crate::nbt::compression::{read_compressed, write_compressed};
+use crate::nbt::{Compression, NbtError, Tag};
+use super::anvil::DATA_VERSION;
+
+const LEVEL_FILE: &str = "level.dat";
+pub const TICKS_PER_SECOND: i64 = 20;
+pub const DAY_LENGTH: i64 = 24000; // Ticks de um dia; 0 é o nascer do sol, 6000 o meio-dia
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl GameMode {
+    // Ids do campo GameType do level.dat
+    pub fn id(self) -> i32 {
+        match self {
+            GameMode::Survival => 0,
+            GameMode::Creative => 1,
+            GameMode::Adventure => 2,
+            GameMode::Spectator => 3,
+        }
+    }
+
+    pub fn from_id(id: i64) -> Option<Self> {
+        match id {
+            0 => Some(GameMode::Survival),
+            1 => Some(GameMode::Creative),
+            2 => Some(GameMode::Adventure),
+            3 => Some(GameMode::Spectator),
+            _ => None,
+        }
+    }
+
+    // Próximo modo na ordem dos ids, para trocar durante o jogo
+    pub fn next(self) -> Self {
+        Self::from_id((self.id() as i64 + 1) % 4).unwrap_or_default()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Survival => "Survival",
+            GameMode::Creative => "Creative",
+            GameMode::Adventure => "Adventure",
+            GameMode::Spectator => "Spectator",
+        }
+    }
+
+    // Vida, dano de queda e consumo de itens só valem nos modos "de sobrevivência"
+    pub fn is_survival_like(self) -> bool {
+        matches!(self, GameMode::Survival | GameMode::Adventure)
+    }
+
+    // Aventura e espectador não quebram nem colocam blocos
+    pub fn can_edit_blocks(self) -> bool {
+        matches!(self, GameMode::Survival | GameMode::Creative)
+    }
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    Nbt(NbtError),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "level.dat I/O error: {}", err),
+            LevelError::Nbt(err) => write!(f, "invalid level.dat NBT: {}", err),
+            LevelError::Invalid(reason) => write!(f, "invalid level.dat: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<io::Error> for LevelError {
+    fn from(err: io::Error) -> Self {
+        LevelError::Io(err)
+    }
+}
+
+impl From<NbtError> for LevelError {
+    fn from(err: NbtError) -> Self {
+        LevelError::Nbt(err)
+    }
+}
+
+// Posição e orientação do jogador, nas convenções do `Player` (yaw -90 olha para -Z, pitch positivo para cima)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerData {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub inventory: Inventory,
+    pub game_mode: GameMode,
+    pub health: f32,
+}
+
+// Estado do mundo salvo em level.dat (NBT com gzip), no layout do Minecraft Java
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelData {
+    pub name: String,
+    pub seed: u64,
+    pub spawn: (i32, i32, i32),
+    pub time: i64,     // Ticks desde a criação do mundo
+    pub day_time: i64, // Hora do dia; para de andar com doDaylightCycle falso
+    pub game_mode: GameMode,
+    pub generator: String, // Preset do gerador do overworld
+    pub game_rules: BTreeMap<String, String>, // Valores em texto, como no vanilla
+    pub player: Option<PlayerData>, // Ausente até o primeiro save
+}
+
+impl LevelData {
+    pub fn new(seed: u64, spawn: (i32, i32, i32)) -> Self {
+        let game_rules = [
+            ("doDaylightCycle", "true"),
+            ("doMobSpawning", "true"),
+            ("keepInventory", "false"),
+            ("randomTickSpeed", "3"),
+        ]
+        .into_iter()
+        .map(|(rule, value)| (rule.to_string(), value.to_string()))
+        .collect();
+
+        Self {
+            name: "world".to_string(),
+            seed,
+            spawn,
+            time: 0,
+            day_time: 0,
+            game_mode: GameMode::Survival,
+            generator: "minecraft:overworld".to_string(),
+            game_rules,
+            player: None,
+        }
+    }
+
+    // Regras ausentes ou com valor diferente de "true" contam como falsas
+    pub fn game_rule(&self, rule: &str) -> bool {
+        self.game_rules.get(rule).is_some_and(|value| value == "true")
+    }
+
+    // Avança um tick de jogo (20 por segundo)
+    pub fn tick(&mut self) {
+        self.time += 1;
+        if self.game_rule("doDaylightCycle") {
+            self.day_time += 1;
+        }
+    }
+
+    pub fn time_of_day(&self) -> i64 {
+        self.day_time.rem_euclid(DAY_LENGTH)
+    }
+
+    // Avança até a próxima vez que o dia chega em `time` (como dormir até o amanhecer), sem
+    // voltar no tempo: as fases da lua seguem contando
+    pub fn set_time_of_day(&mut self, time: i64) {
+        let time = time.rem_euclid(DAY_LENGTH);
+        let mut day_time = self.day_time - self.time_of_day() + time;
+        if day_time < self.day_time {
+            day_time += DAY_LENGTH;
+        }
+        self.day_time = day_time;
+    }
+
+    // `None` se o mundo ainda não tem level.dat
+    pub fn load(world_dir: &Path) -> Result<Option<Self>, LevelError> {
+        let bytes = match fs::read(world_dir.join(LEVEL_FILE)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let (_, root) = read_compressed(&bytes)?;
+        Self::from_nbt(&root).map(Some)
+    }
+
+    // Grava num arquivo novo e só então troca, mantendo o anterior como level.dat_old
+    pub fn save(&self, world_dir: &Path) -> Result<(), LevelError> {
+        fs::create_dir_all(world_dir)?;
+        let bytes = write_compressed("", &self.to_nbt(), Compression::Gzip)?;
+        let path = world_dir.join(LEVEL_FILE);
+        let new_path = world_dir.join("level.dat_new");
+        fs::write(&new_path, bytes)?;
+        if path.exists() {
+            fs::rename(&path, world_dir.join("level.dat_old"))?;
+        }
+        fs::rename(&new_path, &path)?;
+        Ok(())
+    }
+
+    pub fn to_nbt(&self) -> Tag {
+        let mut rules = Tag::compound();
+        for (rule, value) in &self.game_rules {
+            rules.insert(rule, value.as_str());
+        }
+
+        let mut generator = Tag::compound();
+        generator
+            .insert("type", "minecraft:noise")
+            .insert("settings", self.generator.as_str());
+        let mut overworld = Tag::compound();
+        overworld.insert("type", "minecraft:overworld").insert("generator", generator);
+        let mut dimensions = Tag::compound();
+        dimensions.insert("minecraft:overworld", overworld);
+        let mut world_gen = Tag::compound();
+        world_gen
+            .insert("seed", self.seed as i64)
+            .insert("generate_features", 1i8)
+            .insert("bonus_chest", 0i8)
+            .insert("dimensions", dimensions);
+
+        let mut data = Tag::compound();
+        data.insert("DataVersion", DATA_VERSION)
+            .insert("version", 19133i32) // Formato Anvil
+            .insert("LevelName", self.name.as_str())
+            .insert("GameType", self.game_mode.id())
+            .insert("SpawnX", self.spawn.0)
+            .insert("SpawnY", self.spawn.1)
+            .insert("SpawnZ", self.spawn.2)
+            .insert("Time", self.time)
+            .insert("DayTime", self.day_time)
+            .insert("GameRules", rules)
+            .insert("WorldGenSettings", world_gen);
+
+        if let Some(player) = &self.player {
+            // O vanilla mede o yaw a partir de +Z e o pitch positivo olha para baixo
+            let position = player.position.to_array().map(|v| Tag::Double(v as f64));
+            let rotation = vec![Tag::Float(player.yaw - 90.0), Tag::Float(-player.pitch)];
+            let mut tag = Tag::compound();
+            tag.insert("Pos", Tag::List(position.to_vec()))
+                .insert("Rotation", Tag::List(rotation))
+                .insert("Inventory", player.inventory.to_nbt())
+                .insert("Health", player.health)
+                .insert("playerGameType", player.game_mode.id());
+            data.insert("Player", tag);
+        }
+
+        let mut root = Tag::compound();
+        root.insert("Data", data);
+        root
+    }
+
+    pub fn from_nbt(root: &Tag) -> Result<Self, LevelError> {
+        let data = root.get("Data").ok_or(LevelError::Invalid("missing Data compound"))?;
+        let int = |key| data.get(key).and_then(Tag::as_i64);
+        let seed = data
+            .get("WorldGenSettings")
+            .and_then(|settings| settings.get("seed"))
+            .and_then(Tag::as_i64)
+            .ok_or(LevelError::Invalid("missing WorldGenSettings.seed"))?;
+        let spawn = match (int("SpawnX"), int("SpawnY"), int("SpawnZ")) {
+            (Some(x), Some(y), Some(z)) => (x as i32, y as i32, z as i32),
+            _ => return Err(LevelError::Invalid("missing spawn position")),
+        };
+
+        let mut level = Self::new(seed as u64, spawn);
+        if let Some(name) = data.get("LevelName").and_then(Tag::as_str) {
+            level.name = name.to_string();
+        }
+        level.time = int("Time").unwrap_or(0);
+        level.day_time = int("DayTime").unwrap_or(0);
+        level.game_mode = int("GameType").and_then(GameMode::from_id).unwrap_or_default();
+        if let Some(settings) = data
+            .get("WorldGenSettings")
+            .and_then(|settings| settings.get("dimensions"))
+            .and_then(|dimensions| dimensions.get("minecraft:overworld"))
+            .and_then(|overworld| overworld.get("generator"))
+            .and_then(|generator| generator.get("settings"))
+            .and_then(Tag::as_str)
+        {
+            level.generator = settings.to_string();
+        }
+        // Regras salvas substituem os padrões; regras desconhecidas são mantidas
+        if let Some(Tag::Compound(rules)) = data.get("GameRules") {
+            for (rule, value) in rules {
+                if let Some(value) = value.as_str() {
+                    level.game_rules.insert(rule.clone(), value.to_string());
+                }
+            }
+        }
+        level.player = data.get("Player").and_then(|player| player_from_nbt(player, level.game_mode));
+        Ok(level)
+    }
+}
+
+// Sem playerGameType, o jogador usa o modo padrão do mundo
+fn player_from_nbt(tag: &Tag, default_mode: GameMode) -> Option<PlayerData> {
+    let float = |tag: &Tag| match *tag {
+        Tag::Float(v) => Some(v),
+        Tag::Double(v) => Some(v as f32),
+        _ => None,
+    };
+    let position = match tag.get("Pos")?.as_list()? {
+        [x, y, z] => Vec3::new(float(x)?, float(y)?, float(z)?),
+        _ => return None,
+    };
+    let (yaw, pitch) = match tag.get("Rotation")?.as_list()? {
+        [yaw, pitch] => (float(yaw)? + 90.0, -float(pitch)?),
+        _ => return None,
+    };
+    let inventory = tag.get("Inventory").map_or_else(Inventory::new, Inventory::from_nbt);
+    let game_mode = tag.get("playerGameType").and_then(Tag::as_i64).and_then(GameMode::from_id).unwrap_or(default_mode);
+    let health = tag.get("Health").and_then(float).unwrap_or(20.0);
+    position.is_finite().then_some(PlayerData { position, yaw, pitch, inventory, game_mode, health })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::inventory::ItemStack;
+
+    #[test]
+    fn level_round_trips_through_level_dat() {
+        let dir = std::env::temp_dir().join(format!("betacraft-level-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(LevelData::load(&dir).unwrap(), None);
+
+        let mut level = LevelData::new(u64::MAX - 5, (8, 70, -3));
+        level.time = 123_456;
+        level.day_time = 30_000;
+        level.game_mode = GameMode::Creative;
+        level.game_rules.insert("doDaylightCycle".to_string(), "false".to_string());
+        let mut inventory = Inventory::new();
+        inventory.slots[4] = Some(ItemStack::new("minecraft:sand", 12));
+        level.player = Some(PlayerData {
+            position: Vec3::new(1.5, 64.0, -2.25),
+            yaw: -90.0,
+            pitch: 12.5,
+            inventory,
+            game_mode: GameMode::Spectator,
+            health: 7.5,
+        });
+
+        level.save(&dir).unwrap();
+        // Segundo save preserva o anterior
+        level.save(&dir).unwrap();
+        assert!(dir.join("level.dat_old").exists());
+        assert_eq!(LevelData::load(&dir).unwrap(), Some(level));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotation_uses_vanilla_convention() {
+        let mut level = LevelData::new(1, (0, 64, 0));
+        // Nosso yaw -90 (olhando para -Z) é o yaw 180 do vanilla
+        level.player = Some(PlayerData {
+            position: Vec3::ZERO,
+            yaw: -90.0,
+            pitch: 30.0,
+            inventory: Inventory::new(),
+            game_mode: GameMode::Survival,
+            health: 20.0,
+        });
+        let tag = level.to_nbt();
+        let rotation = tag.get("Data").and_then(|d| d.get("Player")).and_then(|p| p.get("Rotation")).unwrap();
+        assert_eq!(rotation, &Tag::List(vec![Tag::Float(-180.0), Tag::Float(-30.0)]));
+    }
+
+    #[test]
+    fn daylight_cycle_rule_stops_day_time() {
+        let mut level = LevelData::new(1, (0, 64, 0));
+        level.tick();
+        level.game_rules.insert("doDaylightCycle".to_string(), "false".to_string());
+        level.tick();
+        assert_eq!((level.time, level.day_time), (2, 1));
+    }
+
+    #[test]
+    fn setting_the_time_of_day_moves_forward() {
+        let mut level = LevelData::new(1, (0, 64, 0));
+        level.day_time = DAY_LENGTH * 2 + 6000;
+        level.set_time_of_day(13000);
+        assert_eq!(level.day_time, DAY_LENGTH * 2 + 13000);
+        level.set_time_of_day(1000);
+        assert_eq!(level.day_time, DAY_LENGTH * 3 + 1000);
+        assert_eq!(level.time_of_day(), 1000);
+        level.set_time_of_day(DAY_LENGTH + 1000);
+        assert_eq!(level.day_time, DAY_LENGTH * 3 + 1000);
+    }
+
+    #[test]
+    fn game_modes_cycle_in_id_order() {
+        let mut mode = GameMode::Survival;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            mode = mode.next();
+            seen.push(mode);
+        }
+        assert_eq!(seen, [GameMode::Creative, GameMode::Adventure, GameMode::Spectator, GameMode::Survival]);
+        assert!(GameMode::Adventure.is_survival_like() && !GameMode::Adventure.can_edit_blocks());
+        assert!(!GameMode::Creative.is_survival_like() && GameMode::Creative.can_edit_blocks());
+    }
+
+    #[test]
+    fn missing_fields_are_errors() {
+        assert!(LevelData::from_nbt(&Tag::compound()).is_err());
+        let mut root = Tag::compound();
+        root.insert("Data", Tag::compound());
+        assert!(LevelData::from_nbt(&root).is_err());
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::{HashSet, VecDeque};
+use crate::game::blocks::{registry, BlockId};
+use super::chunk::{CHUNK_SIZE, WORLD_HEIGHT};
+use super::mesher::affected_sections;
+use super::palette::CONTAINER_SIZE;
+use super::{World, FACE_OFFSETS};
+
+pub const MAX_LIGHT: u8 = 15;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightKind {
+    Sky,   // Luz do céu: 15 descendo reto, perde um nível por bloco para os lados
+    Block, // Luz emitida por blocos (`light_emission`)
+}
+
+// Luz de uma seção, um byte por bloco: céu nos 4 bits altos, blocos nos 4 baixos
+#[derive(Clone)]
+pub struct LightSection(Box<[u8; CONTAINER_SIZE]>);
+
+impl LightSection {
+    pub fn new() -> Self {
+        Self(Box::new([Self::open_sky(LightKind::Sky) << 4; CONTAINER_SIZE]))
+    }
+
+    // Nível de quem está sob o céu aberto, sem fontes por perto
+    pub fn open_sky(kind: LightKind) -> u8 {
+        match kind {
+            LightKind::Sky => MAX_LIGHT,
+            LightKind::Block => 0,
+        }
+    }
+
+    pub fn get(&self, index: usize, kind: LightKind) -> u8 {
+        match kind {
+            LightKind::Sky => self.0[index] >> 4,
+            LightKind::Block => self.0[index] & 0x0F,
+        }
+    }
+
+    pub fn set(&mut self, index: usize, kind: LightKind, level: u8) {
+        let cell = &mut self.0[index];
+        *cell = match kind {
+            LightKind::Sky => (*cell & 0x0F) | (level << 4),
+            LightKind::Block => (*cell & 0xF0) | level,
+        };
+    }
+}
+
+impl Default for LightSection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Quanto a luz perde ao entrar no bloco: opacos barram tudo, fluidos filtram um nível
+fn opacity(id: BlockId) -> u8 {
+    let registry = registry();
+    if id == BlockId::AIR {
+        0
+    } else if !registry.is_transparent(id) {
+        MAX_LIGHT
+    } else if registry.is_fluid(id) {
+        1
+    } else {
+        0
+    }
+}
+
+// Nível que chega ao vizinho; a luz do céu no máximo desce sem perder nada
+fn spread(kind: LightKind, level: u8, downwards: bool, opacity: u8) -> u8 {
+    if opacity >= MAX_LIGHT {
+        0
+    } else if kind == LightKind::Sky && downwards && level == MAX_LIGHT && opacity == 0 {
+        MAX_LIGHT
+    } else {
+        level.saturating_sub(opacity.max(1))
+    }
+}
+
+fn emission(id: BlockId) -> u8 {
+    registry().properties(id).light_emission.min(MAX_LIGHT)
+}
+
+impl World {
+    // Nível de luz para desenhar; acima do mundo e em chunks não carregados é céu aberto,
+    // abaixo do mundo é escuro
+    pub fn get_light(&self, x: i32, y: i32, z: i32, kind: LightKind) -> u8 {
+        if y < 0 {
+            return 0;
+        } else if y >= WORLD_HEIGHT {
+            return LightSection::open_sky(kind);
+        }
+        self.stored_light((x, y, z), kind).unwrap_or(LightSection::open_sky(kind))
+    }
+
+    // None fora do mundo ou em chunks não carregados, onde a propagação para
+    fn stored_light(&self, (x, y, z): (i32, i32, i32), kind: LightKind) -> Option<u8> {
+        if !(0..WORLD_HEIGHT).contains(&y) {
+            return None;
+        }
+        let (key, (lx, lz)) = Self::chunk_coords(x, z);
+        Some(self.chunks.get(&key)?.light(lx, y as usize, lz, kind))
+    }
+
+    // Grava o nível e anota as seções cuja malha depende dele
+    fn store_light(&mut self, (x, y, z): (i32, i32, i32), kind: LightKind, level: u8, touched: &mut HashSet<(i32, i32, usize)>) {
+        let (key, (lx, lz)) = Self::chunk_coords(x, z);
+        if let Some(chunk) = self.chunks.get_mut(&key) {
+            chunk.set_light(lx, y as usize, lz, kind, level);
+            touched.extend(affected_sections(x, y, z));
+        }
+    }
+
+    // Luz inicial de um chunk recém-gerado ou lido do disco: colunas de céu aberto e fontes de
+    // luz, espalhadas só dentro dele. Roda nos workers, num mundo só com o chunk; a troca com os
+    // vizinhos fica para `light_seams`
+    pub fn light_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+        let mut touched = HashSet::new();
+        let (base_x, base_z) = (chunk_x * CHUNK_SIZE, chunk_z * CHUNK_SIZE);
+        let size = CHUNK_SIZE as usize;
+        let mut sky = VecDeque::new();
+        let mut block = VecDeque::new();
+        let Some(chunk) = self.chunks.get_mut(&(chunk_x, chunk_z)) else {
+            return;
+        };
+
+        // Altura a partir da qual cada coluna vê o céu
+        let mut heights = vec![0; size * size];
+        for z in 0..size {
+            for x in 0..size {
+                let mut open = true;
+                for y in (0..WORLD_HEIGHT as usize).rev() {
+                    let id = chunk.get_block(x, y, z);
+                    if open && opacity(id) == 0 {
+                        chunk.set_light(x, y, z, LightKind::Sky, MAX_LIGHT);
+                    } else {
+                        if open {
+                            heights[z * size + x] = y as i32 + 1;
+                            open = false;
+                        }
+                        chunk.set_light(x, y, z, LightKind::Sky, 0);
+                    }
+                    let level = emission(id);
+                    chunk.set_light(x, y, z, LightKind::Block, level);
+                    if level > 0 {
+                        block.push_back((base_x + x as i32, y as i32, base_z + z as i32));
+                    }
+                }
+            }
+        }
+
+        // Só espalha para os lados o céu que fica abaixo da altura de algum vizinho; nas bordas o
+        // vizinho pode ser qualquer coisa
+        for z in 0..size as i32 {
+            for x in 0..size as i32 {
+                let height = heights[z as usize * size + x as usize];
+                let neighbor_height = FACE_OFFSETS
+                    .iter()
+                    .filter(|&&(_, dy, _)| dy == 0)
+                    .map(|&(dx, _, dz)| {
+                        let (nx, nz) = (x + dx, z + dz);
+                        if (0..CHUNK_SIZE).contains(&nx) && (0..CHUNK_SIZE).contains(&nz) {
+                            heights[nz as usize * size + nx as usize]
+                        } else {
+                            WORLD_HEIGHT
+                        }
+                    })
+                    .max()
+                    .unwrap_or(0);
+                for y in height..neighbor_height.max(height + 1).min(WORLD_HEIGHT) {
+                    sky.push_back((base_x + x, y, base_z + z));
+                }
+            }
+        }
+
+        self.propagate(LightKind::Sky, sky, &mut touched);
+        self.propagate(LightKind::Block, block, &mut touched);
+    }
+
+    // Luz que cruza a borda entre um chunk recém-inserido (já iluminado por `light_chunk`) e os
+    // vizinhos carregados, nos dois sentidos. Retorna as seções que mudaram, inclusive nos
+    // vizinhos em diagonal
+    pub fn light_seams(&mut self, chunk_x: i32, chunk_z: i32) -> Vec<(i32, i32, usize)> {
+        let mut touched = HashSet::new();
+        let (base_x, base_z) = (chunk_x * CHUNK_SIZE, chunk_z * CHUNK_SIZE);
+        let mut sky = VecDeque::new();
+        let mut block = VecDeque::new();
+        if !self.has_chunk(chunk_x, chunk_z) {
+            return Vec::new();
+        }
+
+        for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            if !self.has_chunk(chunk_x + dx, chunk_z + dz) {
+                continue;
+            }
+            // As duas colunas encostadas na borda: a do vizinho e a deste chunk
+            for i in 0..CHUNK_SIZE {
+                let (outside, inside) = match (dx, dz) {
+                    (1, _) => ((base_x + CHUNK_SIZE, base_z + i), (base_x + CHUNK_SIZE - 1, base_z + i)),
+                    (-1, _) => ((base_x - 1, base_z + i), (base_x, base_z + i)),
+                    (_, 1) => ((base_x + i, base_z + CHUNK_SIZE), (base_x + i, base_z + CHUNK_SIZE - 1)),
+                    _ => ((base_x + i, base_z - 1), (base_x + i, base_z)),
+                };
+                for (x, z) in [outside, inside] {
+                    for y in 0..WORLD_HEIGHT {
+                        if self.stored_light((x, y, z), LightKind::Sky) > Some(1) {
+                            sky.push_back((x, y, z));
+                        }
+                        if self.stored_light((x, y, z), LightKind::Block) > Some(1) {
+                            block.push_back((x, y, z));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.propagate(LightKind::Sky, sky, &mut touched);
+        self.propagate(LightKind::Block, block, &mut touched);
+        touched.into_iter().collect()
+    }
+
+    // Refaz a luz em volta de um bloco que acabou de mudar. Retorna as seções a remalhar
+    pub fn update_light(&mut self, x: i32, y: i32, z: i32) -> Vec<(i32, i32, usize)> {
+        let mut touched = HashSet::new();
+        if self.stored_light((x, y, z), LightKind::Sky).is_none() {
+            return Vec::new();
+        }
+        let id = self.get_block(x, y, z);
+        // O topo do mundo não tem vizinho de cima para trazer o céu de volta
+        let sky = if y == WORLD_HEIGHT - 1 && opacity(id) == 0 { MAX_LIGHT } else { 0 };
+        self.relight(LightKind::Sky, (x, y, z), sky, &mut touched);
+        self.relight(LightKind::Block, (x, y, z), emission(id), &mut touched);
+        touched.into_iter().collect()
+    }
+
+    // Apaga a luz que dependia da posição e propaga de novo a partir das bordas do que foi
+    // apagado e da própria fonte da posição (`source`)
+    fn relight(&mut self, kind: LightKind, pos: (i32, i32, i32), source: u8, touched: &mut HashSet<(i32, i32, usize)>) {
+        let mut removal = VecDeque::new();
+        let mut queue = VecDeque::new();
+        if let Some(old) = self.stored_light(pos, kind).filter(|&old| old > 0) {
+            self.store_light(pos, kind, 0, touched);
+            removal.push_back((pos, old));
+        }
+
+        while let Some(((x, y, z), level)) = removal.pop_front() {
+            for (dx, dy, dz) in FACE_OFFSETS {
+                let neighbor = (x + dx, y + dy, z + dz);
+                let Some(current) = self.stored_light(neighbor, kind).filter(|&current| current > 0) else {
+                    continue;
+                };
+                // Mais fraco que a origem (ou a coluna de céu abaixo dela) só podia vir dela
+                let column = kind == LightKind::Sky && dy == -1 && level == MAX_LIGHT && current == MAX_LIGHT;
+                if current < level || column {
+                    self.store_light(neighbor, kind, 0, touched);
+                    removal.push_back((neighbor, current));
+                } else {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        if source > 0 {
+            self.store_light(pos, kind, source, touched);
+            queue.push_back(pos);
+        }
+        let (x, y, z) = pos;
+        queue.extend(FACE_OFFSETS.iter().map(|&(dx, dy, dz)| (x + dx, y + dy, z + dz)));
+        self.propagate(kind, queue, touched);
+    }
+
+    // Flood fill (BFS) a partir das posições da fila, só aumentando níveis
+    fn propagate(&mut self, kind: LightKind, mut queue: VecDeque<(i32, i32, i32)>, touched: &mut HashSet<(i32, i32, usize)>) {
+        while let Some((x, y, z)) = queue.pop_front() {
+            let Some(level) = self.stored_light((x, y, z), kind).filter(|&level| level > 1) else {
+                continue;
+            };
+            for (dx, dy, dz) in FACE_OFFSETS {
+                let neighbor = (x + dx, y + dy, z + dz);
+                let Some(current) = self.stored_light(neighbor, kind) else {
+                    continue;
+                };
+                let value = spread(kind, level, dy == -1, opacity(self.get_block(neighbor.0, neighbor.1, neighbor.2)));
+                if value > current {
+                    self.store_light(neighbor, kind, value, touched);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::world::chunk::Chunk;
+
+    const FLOOR: i32 = 60;
+
+    fn stone() -> BlockId {
+        registry().id("minecraft:stone").unwrap()
+    }
+
+    // Chunks com chão de pedra em y = 60 e um teto 4 blocos acima nas colunas locais `roof`,
+    // carregados e iluminados um a um, como no jogo
+    fn floor_world(chunks: &[(i32, i32)], roof: &[(usize, usize)]) -> World {
+        let mut world = World::new();
+        for &(chunk_x, chunk_z) in chunks {
+            let mut chunk = Chunk::new(chunk_x, chunk_z);
+            for z in 0..CHUNK_SIZE as usize {
+                for x in 0..CHUNK_SIZE as usize {
+                    chunk.set_block(x, FLOOR as usize, z, stone());
+                }
+            }
+            if (chunk_x, chunk_z) == (0, 0) {
+                for &(x, z) in roof {
+                    chunk.set_block(x, FLOOR as usize + 4, z, stone());
+                }
+            }
+            world.insert_chunk(chunk);
+            world.light_chunk(chunk_x, chunk_z);
+            world.light_seams(chunk_x, chunk_z);
+        }
+        world
+    }
+
+    fn square(size: usize) -> Vec<(usize, usize)> {
+        (0..size).flat_map(|x| (0..size).map(move |z| (x, z))).collect()
+    }
+
+    fn around() -> Vec<(i32, i32)> {
+        (-1..=1).flat_map(|x| (-1..=1).map(move |z| (x, z))).collect()
+    }
+
+    fn sky(world: &World, x: i32, y: i32, z: i32) -> u8 {
+        world.get_light(x, y, z, LightKind::Sky)
+    }
+
+    #[test]
+    fn overhang_is_lit_from_its_open_sides() {
+        let world = floor_world(&around(), &square(8));
+
+        assert_eq!(sky(&world, 8, FLOOR + 1, 4), MAX_LIGHT);
+        assert_eq!(sky(&world, 7, FLOOR + 1, 4), 14);
+        assert_eq!(sky(&world, 4, FLOOR + 1, 4), 11);
+        assert_eq!(sky(&world, 4, FLOOR + 3, 3), 11);
+        assert_eq!(sky(&world, 4, FLOOR + 4, 4), 0); // Dentro da pedra
+        assert_eq!(sky(&world, 4, FLOOR - 1, 4), 0); // Sob o chão
+        assert_eq!(world.get_light(4, FLOOR + 1, 4, LightKind::Block), 0);
+    }
+
+    #[test]
+    fn removing_and_placing_blocks_relights_incrementally() {
+        let mut world = floor_world(&around(), &square(8));
+
+        world.remove_block(4, FLOOR + 4, 4);
+        let sections = world.update_light(4, FLOOR + 4, 4);
+        assert_eq!(sky(&world, 4, FLOOR + 1, 4), MAX_LIGHT);
+        assert_eq!(sky(&world, 3, FLOOR + 1, 4), 14);
+        assert_eq!(sky(&world, 5, FLOOR + 2, 5), 13);
+        assert!(sections.contains(&(0, 0, 3)) && sections.contains(&(0, 0, 4)));
+
+        world.set_block(4, FLOOR + 4, 4, stone());
+        world.update_light(4, FLOOR + 4, 4);
+        assert_eq!(sky(&world, 4, FLOOR + 1, 4), 11);
+        assert_eq!(sky(&world, 3, FLOOR + 1, 4), 11);
+        assert_eq!(sky(&world, 7, FLOOR + 1, 4), 14);
+
+        // Um bloco solto no céu aberto faz sombra só na própria coluna
+        world.set_block(12, FLOOR + 10, 12, stone());
+        world.update_light(12, FLOOR + 10, 12);
+        assert_eq!(sky(&world, 12, FLOOR + 1, 12), 14);
+        assert_eq!(sky(&world, 13, FLOOR + 1, 12), MAX_LIGHT);
+    }
+
+    #[test]
+    fn block_light_fades_with_distance_and_goes_away_with_its_source() {
+        let mut world = floor_world(&around(), &[]);
+        let block = |world: &World, x, z| world.get_light(x, FLOOR + 1, z, LightKind::Block);
+        // Uma parede de pedra entre a fonte e x = 13
+        for y in FLOOR + 1..FLOOR + 4 {
+            for z in 0..3 {
+                world.set_block(12, y, z, stone());
+            }
+        }
+
+        let mut touched = HashSet::new();
+        world.relight(LightKind::Block, (14, FLOOR + 1, 0), 14, &mut touched);
+        assert_eq!(block(&world, 14, 0), 14);
+        assert_eq!(block(&world, 16, 0), 12); // Já no chunk vizinho
+        assert_eq!(block(&world, 14, -3), 11);
+        assert_eq!(block(&world, 12, 0), 0);
+        assert_eq!(block(&world, 11, 0), 9); // Contornando a parede por z = 3
+        assert!(touched.contains(&(1, 0, 3)) && touched.contains(&(0, -1, 3)));
+
+        world.relight(LightKind::Block, (14, FLOOR + 1, 0), 0, &mut touched);
+        assert_eq!(block(&world, 14, 0), 0);
+        assert_eq!(block(&world, 16, 0), 0);
+        assert_eq!(block(&world, 11, 0), 0);
+    }
+
+    #[test]
+    fn light_flows_across_chunk_borders_on_load() {
+        let mut world = floor_world(&[(0, 0)], &square(CHUNK_SIZE as usize));
+        // Sem vizinhos carregados não entra luz por baixo do teto
+        assert_eq!(sky(&world, 15, FLOOR + 1, 8), 0);
+
+        let mut neighbor = floor_world(&[(1, 0)], &[]);
+        world.insert_chunk(neighbor.remove_chunk(1, 0).unwrap());
+        let sections = world.light_seams(1, 0);
+        assert_eq!(sky(&world, 16, FLOOR + 1, 8), MAX_LIGHT);
+        assert_eq!(sky(&world, 15, FLOOR + 1, 8), 14);
+        assert_eq!(sky(&world, 10, FLOOR + 1, 8), 9);
+        assert_eq!(sky(&world, 0, FLOOR + 1, 8), 0);
+        assert!(sections.contains(&(0, 0, 3)));
+    }
+}
@@ -0,0 +1,629 @@
+use glam::Vec3;
+use crate::game::atlas::Tile;
+use crate::game::blocks::model::{BlockModels, ModelQuad};
+use crate::game::blocks::registry::RenderLayer;
+use crate::game::blocks::{registry, BlockId};
+use super::light::{LightKind, MAX_LIGHT};
+use super::{World, FACE_OFFSETS};
+use super::chunk::{CHUNK_SIZE, SECTION_COUNT, SECTION_HEIGHT};
+
+const SIZE: usize = CHUNK_SIZE as usize; // Seções são cubos de 16
+
+// Eixos (u, v, normal) de cada face, na ordem de FACE_OFFSETS; u/v seguem as UVs do cubo original
+const FACE_AXES: [(usize, usize, usize); 6] = [
+    (0, 1, 2),
+    (0, 1, 2),
+    (0, 2, 1),
+    (0, 2, 1),
+    (2, 1, 0),
+    (2, 1, 0),
+];
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2], // Em blocos: um quad de 3x2 vai de 0 a 3 e 0 a 2; o shader repete a textura dentro do tile
+    pub normal: [f32; 3],
+    pub tile: [f32; 4], // Retângulo da textura no atlas
+    pub layer: f32,     // Camada, quando as texturas estão num array
+    pub tint: [f32; 3], // Cor multiplicada na textura (grama e folhas de packs vanilla)
+    pub light: [f32; 2], // Luz do céu e dos blocos, de 0 a 1
+    pub ao: f32,         // Oclusão ambiente do canto: 1 livre, menos quanto mais cercado
+}
+
+// Faixa de índices desenhada com a mesma opacidade; as translúcidas (água) vêm por último
+#[derive(Clone, Debug, PartialEq)]
+pub struct Batch {
+    pub translucent: bool,
+    pub first_index: usize,
+    pub index_count: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SectionMesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub batches: Vec<Batch>,
+}
+
+impl SectionMesh {
+    #[cfg(test)]
+    pub fn quad_count(&self) -> usize {
+        self.indices.len() / 6
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+// Fator de oclusão por quantos dos três vizinhos de um canto estão livres
+const AO_LEVELS: [f32; 4] = [0.5, 0.7, 0.85, 1.0];
+
+// Luz suavizada e oclusão de um canto de face
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CornerLight {
+    light: [f32; 2],
+    ao: f32,
+}
+
+// Bloco e os quatro cantos de uma face visível na máscara da camada
+type FaceCell = Option<(BlockId, [CornerLight; 4])>;
+
+// Retângulo já mesclado, em coordenadas da camada
+struct Quad {
+    block: BlockId,
+    corners: [CornerLight; 4], // Na ordem (0, 0), (1, 0), (1, 1), (0, 1) em u/v
+    face: usize,
+    layer: usize,
+    u: usize,
+    v: usize,
+    width: usize,
+    height: usize,
+}
+
+// Luz do céu e dos blocos na posição; as faces usam a do bloco para onde apontam
+fn light_at(world: &World, x: i32, y: i32, z: i32) -> [u8; 2] {
+    [world.get_light(x, y, z, LightKind::Sky), world.get_light(x, y, z, LightKind::Block)]
+}
+
+fn vertex_light(light: [u8; 2]) -> [f32; 2] {
+    light.map(|level| level as f32 / MAX_LIGHT as f32)
+}
+
+// Luz suave do vanilla: o canto fica com a média da luz do bloco em frente à face e dos três
+// vizinhos dele que encostam no canto (os opacos não entram) e escurece com cada um que é opaco.
+// `du`/`dv` (±1) dizem para que lado de `front` está o canto
+fn corner_light(world: &World, front: [i32; 3], u_axis: usize, v_axis: usize, du: i32, dv: i32) -> CornerLight {
+    let registry = registry();
+    let offset = |su, sv| {
+        let mut pos = front;
+        pos[u_axis] += su;
+        pos[v_axis] += sv;
+        pos
+    };
+    let open = |pos: [i32; 3]| registry.is_transparent(world.get_block(pos[0], pos[1], pos[2]));
+    let (side_u, side_v, diagonal) = (offset(du, 0), offset(0, dv), offset(du, dv));
+    let (open_u, open_v) = (open(side_u), open(side_v));
+    // Com os dois lados fechados o diagonal fica escondido, mesmo livre
+    let open_diagonal = (open_u || open_v) && open(diagonal);
+
+    let mut sum = [0.0; 2];
+    let mut count = 0.0;
+    for (pos, _) in [(front, true), (side_u, open_u), (side_v, open_v), (diagonal, open_diagonal)]
+        .into_iter()
+        .filter(|&(_, open)| open)
+    {
+        let light = vertex_light(light_at(world, pos[0], pos[1], pos[2]));
+        sum[0] += light[0];
+        sum[1] += light[1];
+        count += 1.0;
+    }
+    CornerLight {
+        light: sum.map(|total| total / count),
+        ao: AO_LEVELS[open_u as usize + open_v as usize + open_diagonal as usize],
+    }
+}
+
+// Malha de uma seção 16x16x16 com greedy meshing: faces visíveis coplanares do mesmo bloco e
+// com a mesma luz viram um único quad. Blocos com modelo próprio (slabs, plantas) entram quad a quad.
+// `world` precisa conter os chunks vizinhos para as faces da borda
+pub fn mesh_section(world: &World, models: &BlockModels, chunk_x: i32, chunk_z: i32, section: usize) -> SectionMesh {
+    if world.chunk(chunk_x, chunk_z).and_then(|chunk| chunk.section(section)).is_none() {
+        return SectionMesh::default(); // Seção vazia não tem faces
+    }
+    let registry = registry();
+    let base = [chunk_x * CHUNK_SIZE, section as i32 * SECTION_HEIGHT, chunk_z * CHUNK_SIZE];
+    let mut quads = Vec::new();
+
+    for (face, &(u_axis, v_axis, n_axis)) in FACE_AXES.iter().enumerate() {
+        let (dx, dy, dz) = FACE_OFFSETS[face];
+        for layer in 0..SIZE {
+            // Bloco e luz de cada face visível nesta camada, indexado por [v][u]
+            let mut mask = [[None; SIZE]; SIZE];
+            for (v, row) in mask.iter_mut().enumerate() {
+                for (u, cell) in row.iter_mut().enumerate() {
+                    let mut pos = base;
+                    pos[u_axis] += u as i32;
+                    pos[v_axis] += v as i32;
+                    pos[n_axis] += layer as i32;
+
+                    let id = world.get_block(pos[0], pos[1], pos[2]);
+                    let cube = id != BlockId::AIR && models.shape(id).is_none();
+                    let front = [pos[0] + dx, pos[1] + dy, pos[2] + dz];
+                    if cube && face_visible(id, world.get_block(front[0], front[1], front[2])) {
+                        let corners = [(-1, -1), (1, -1), (1, 1), (-1, 1)]
+                            .map(|(du, dv)| corner_light(world, front, u_axis, v_axis, du, dv));
+                        *cell = Some((id, corners));
+                    }
+                }
+            }
+            merge_layer(&mut mask, face, layer, &mut quads);
+        }
+    }
+
+    let mut shaped = Vec::new();
+    for y in 0..SIZE as i32 {
+        for z in 0..SIZE as i32 {
+            for x in 0..SIZE as i32 {
+                let pos = [base[0] + x, base[1] + y, base[2] + z];
+                let id = world.get_block(pos[0], pos[1], pos[2]);
+                if let Some(shape) = models.shape(id) {
+                    shaped.push((id, pos, shape));
+                }
+            }
+        }
+    }
+
+    // As texturas estão todas num atlas; só a opacidade separa os grupos de draw
+    let is_translucent = |block| registry.render_layer(block) == RenderLayer::Translucent;
+    let mut mesh = SectionMesh::default();
+    for translucent in [false, true] {
+        for quad in quads.iter().filter(|quad| is_translucent(quad.block) == translucent) {
+            emit_quad(&mut mesh, base, quad, models);
+            extend_batch(&mut mesh, translucent);
+        }
+        for &(block, pos, shape) in shaped.iter().filter(|&&(block, _, _)| is_translucent(block) == translucent) {
+            for quad in shape {
+                // `cullface` some quando o vizinho daquele lado esconde a face
+                let hidden = quad.cullface.is_some_and(|face| {
+                    let (dx, dy, dz) = FACE_OFFSETS[face];
+                    !face_visible(block, world.get_block(pos[0] + dx, pos[1] + dy, pos[2] + dz))
+                });
+                if !hidden {
+                    // Faces rentes ao vizinho pegam a luz dele; as de dentro do bloco, a do próprio
+                    let (dx, dy, dz) = quad.cullface.map_or((0, 0, 0), |face| FACE_OFFSETS[face]);
+                    let light = light_at(world, pos[0] + dx, pos[1] + dy, pos[2] + dz);
+                    emit_model_quad(&mut mesh, pos, quad, light);
+                    extend_batch(&mut mesh, translucent);
+                }
+            }
+        }
+    }
+    mesh
+}
+
+// A face de `block` encostada em `neighbor` aparece se dá para ver através do vizinho. Entre dois
+// blocos do mesmo tipo translúcido ou fluido (água com água) ela ficaria dentro do volume, e as
+// camadas semitransparentes se empilhariam
+fn face_visible(block: BlockId, neighbor: BlockId) -> bool {
+    let registry = registry();
+    if !registry.is_transparent(neighbor) {
+        return false;
+    }
+    let merges = registry.is_fluid(block) || registry.render_layer(block) == RenderLayer::Translucent;
+    !(merges && registry.get(block).name == registry.get(neighbor).name)
+}
+
+// Junta o quad recém-emitido ao último grupo, ou abre um novo se a opacidade mudou
+fn extend_batch(mesh: &mut SectionMesh, translucent: bool) {
+    match mesh.batches.last_mut() {
+        Some(batch) if batch.translucent == translucent => batch.index_count += 6,
+        _ => mesh.batches.push(Batch { translucent, first_index: mesh.indices.len() - 6, index_count: 6 }),
+    }
+}
+
+// Seções (chunk x, chunk z, seção) cuja malha muda quando o bloco em (x, y, z) muda:
+// a dele e as vizinhas que encostam no bloco, cujas faces da borda dependem dele
+pub fn affected_sections(x: i32, y: i32, z: i32) -> Vec<(i32, i32, usize)> {
+    let (chunk_x, chunk_z) = (x.div_euclid(CHUNK_SIZE), z.div_euclid(CHUNK_SIZE));
+    let section = y.div_euclid(SECTION_HEIGHT);
+    let mut sections = vec![(chunk_x, chunk_z, section)];
+    for (dx, dy, dz) in FACE_OFFSETS {
+        let neighbor = (
+            (x + dx).div_euclid(CHUNK_SIZE),
+            (z + dz).div_euclid(CHUNK_SIZE),
+            (y + dy).div_euclid(SECTION_HEIGHT),
+        );
+        if !sections.contains(&neighbor) {
+            sections.push(neighbor);
+        }
+    }
+    sections
+        .into_iter()
+        .filter(|&(_, _, section)| (0..SECTION_COUNT as i32).contains(&section))
+        .map(|(chunk_x, chunk_z, section)| (chunk_x, chunk_z, section as usize))
+        .collect()
+}
+
+// Extrai os maiores retângulos possíveis da máscara, linha por linha
+fn merge_layer(mask: &mut [[FaceCell; SIZE]; SIZE], face: usize, layer: usize, quads: &mut Vec<Quad>) {
+    for v in 0..SIZE {
+        let mut u = 0;
+        while u < SIZE {
+            let Some((block, corners)) = mask[v][u] else {
+                u += 1;
+                continue;
+            };
+
+            // Só junta faces com os mesmos cantos, senão a luz interpolada mudaria
+            let same = Some((block, corners));
+            let mut width = 1;
+            while u + width < SIZE && mask[v][u + width] == same {
+                width += 1;
+            }
+            let mut height = 1;
+            while v + height < SIZE && mask[v + height][u..u + width].iter().all(|&cell| cell == same) {
+                height += 1;
+            }
+
+            for row in &mut mask[v..v + height] {
+                row[u..u + width].fill(None);
+            }
+            quads.push(Quad { block, corners, face, layer, u, v, width, height });
+            u += width;
+        }
+    }
+}
+
+fn emit_quad(mesh: &mut SectionMesh, base: [i32; 3], quad: &Quad, models: &BlockModels) {
+    let (u_axis, v_axis, n_axis) = FACE_AXES[quad.face];
+    let tile = models.textures.get(quad.block, quad.face);
+    let (dx, dy, dz) = FACE_OFFSETS[quad.face];
+    let normal = Vec3::new(dx as f32, dy as f32, dz as f32);
+    // Faces positivas ficam no lado de cima da camada
+    let depth = quad.layer + (dx + dy + dz > 0) as usize;
+
+    let first = mesh.vertices.len() as u32;
+    let corners = [(0, 0), (quad.width, 0), (quad.width, quad.height), (0, quad.height)];
+    for ((cu, cv), corner) in corners.into_iter().zip(quad.corners) {
+        let mut position = [0.0; 3];
+        position[u_axis] = (base[u_axis] + (quad.u + cu) as i32) as f32;
+        position[v_axis] = (base[v_axis] + (quad.v + cv) as i32) as f32;
+        position[n_axis] = (base[n_axis] + depth as i32) as f32;
+        mesh.vertices.push(Vertex {
+            // Blocos são desenhados centrados na coordenada inteira
+            position: position.map(|p| p - 0.5),
+            uv: [cu as f32, cv as f32],
+            normal: normal.to_array(),
+            tile: tile.rect,
+            layer: tile.layer as f32,
+            tint: [1.0; 3],
+            light: corner.light,
+            ao: corner.ao,
+        });
+    }
+
+    mesh.indices.extend(quad_order(u_axis, v_axis, normal, &quad.corners).map(|i| first + i));
+}
+
+// Corta o quad pela diagonal entre os cantos mais claros: um canto escuro sozinho na diagonal
+// espalharia a sombra pelo meio do quad
+fn quad_order(u_axis: usize, v_axis: usize, normal: Vec3, corners: &[CornerLight; 4]) -> [u32; 6] {
+    let order = face_order(u_axis, v_axis, normal);
+    let weight = |corner: &CornerLight| corner.ao + corner.light[0].max(corner.light[1]);
+    if weight(&corners[0]) + weight(&corners[2]) < weight(&corners[1]) + weight(&corners[3]) {
+        order.map(|i| (i + 1) % 4)
+    } else {
+        order
+    }
+}
+
+// Ordem anti-horária vista de fora da face para os cantos (0, 0), (1, 0), (1, 1), (0, 1) em u/v
+fn face_order(u_axis: usize, v_axis: usize, normal: Vec3) -> [u32; 6] {
+    let mut u_dir = Vec3::ZERO;
+    let mut v_dir = Vec3::ZERO;
+    u_dir[u_axis] = 1.0;
+    v_dir[v_axis] = 1.0;
+    if u_dir.cross(v_dir).dot(normal) > 0.0 {
+        [0, 1, 2, 0, 2, 3]
+    } else {
+        [0, 2, 1, 0, 3, 2]
+    }
+}
+
+// Cubo com a mesma textura nas seis faces, um pouco maior que o bloco em (x, y, z) para ser
+// desenhado por cima dele (rachaduras da quebra)
+pub fn block_overlay(x: i32, y: i32, z: i32, tile: Tile) -> SectionMesh {
+    const INFLATE: f32 = 0.002;
+    let center = Vec3::new(x as f32, y as f32, z as f32);
+    let mut mesh = SectionMesh::default();
+    for (face, &(u_axis, v_axis, _)) in FACE_AXES.iter().enumerate() {
+        let (dx, dy, dz) = FACE_OFFSETS[face];
+        let normal = Vec3::new(dx as f32, dy as f32, dz as f32);
+        let first = mesh.vertices.len() as u32;
+        for (cu, cv) in [(0, 0), (1, 0), (1, 1), (0, 1)] {
+            let mut position = center + normal * (0.5 + INFLATE);
+            position[u_axis] += (cu as f32 - 0.5) * (1.0 + 2.0 * INFLATE);
+            position[v_axis] += (cv as f32 - 0.5) * (1.0 + 2.0 * INFLATE);
+            mesh.vertices.push(Vertex {
+                position: position.to_array(),
+                uv: [cu as f32, cv as f32],
+                normal: normal.to_array(),
+                tile: tile.rect,
+                layer: tile.layer as f32,
+                tint: [1.0; 3],
+                light: [1.0; 2],
+                ao: 1.0,
+            });
+        }
+        mesh.indices.extend(face_order(u_axis, v_axis, normal).map(|i| first + i));
+        extend_batch(&mut mesh, false);
+    }
+    mesh
+}
+
+// Os quads de modelo já vêm em ordem anti-horária, em coordenadas do bloco
+fn emit_model_quad(mesh: &mut SectionMesh, pos: [i32; 3], quad: &ModelQuad, light: [u8; 2]) {
+    let first = mesh.vertices.len() as u32;
+    for (corner, uv) in quad.positions.iter().zip(quad.uvs) {
+        mesh.vertices.push(Vertex {
+            position: std::array::from_fn(|axis| pos[axis] as f32 + corner[axis] - 0.5),
+            uv,
+            normal: quad.normal,
+            tile: quad.tile.rect,
+            layer: quad.tile.layer as f32,
+            tint: quad.tint,
+            light: vertex_light(light),
+            ao: 1.0,
+        });
+    }
+    mesh.indices.extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::atlas::BlockTextures;
+    use crate::game::world::chunk::Chunk;
+
+    fn world_with(blocks: &[(usize, usize, usize, BlockId)]) -> World {
+        let mut chunk = Chunk::new(0, 0);
+        for &(x, y, z, id) in blocks {
+            chunk.set_block(x, y, z, id);
+        }
+        let mut world = World::new();
+        world.insert_chunk(chunk);
+        world
+    }
+
+    fn id(name: &str) -> BlockId {
+        registry().id(name).unwrap()
+    }
+
+    fn mesh_of(world: &World, section: usize) -> SectionMesh {
+        mesh_section(world, &BlockModels::default(), 0, 0, section)
+    }
+
+    #[test]
+    fn single_block_has_six_quads() {
+        let mesh = mesh_of(&world_with(&[(3, 4, 5, id("minecraft:stone"))]), 0);
+        assert_eq!(mesh.quad_count(), 6);
+        assert_eq!(mesh.vertices.len(), 24);
+        assert_eq!(mesh.indices.len(), 36);
+        assert_eq!(mesh.batches, vec![Batch { translucent: false, first_index: 0, index_count: 36 }]);
+        // Face +Y do bloco em y = 4 fica em 4.5
+        let top = mesh.vertices.iter().find(|v| v.normal == [0.0, 1.0, 0.0]).unwrap();
+        assert_eq!(top.position[1], 4.5);
+    }
+
+    #[test]
+    fn full_layer_merges_into_one_quad_per_side() {
+        let stone = id("minecraft:stone");
+        let layer: Vec<_> = (0..256).map(|i| (i % 16, 0, i / 16, stone)).collect();
+        let mesh = mesh_of(&world_with(&layer), 0);
+        assert_eq!(mesh.quad_count(), 6);
+
+        // As UVs cobrem o quad inteiro para a textura repetir por bloco
+        let max_u = mesh.vertices.iter().map(|v| v.uv[0]).fold(0.0, f32::max);
+        assert_eq!(max_u, 16.0);
+    }
+
+    #[test]
+    fn solid_section_only_shows_outer_faces() {
+        let stone = id("minecraft:stone");
+        // Longe do fundo do mundo, que é escuro e mudaria a luz dos cantos de baixo
+        let cube: Vec<_> = (0..4096).map(|i| (i % 16, 16 + i / 256, (i / 16) % 16, stone)).collect();
+        let mesh = mesh_of(&world_with(&cube), 1);
+        assert_eq!(mesh.quad_count(), 6);
+        assert_eq!(mesh.vertices.len(), 24);
+    }
+
+    #[test]
+    fn different_blocks_are_not_merged() {
+        let mesh = mesh_of(&world_with(&[(0, 0, 0, id("minecraft:stone")), (1, 0, 0, id("minecraft:dirt"))]), 0);
+        // As faces entre os dois blocos ficam escondidas; o resto não mescla
+        assert_eq!(mesh.quad_count(), 10);
+
+        let mesh = mesh_of(&world_with(&[(0, 0, 0, id("minecraft:stone")), (1, 0, 0, id("minecraft:stone"))]), 0);
+        assert_eq!(mesh.quad_count(), 6);
+    }
+
+    #[test]
+    fn faces_carry_their_tile_and_translucent_blocks_come_last() {
+        let (grass, water) = (id("minecraft:grass_block"), id("minecraft:water"));
+        let side = Tile { rect: [0.5, 0.0, 0.25, 0.25], layer: 0 };
+        let top = Tile { rect: [0.0, 0.5, 0.25, 0.25], layer: 0 };
+        let mut textures = BlockTextures::new(Tile::FULL);
+        textures.insert(grass, [side, side, top, side, side, side]);
+
+        let world = world_with(&[(0, 0, 0, water), (5, 0, 0, grass)]);
+        let mesh = mesh_section(&world, &BlockModels::new(textures), 0, 0, 0);
+        let tile_of = |normal: [f32; 3], block_x: f32| {
+            let vertex = mesh.vertices.iter().find(|v| v.normal == normal && (v.position[0] - block_x).abs() <= 0.5);
+            vertex.unwrap().tile
+        };
+        assert_eq!(tile_of([0.0, 1.0, 0.0], 5.0), top.rect);
+        assert_eq!(tile_of([1.0, 0.0, 0.0], 5.0), side.rect);
+        assert_eq!(tile_of([1.0, 0.0, 0.0], 0.0), Tile::FULL.rect);
+
+        let translucent: Vec<_> = mesh.batches.iter().map(|b| b.translucent).collect();
+        assert_eq!(translucent, vec![false, true]);
+        assert_eq!(mesh.batches[1].first_index, 36);
+    }
+
+    #[test]
+    fn transparent_neighbors_keep_shared_faces() {
+        let leaves = id("minecraft:oak_leaves");
+        let mesh = mesh_of(&world_with(&[(0, 0, 0, leaves), (1, 0, 0, leaves)]), 0);
+        // 4 lados mesclados + 2 pontas + as 2 faces internas que se veem através das folhas
+        assert_eq!(mesh.quad_count(), 8);
+    }
+
+    #[test]
+    fn water_hides_faces_between_water_blocks() {
+        let water = id("minecraft:water");
+        // 2×1×1: 4 lados mesclados + 2 pontas, sem as faces internas
+        let mesh = mesh_of(&world_with(&[(0, 0, 0, water), (1, 0, 0, water)]), 0);
+        assert_eq!(mesh.quad_count(), 6);
+        // Níveis diferentes ainda são água
+        let flowing = registry().with_property(water, "level", "3").unwrap();
+        let mesh = mesh_of(&world_with(&[(0, 0, 0, water), (1, 0, 0, flowing)]), 0);
+        assert_eq!(mesh.quad_count(), 10);
+        // Contra outro bloco transparente a face continua
+        let leaves = id("minecraft:oak_leaves");
+        let mesh = mesh_of(&world_with(&[(0, 0, 0, water), (1, 0, 0, leaves)]), 0);
+        assert_eq!(mesh.quad_count(), 12);
+    }
+
+    #[test]
+    fn neighbor_chunks_hide_border_faces() {
+        let stone = id("minecraft:stone");
+        let mut world = world_with(&[(15, 0, 0, stone)]);
+        let mut neighbor = Chunk::new(1, 0);
+        neighbor.set_block(0, 0, 0, stone);
+        world.insert_chunk(neighbor);
+        assert_eq!(mesh_of(&world, 0).quad_count(), 5);
+    }
+
+    #[test]
+    fn model_quads_skip_greedy_meshing_and_respect_cullface() {
+        let (stone, leaves) = (id("minecraft:stone"), id("minecraft:oak_leaves"));
+        // Meio bloco de folhas: a face de cima não tem cullface, as outras somem contra blocos opacos
+        let mut models = BlockModels::default();
+        let half = |face: usize, y: f32| ModelQuad {
+            positions: [[0.0, y, 0.0], [0.0, y, 1.0], [1.0, y, 1.0], [1.0, y, 0.0]],
+            uvs: [[0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 1.0]],
+            normal: [0.0, if face == 2 { 1.0 } else { -1.0 }, 0.0],
+            tile: Tile::FULL,
+            cullface: (face == 3).then_some(3),
+            tint: [0.5, 1.0, 0.5],
+        };
+        models.insert_shape(leaves, vec![half(2, 0.5), half(3, 0.0)]);
+
+        let mesh = mesh_section(&world_with(&[(2, 1, 2, leaves), (3, 1, 2, leaves)]), &models, 0, 0, 0);
+        // Nada mescla: dois quads por bloco
+        assert_eq!(mesh.quad_count(), 4);
+        let top = mesh.vertices.iter().find(|v| v.normal == [0.0, 1.0, 0.0]).unwrap();
+        assert_eq!((top.position[1], top.tint), (1.0, [0.5, 1.0, 0.5]));
+
+        let mesh = mesh_section(&world_with(&[(2, 1, 2, leaves), (2, 0, 2, stone)]), &models, 0, 0, 0);
+        // A face de baixo do modelo some; a de cima da pedra continua visível sob o modelo
+        assert_eq!(mesh.quad_count(), 1 + 6);
+    }
+
+    #[test]
+    fn faces_take_the_light_in_front_of_them() {
+        let stone = id("minecraft:stone");
+        let layer: Vec<_> = (0..256).map(|i| (i % 16, 0, i / 16, stone)).collect();
+        let mut world = world_with(&layer);
+        world.set_block(4, 3, 4, stone); // Faz sombra só na própria coluna
+        world.light_chunk(0, 0);
+        let mesh = mesh_of(&world, 0);
+
+        // O topo da camada se divide em volta da sombra, que se mistura com a luz dos vizinhos
+        // nos cantos; embaixo fica no escuro
+        let tops: Vec<_> = mesh.vertices.iter().filter(|v| v.normal == [0.0, 1.0, 0.0] && v.position[1] == 0.5).collect();
+        assert!(tops.iter().any(|v| v.light == [1.0, 0.0]));
+        assert!(tops.iter().any(|v| v.light == [(14.0 + 3.0 * 15.0) / 60.0, 0.0]));
+        assert!(mesh.quad_count() > 6);
+        let bottom = mesh.vertices.iter().find(|v| v.normal == [0.0, -1.0, 0.0] && v.position[1] == -0.5).unwrap();
+        assert_eq!(bottom.light, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn corners_next_to_blocks_are_occluded() {
+        let stone = id("minecraft:stone");
+        let mut blocks: Vec<_> = (0..256).map(|i| (i % 16, 0, i / 16, stone)).collect();
+        blocks.push((4, 1, 4, stone));
+        let mesh = mesh_of(&world_with(&blocks), 0);
+        let floor_ao = |x: f32, z: f32| {
+            mesh.vertices
+                .iter()
+                .filter(|v| v.normal == [0.0, 1.0, 0.0] && v.position == [x, 0.5, z])
+                .map(|v| v.ao)
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        // Na quina do bloco algum canto sempre tem um vizinho opaco; longe dele nada escurece
+        assert_eq!(floor_ao(3.5, 4.5), AO_LEVELS[2]);
+        assert_eq!(floor_ao(-0.5, -0.5), AO_LEVELS[3]);
+        assert!(mesh.vertices.iter().all(|v| v.normal != [0.0, 1.0, 0.0] || v.position[1] != 0.5 || v.ao > AO_LEVELS[1]));
+
+        // Dois lados fechados escondem o canto mesmo com o diagonal livre
+        let mut world = world_with(&[(4, 4, 4, stone), (3, 5, 4, stone), (4, 5, 3, stone)]);
+        world.light_chunk(0, 0);
+        let corner = corner_light(&world, [4, 5, 4], 0, 2, -1, -1);
+        assert_eq!(corner.ao, AO_LEVELS[0]);
+        assert_eq!(corner.light, [1.0, 0.0]);
+    }
+
+    #[test]
+    fn quads_are_split_along_the_brighter_diagonal() {
+        let lit = CornerLight { light: [1.0, 0.0], ao: 1.0 };
+        let dark = CornerLight { light: [0.5, 0.0], ao: 0.5 };
+        let normal = Vec3::Y;
+        assert_eq!(quad_order(0, 2, normal, &[lit; 4]), face_order(0, 2, normal));
+        // Canto 0 escuro: a diagonal passa por 1 e 3, e o 0 aparece num triângulo só
+        let order = quad_order(0, 2, normal, &[dark, lit, lit, lit]);
+        assert_eq!(order.iter().filter(|&&i| i == 0).count(), 1);
+        assert_eq!(order.iter().filter(|&&i| i == 1).count(), 2);
+        assert_eq!(order.iter().filter(|&&i| i == 3).count(), 2);
+    }
+
+    #[test]
+    fn border_blocks_affect_neighbor_sections() {
+        assert_eq!(affected_sections(5, 20, 5), vec![(0, 0, 1)]);
+        // Canto de chunk e de seção: a própria, a de baixo e os dois chunks vizinhos
+        let mut sections = affected_sections(-16, 16, 15);
+        sections.sort();
+        assert_eq!(sections, vec![(-2, 0, 1), (-1, 0, 0), (-1, 0, 1), (-1, 1, 1)]);
+        // Sem seções fora da altura do mundo
+        assert_eq!(affected_sections(1, 0, 1), vec![(0, 0, 0)]);
+    }
+
+    #[test]
+    fn sections_only_mesh_their_own_blocks() {
+        let world = world_with(&[(0, 20, 0, id("minecraft:stone"))]);
+        assert!(mesh_of(&world, 0).is_empty());
+        assert_eq!(mesh_of(&world, 1).quad_count(), 6);
+    }
+
+    #[test]
+    fn overlay_wraps_the_block_facing_outwards() {
+        let tile = Tile { rect: [0.25, 0.5, 0.25, 0.25], layer: 0 };
+        let mesh = block_overlay(-3, 70, 8, tile);
+        assert_eq!(mesh.quad_count(), 6);
+        for v in &mesh.vertices {
+            let offset = Vec3::from(v.position) - Vec3::new(-3.0, 70.0, 8.0);
+            assert!((offset.abs().max_element() - 0.502).abs() < 1e-4, "{:?}", offset);
+            assert_eq!(v.tile, tile.rect);
+        }
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(mesh.vertices[triangle[i] as usize].position));
+            let normal = Vec3::from(mesh.vertices[triangle[0] as usize].normal);
+            assert!((b - a).cross(c - a).dot(normal) > 0.0);
+        }
+    }
+}
@@ -1 +1,193 @@
-pub mod generation;
\ No newline at end of file
+pub mod anvil;
+pub mod chunk;
+pub mod culling;
+pub mod generation;
+pub mod level;
+pub mod light;
+pub mod mesher;
+pub mod palette;
+pub mod raycast;
+pub mod streaming;
+pub mod workers;
+
+use std::collections::HashMap;
+use crate::game::blocks::{registry, BlockId};
+use anvil::{AnvilError, RegionStorage};
+use chunk::{Chunk, CHUNK_SIZE, WORLD_HEIGHT};
+use glam::Vec3;
+use raycast::RayHit;
+
+// Deslocamentos dos vizinhos na mesma ordem das faces: +Z, -Z, +Y, -Y, -X, +X
+pub const FACE_OFFSETS: [(i32, i32, i32); 6] = [
+    (0, 0, 1),
+    (0, 0, -1),
+    (0, 1, 0),
+    (0, -1, 0),
+    (-1, 0, 0),
+    (1, 0, 0),
+];
+
+#[derive(Default)]
+pub struct World {
+    chunks: HashMap<(i32, i32), Chunk>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Converte coordenada absoluta em (chunk, local)
+    #[inline]
+    pub fn chunk_coords(x: i32, z: i32) -> ((i32, i32), (usize, usize)) {
+        (
+            (x.div_euclid(CHUNK_SIZE), z.div_euclid(CHUNK_SIZE)),
+            (x.rem_euclid(CHUNK_SIZE) as usize, z.rem_euclid(CHUNK_SIZE) as usize),
+        )
+    }
+
+    // Posições fora do mundo ou de chunks não gerados são ar
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> BlockId {
+        if !(0..WORLD_HEIGHT).contains(&y) {
+            return BlockId::AIR;
+        }
+        let (key, (lx, lz)) = Self::chunk_coords(x, z);
+        self.chunks
+            .get(&key)
+            .map_or(BlockId::AIR, |c| c.get_block(lx, y as usize, lz))
+    }
+
+    // Coloca um bloco, retornando o que estava na posição (ar remove).
+    // Chunks não carregados são ignorados
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, id: BlockId) -> BlockId {
+        if !(0..WORLD_HEIGHT).contains(&y) {
+            return BlockId::AIR;
+        }
+        let (key, (lx, lz)) = Self::chunk_coords(x, z);
+        self.chunks
+            .get_mut(&key)
+            .map_or(BlockId::AIR, |c| c.set_block(lx, y as usize, lz, id))
+    }
+
+    pub fn remove_block(&mut self, x: i32, y: i32, z: i32) -> BlockId {
+        self.set_block(x, y, z, BlockId::AIR)
+    }
+
+    pub fn is_solid_block(&self, x: i32, y: i32, z: i32) -> bool {
+        registry().is_solid(self.get_block(x, y, z))
+    }
+
+    // Primeiro bloco mirado ao longo do raio; ar e fluidos são atravessados
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, reach: f32) -> Option<RayHit> {
+        let registry = registry();
+        raycast::raycast(origin, direction, reach, |x, y, z| {
+            let id = self.get_block(x, y, z);
+            id != BlockId::AIR && !registry.is_fluid(id)
+        })
+    }
+
+    pub fn insert_chunk(&mut self, chunk: Chunk) {
+        self.chunks.insert((chunk.x, chunk.z), chunk);
+    }
+
+    pub fn remove_chunk(&mut self, x: i32, z: i32) -> Option<Chunk> {
+        self.chunks.remove(&(x, z))
+    }
+
+    pub fn has_chunk(&self, x: i32, z: i32) -> bool {
+        self.chunks.contains_key(&(x, z))
+    }
+
+    pub fn chunk(&self, x: i32, z: i32) -> Option<&Chunk> {
+        self.chunks.get(&(x, z))
+    }
+
+    // Cópia do chunk e dos vizinhos diretos, suficiente para calcular as faces visíveis
+    pub fn snapshot_around(&self, x: i32, z: i32) -> World {
+        let mut snapshot = World::new();
+        for (dx, dz) in [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)] {
+            if let Some(chunk) = self.chunk(x + dx, z + dz) {
+                snapshot.insert_chunk(chunk.clone());
+            }
+        }
+        snapshot
+    }
+
+    // Grava todos os chunks carregados nos arquivos de região
+    pub fn save(&self, storage: &mut RegionStorage) -> Result<(), AnvilError> {
+        for chunk in self.chunks.values() {
+            storage.save_chunk(chunk)?;
+        }
+        Ok(())
+    }
+
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.chunks.keys().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(name: &str) -> BlockId {
+        registry().id(name).unwrap()
+    }
+
+    #[test]
+    fn chunk_coords_floor_negative_positions() {
+        assert_eq!(World::chunk_coords(0, 15), ((0, 0), (0, 15)));
+        assert_eq!(World::chunk_coords(16, -1), ((1, -1), (0, 15)));
+        assert_eq!(World::chunk_coords(-17, -16), ((-2, -1), (15, 0)));
+    }
+
+    // Mundo com os chunks de (-2, -1) a (1, 2) carregados e vazios
+    fn loaded_world() -> World {
+        let mut world = World::new();
+        for x in -2..2 {
+            for z in -1..3 {
+                world.insert_chunk(Chunk::new(x, z));
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn blocks_are_found_across_chunk_borders() {
+        let (stone, dirt) = (id("minecraft:stone"), id("minecraft:dirt"));
+        let mut world = loaded_world();
+        for (x, z) in [(0, 0), (15, 15), (16, 0), (-1, -1), (-17, 33)] {
+            assert_eq!(world.set_block(x, 64, z, stone), BlockId::AIR);
+        }
+        assert_eq!(world.get_block(-17, 64, 33), stone);
+        assert_eq!(world.get_block(-16, 64, 33), BlockId::AIR);
+        assert_eq!(world.get_block(15, 63, 15), BlockId::AIR);
+        assert_eq!(world.chunk(0, 0).unwrap().blocks().count(), 2);
+        assert_eq!(world.chunk(-2, 2).unwrap().blocks().count(), 1);
+
+        // Substituir e remover devolvem o bloco anterior
+        assert_eq!(world.set_block(16, 64, 0, dirt), stone);
+        assert_eq!(world.remove_block(16, 64, 0), dirt);
+        assert_eq!(world.get_block(16, 64, 0), BlockId::AIR);
+        assert_eq!(world.chunk(1, 0).unwrap().blocks().count(), 0);
+    }
+
+    #[test]
+    fn positions_outside_the_world_are_air() {
+        let stone = id("minecraft:stone");
+        let mut world = loaded_world();
+        assert_eq!(world.set_block(0, WORLD_HEIGHT, 0, stone), BlockId::AIR);
+        assert_eq!(world.set_block(0, -1, 0, stone), BlockId::AIR);
+        assert_eq!(world.get_block(0, WORLD_HEIGHT, 0), BlockId::AIR);
+        assert_eq!(world.chunk(0, 0).unwrap().blocks().count(), 0);
+        world.set_block(0, WORLD_HEIGHT - 1, 0, stone);
+        assert_eq!(world.get_block(0, WORLD_HEIGHT - 1, 0), stone);
+        assert!(!world.is_solid_block(0, WORLD_HEIGHT, 0));
+        assert!(world.is_solid_block(0, WORLD_HEIGHT - 1, 0));
+
+        // Chunks não carregados ficam como estão
+        assert_eq!(world.set_block(64, 10, 64, stone), BlockId::AIR);
+        assert_eq!(world.get_block(64, 10, 64), BlockId::AIR);
+        assert!(!world.has_chunk(4, 4));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::game::blocks::BlockId;
+
+pub const MIN_BITS: u8 = 4;          // Menor tamanho de entrada no modo indireto
+pub const MAX_INDIRECT_BITS: u8 = 8; // Acima disso usa a paleta global (ids diretos)
+pub const GLOBAL_BITS: u8 = 15;      // Bits por entrada no modo direto
+
+pub const CONTAINER_SIZE: usize = 4096;
+
+// Paleta + array de bits empacotados no layout do Minecraft Java:
+// entradas nunca atravessam dois longs e o índice é (y * 16 + z) * 16 + x.
+#[derive(Clone, Debug)]
+pub struct PalettedContainer {
+    bits: u8,
+    palette: Vec<BlockId>, // Vazia no modo direto
+    data: Vec<u64>,
+}
+
+impl PalettedContainer {
+    // Paleta de valor único: nenhum dado armazenado
+    pub fn new(value: BlockId) -> Self {
+        Self {
+            bits: 0,
+            palette: vec![value],
+            data: Vec::new(),
+        }
+    }
+
+    pub fn is_direct(&self) -> bool {
+        self.bits > MAX_INDIRECT_BITS
+    }
+
+    pub fn get(&self, index: usize) -> BlockId {
+        if self.bits == 0 {
+            return self.palette[0];
+        }
+
+        let raw = read_entry(&self.data, self.bits, index) as u16;
+        if self.is_direct() {
+            BlockId(raw)
+        } else {
+            self.palette[raw as usize]
+        }
+    }
+
+    // Retorna o valor anterior
+    pub fn set(&mut self, index: usize, value: BlockId) -> BlockId {
+        let old = self.get(index);
+        if old == value {
+            return old;
+        }
+
+        let raw = match self.palette_index(value) {
+            Some(raw) => raw,
+            None => self.grow(value),
+        };
+        write_entry(&mut self.data, self.bits, index, raw);
+        old
+    }
+
+    fn palette_index(&self, value: BlockId) -> Option<u64> {
+        if self.is_direct() {
+            return Some(value.0 as u64);
+        }
+        self.palette.iter().position(|&v| v == value).map(|i| i as u64)
+    }
+
+    // Adiciona um valor novo à paleta, aumentando os bits por entrada se preciso
+    fn grow(&mut self, value: BlockId) -> u64 {
+        self.palette.push(value);
+        let len = self.palette.len();
+
+        if self.bits == 0 || len > 1 << self.bits {
+            let values = self.values();
+            let palette = std::mem::take(&mut self.palette);
+            self.rebuild(&values, palette);
+        }
+
+        self.palette_index(value).unwrap()
+    }
+
+    fn values(&self) -> Vec<BlockId> {
+        (0..CONTAINER_SIZE).map(|i| self.get(i)).collect()
+    }
+
+    // Reempacota os valores com a menor paleta possível
+    pub fn compact(&mut self) {
+        let values = self.values();
+        let mut palette: Vec<BlockId> = Vec::new();
+        for &value in &values {
+            if !palette.contains(&value) {
+                palette.push(value);
+            }
+        }
+        self.rebuild(&values, palette);
+    }
+
+    // Formato salvo em disco (Anvil): sempre com paleta local, nunca menos de 4 bits
+    // e sem dados quando há um único valor
+    pub fn to_disk(&self) -> (Vec<BlockId>, Vec<u64>) {
+        let values = self.values();
+        let mut palette: Vec<BlockId> = Vec::new();
+        for &value in &values {
+            if !palette.contains(&value) {
+                palette.push(value);
+            }
+        }
+        if palette.len() == 1 {
+            return (palette, Vec::new());
+        }
+
+        let bits = disk_bits_for(palette.len());
+        let mut data = vec![0; longs_for(bits, CONTAINER_SIZE)];
+        for (i, value) in values.iter().enumerate() {
+            let raw = palette.iter().position(|v| v == value).unwrap() as u64;
+            write_entry(&mut data, bits, i, raw);
+        }
+        (palette, data)
+    }
+
+    // Inverso de `to_disk`; `None` se o tamanho dos dados ou algum índice não bate com a paleta
+    pub fn from_disk(palette: &[BlockId], data: &[u64]) -> Option<Self> {
+        match palette.len() {
+            0 => return None,
+            1 => return Some(Self::new(palette[0])),
+            _ => {}
+        }
+
+        let bits = disk_bits_for(palette.len());
+        if data.len() != longs_for(bits, CONTAINER_SIZE) {
+            return None;
+        }
+        let values = (0..CONTAINER_SIZE)
+            .map(|i| palette.get(read_entry(data, bits, i) as usize).copied())
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut container = Self::new(values[0]);
+        let mut unique: Vec<BlockId> = Vec::new();
+        for &value in &values {
+            if !unique.contains(&value) {
+                unique.push(value);
+            }
+        }
+        container.rebuild(&values, unique);
+        Some(container)
+    }
+
+    fn rebuild(&mut self, values: &[BlockId], palette: Vec<BlockId>) {
+        if palette.len() == 1 {
+            *self = Self::new(palette[0]);
+            return;
+        }
+
+        let bits = bits_for(palette.len());
+        let mut data = vec![0; longs_for(bits, CONTAINER_SIZE)];
+        for (i, value) in values.iter().enumerate() {
+            let raw = if bits > MAX_INDIRECT_BITS {
+                value.0 as u64
+            } else {
+                palette.iter().position(|v| v == value).unwrap() as u64
+            };
+            write_entry(&mut data, bits, i, raw);
+        }
+
+        self.bits = bits;
+        self.palette = if bits > MAX_INDIRECT_BITS { Vec::new() } else { palette };
+        self.data = data;
+    }
+}
+
+// Bits necessários para indexar uma paleta de `len` valores
+pub fn bits_for(len: usize) -> u8 {
+    let bits = (usize::BITS - (len.max(1) - 1).leading_zeros()) as u8;
+    if bits > MAX_INDIRECT_BITS {
+        GLOBAL_BITS
+    } else {
+        bits.max(MIN_BITS)
+    }
+}
+
+// Em disco a paleta local é usada mesmo acima de 256 valores
+pub fn disk_bits_for(len: usize) -> u8 {
+    ((usize::BITS - (len.max(1) - 1).leading_zeros()) as u8).max(MIN_BITS)
+}
+
+pub fn longs_for(bits: u8, size: usize) -> usize {
+    if bits == 0 {
+        return 0;
+    }
+    let per_long = 64 / bits as usize;
+    size.div_ceil(per_long)
+}
+
+#[inline]
+fn read_entry(data: &[u64], bits: u8, index: usize) -> u64 {
+    let per_long = 64 / bits as usize;
+    let shift = (index % per_long) * bits as usize;
+    (data[index / per_long] >> shift) & ((1 << bits) - 1)
+}
+
+#[inline]
+fn write_entry(data: &mut [u64], bits: u8, index: usize, value: u64) {
+    let per_long = 64 / bits as usize;
+    let shift = (index % per_long) * bits as usize;
+    let mask = ((1u64 << bits) - 1) << shift;
+    let word = &mut data[index / per_long];
+    *word = (*word & !mask) | (value << shift);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_value_palette_stores_no_data() {
+        let container = PalettedContainer::new(BlockId(3));
+        assert_eq!(container.bits, 0);
+        assert!(container.data.is_empty());
+        assert!((0..CONTAINER_SIZE).all(|i| container.get(i) == BlockId(3)));
+    }
+
+    #[test]
+    fn setting_the_same_value_keeps_single_palette() {
+        let mut container = PalettedContainer::new(BlockId::AIR);
+        container.set(42, BlockId::AIR);
+        assert_eq!(container.bits, 0);
+    }
+
+    #[test]
+    fn palette_grows_bits_per_entry() {
+        let mut container = PalettedContainer::new(BlockId::AIR);
+        container.set(0, BlockId(1));
+        assert_eq!(container.bits, 4);
+        assert_eq!(container.data.len(), 256);
+
+        for i in 2..=16u16 {
+            container.set(i as usize, BlockId(i));
+        }
+        assert_eq!(container.palette.len(), 17);
+        assert_eq!(container.bits, 5);
+        // 12 entradas de 5 bits por long, sem atravessar longs
+        assert_eq!(container.data.len(), 342);
+
+        assert_eq!(container.get(0), BlockId(1));
+        assert_eq!(container.get(1), BlockId::AIR);
+        for i in 2..=16u16 {
+            assert_eq!(container.get(i as usize), BlockId(i));
+        }
+        assert_eq!(container.get(4095), BlockId::AIR);
+    }
+
+    #[test]
+    fn large_palette_switches_to_direct_ids() {
+        let mut container = PalettedContainer::new(BlockId::AIR);
+        for i in 1..=300u16 {
+            container.set(i as usize, BlockId(i));
+        }
+        assert!(container.is_direct());
+        assert_eq!(container.bits, GLOBAL_BITS);
+        assert!((1..=300u16).all(|i| container.get(i as usize) == BlockId(i)));
+        assert_eq!(container.get(301), BlockId::AIR);
+    }
+
+    #[test]
+    fn compact_shrinks_palette() {
+        let mut container = PalettedContainer::new(BlockId::AIR);
+        for i in 1..=20u16 {
+            container.set(i as usize, BlockId(i));
+        }
+        assert_eq!(container.bits, 5);
+
+        for i in 3..=20u16 {
+            container.set(i as usize, BlockId::AIR);
+        }
+        container.compact();
+        assert_eq!(container.palette, vec![BlockId::AIR, BlockId(1), BlockId(2)]);
+        assert_eq!(container.bits, 4);
+        assert_eq!(container.get(1), BlockId(1));
+        assert_eq!(container.get(2), BlockId(2));
+        assert_eq!(container.get(3), BlockId::AIR);
+    }
+
+    #[test]
+    fn compact_returns_to_single_value() {
+        let mut container = PalettedContainer::new(BlockId::AIR);
+        container.set(7, BlockId(5));
+        container.set(7, BlockId::AIR);
+        container.compact();
+        assert_eq!(container.bits, 0);
+        assert_eq!(container.palette, vec![BlockId::AIR]);
+    }
+
+    #[test]
+    fn disk_format_round_trips() {
+        let mut container = PalettedContainer::new(BlockId::AIR);
+        for i in 0..CONTAINER_SIZE {
+            container.set(i, BlockId((i % 300) as u16));
+        }
+        assert!(container.is_direct());
+
+        let (palette, data) = container.to_disk();
+        assert_eq!(palette.len(), 300);
+        assert_eq!(data.len(), longs_for(9, CONTAINER_SIZE));
+
+        let loaded = PalettedContainer::from_disk(&palette, &data).unwrap();
+        assert!((0..CONTAINER_SIZE).all(|i| loaded.get(i) == container.get(i)));
+    }
+
+    #[test]
+    fn disk_format_rejects_bad_data() {
+        let palette = [BlockId::AIR, BlockId(1)];
+        assert!(PalettedContainer::from_disk(&[], &[]).is_none());
+        assert!(PalettedContainer::from_disk(&palette, &[0; 3]).is_none());
+        // Índice 2 não existe numa paleta de dois valores
+        assert!(PalettedContainer::from_disk(&palette, &[2; 256]).is_none());
+        assert_eq!(PalettedContainer::from_disk(&[BlockId(4)], &[]).unwrap().get(0), BlockId(4));
+    }
+
+    #[test]
+    fn bits_for_matches_vanilla_sizes() {
+        assert_eq!(bits_for(1), MIN_BITS);
+        assert_eq!(bits_for(16), 4);
+        assert_eq!(bits_for(17), 5);
+        assert_eq!(bits_for(256), 8);
+        assert_eq!(bits_for(257), GLOBAL_BITS);
+    }
+}
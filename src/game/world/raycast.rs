@@ -0,0 +1,137 @@
+use glam::Vec3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub block: (i32, i32, i32),
+    pub normal: (i32, i32, i32), // Face atingida, para fora do bloco; (0, 0, 0) se a origem já está dentro dele
+    pub point: Vec3,             // Ponto exato na face
+    pub distance: f32,
+}
+
+// Percorre as células que o raio atravessa, em ordem (DDA de Amanatides & Woo), até `reach`.
+// As células seguem o desenho dos blocos: o bloco b ocupa [b - 0.5, b + 0.5] em cada eixo.
+// `hits` diz se o bloco na posição para o raio
+pub fn raycast(origin: Vec3, direction: Vec3, reach: f32, mut hits: impl FnMut(i32, i32, i32) -> bool) -> Option<RayHit> {
+    let direction = direction.normalize_or_zero();
+    if direction == Vec3::ZERO {
+        return None;
+    }
+
+    // Em coordenadas de grade, onde a célula é o floor da posição
+    let start = origin + Vec3::splat(0.5);
+    let mut cell = start.floor().as_ivec3();
+    let step = direction.signum().as_ivec3();
+    let mut t_max = Vec3::ZERO;
+    let mut t_delta = Vec3::ZERO;
+    for axis in 0..3 {
+        if direction[axis] == 0.0 {
+            t_max[axis] = f32::INFINITY;
+            t_delta[axis] = f32::INFINITY;
+            continue;
+        }
+        // Distância até a primeira borda de célula e entre bordas seguintes
+        let boundary = if step[axis] > 0 { cell[axis] as f32 + 1.0 } else { cell[axis] as f32 };
+        t_max[axis] = (boundary - start[axis]) / direction[axis];
+        t_delta[axis] = 1.0 / direction[axis].abs();
+    }
+
+    let mut normal = (0, 0, 0);
+    let mut distance = 0.0;
+    loop {
+        if hits(cell.x, cell.y, cell.z) {
+            return Some(RayHit {
+                block: (cell.x, cell.y, cell.z),
+                normal,
+                point: origin + direction * distance,
+                distance,
+            });
+        }
+
+        let axis = if t_max.x < t_max.y && t_max.x < t_max.z {
+            0
+        } else if t_max.y < t_max.z {
+            1
+        } else {
+            2
+        };
+        if t_max[axis] > reach {
+            return None;
+        }
+        distance = t_max[axis];
+        cell[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+        normal = (0, 0, 0);
+        match axis {
+            0 => normal.0 = -step.x,
+            1 => normal.1 = -step.y,
+            _ => normal.2 = -step.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(blocks: &[(i32, i32, i32)]) -> impl FnMut(i32, i32, i32) -> bool + '_ {
+        move |x, y, z| blocks.contains(&(x, y, z))
+    }
+
+    #[test]
+    fn hits_the_face_facing_the_ray() {
+        // Olhando para -Z a partir de z = 0.2: o bloco em z = -3 ocupa de -3.5 a -2.5
+        let hit = raycast(Vec3::new(0.0, 0.0, 0.2), Vec3::NEG_Z, 5.0, only(&[(0, 0, -3)])).unwrap();
+        assert_eq!(hit.block, (0, 0, -3));
+        assert_eq!(hit.normal, (0, 0, 1));
+        assert!((hit.distance - 2.7).abs() < 1e-5);
+        assert!(hit.point.abs_diff_eq(Vec3::new(0.0, 0.0, -2.5), 1e-5));
+
+        // De cima, cai na face +Y
+        let hit = raycast(Vec3::new(0.3, 10.0, -0.2), Vec3::NEG_Y, 20.0, only(&[(0, 2, 0)])).unwrap();
+        assert_eq!((hit.block, hit.normal), ((0, 2, 0), (0, 1, 0)));
+        assert!(hit.point.abs_diff_eq(Vec3::new(0.3, 2.5, -0.2), 1e-5));
+    }
+
+    #[test]
+    fn diagonal_rays_visit_every_cell_they_cross() {
+        let mut visited = Vec::new();
+        let direction = Vec3::new(1.0, 0.5, 0.0);
+        raycast(Vec3::ZERO, direction, 3.0, |x, y, z| {
+            visited.push((x, y, z));
+            false
+        });
+        // Passa por células vizinhas por uma face, nunca pulando na diagonal
+        for pair in visited.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs(), 1, "{:?}", visited);
+        }
+        assert_eq!(visited.first(), Some(&(0, 0, 0)));
+        assert_eq!(visited.last(), Some(&(3, 1, 0)));
+
+        let hit = raycast(Vec3::ZERO, direction, 5.0, only(&[(2, 1, 0)])).unwrap();
+        // Entra pelo lado -X: a face y = 0.5 é cruzada antes, em x = 1
+        assert_eq!(hit.normal, (-1, 0, 0));
+        assert!((hit.point.x - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn stops_at_the_reach_distance() {
+        assert!(raycast(Vec3::ZERO, Vec3::X, 4.0, only(&[(5, 0, 0)])).is_none());
+        assert!(raycast(Vec3::ZERO, Vec3::X, 4.6, only(&[(5, 0, 0)])).is_some());
+        assert!(raycast(Vec3::ZERO, Vec3::ZERO, 4.0, |_, _, _| true).is_none());
+    }
+
+    #[test]
+    fn origin_inside_a_block_hits_it_immediately() {
+        let hit = raycast(Vec3::new(0.1, 0.2, 0.3), Vec3::Y, 5.0, |_, _, _| true).unwrap();
+        assert_eq!((hit.block, hit.normal, hit.distance), ((0, 0, 0), (0, 0, 0), 0.0));
+    }
+
+    #[test]
+    fn negative_coordinates_round_like_the_renderer() {
+        // -0.6 está no bloco -1 (de -1.5 a -0.5)
+        let hit = raycast(Vec3::new(-0.6, 0.0, 0.0), Vec3::NEG_X, 5.0, only(&[(-3, 0, 0)])).unwrap();
+        assert_eq!((hit.block, hit.normal), ((-3, 0, 0), (1, 0, 0)));
+        assert!((hit.distance - 1.9).abs() < 1e-5);
+    }
+}
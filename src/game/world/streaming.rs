@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use glam::Vec3;
+use super::World;
+use super::chunk::{Chunk, CHUNK_SIZE};
+use super::workers::{Task, WorkerPool};
+
+pub const DEFAULT_RENDER_DISTANCE: i32 = 4; // Em chunks
+
+#[derive(Default, Debug)]
+pub struct StreamUpdate {
+    pub unloaded: Vec<(i32, i32)>,
+}
+
+// Pede chunks em volta do jogador aos workers e descarrega os que saem do alcance
+pub struct ChunkStreamer {
+    pub render_distance: i32,
+    center: (i32, i32),
+    pending: HashSet<(i32, i32)>, // Pedidos de geração ainda não recebidos
+}
+
+impl ChunkStreamer {
+    pub fn new(render_distance: i32) -> Self {
+        Self {
+            render_distance,
+            center: (0, 0),
+            pending: HashSet::new(),
+        }
+    }
+
+    pub fn chunk_at(position: Vec3) -> (i32, i32) {
+        (
+            (position.x.floor() as i32).div_euclid(CHUNK_SIZE),
+            (position.z.floor() as i32).div_euclid(CHUNK_SIZE),
+        )
+    }
+
+    pub fn in_range(&self, center: (i32, i32), chunk: (i32, i32)) -> bool {
+        (chunk.0 - center.0).abs() <= self.render_distance
+            && (chunk.1 - center.1).abs() <= self.render_distance
+    }
+
+    // Chunks dentro do raio que ainda não existem, do mais perto para o mais longe
+    pub fn missing_chunks(&self, world: &World, center: (i32, i32)) -> Vec<(i32, i32)> {
+        let r = self.render_distance;
+        let mut missing: Vec<(i32, i32)> = (-r..=r)
+            .flat_map(|dx| (-r..=r).map(move |dz| (center.0 + dx, center.1 + dz)))
+            .filter(|&(x, z)| !world.has_chunk(x, z))
+            .collect();
+        missing.sort_by_key(|&(x, z)| (x - center.0).pow(2) + (z - center.1).pow(2));
+        missing
+    }
+
+    pub fn update(&mut self, world: &mut World, workers: &WorkerPool, position: Vec3) -> StreamUpdate {
+        let center = Self::chunk_at(position);
+        self.center = center;
+        workers.set_center(center);
+
+        let update = StreamUpdate {
+            unloaded: world
+                .loaded_chunks()
+                .filter(|&chunk| !self.in_range(center, chunk))
+                .collect(),
+        };
+        // Os workers salvam antes de qualquer novo pedido de geração poder ler o chunk do disco
+        for &(x, z) in &update.unloaded {
+            if let Some(chunk) = world.remove_chunk(x, z) {
+                workers.submit(Task::Save(chunk));
+            }
+        }
+
+        // Cancela o que saiu do alcance antes de ficar pronto
+        let render_distance = self.render_distance;
+        let in_range = |(x, z): (i32, i32)| {
+            (x - center.0).abs() <= render_distance && (z - center.1).abs() <= render_distance
+        };
+        self.pending.retain(|&chunk| in_range(chunk));
+        workers.cancel(in_range);
+
+        for (x, z) in self.missing_chunks(world, center) {
+            if self.pending.insert((x, z)) {
+                workers.submit(Task::Generate { x, z });
+            }
+        }
+
+        update
+    }
+
+    // Recebe um chunk gerado; descarta se ele já saiu do alcance
+    pub fn accept(&mut self, world: &mut World, chunk: Chunk) -> bool {
+        let key = (chunk.x, chunk.z);
+        if !self.pending.remove(&key) || !self.in_range(self.center, key) {
+            return false;
+        }
+        world.insert_chunk(chunk);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use crate::game::world::generation::WorldGenerator;
+    use crate::game::world::workers::TaskResult;
+
+    fn fill(streamer: &mut ChunkStreamer, world: &mut World, workers: &WorkerPool, position: Vec3) {
+        streamer.update(world, workers, position);
+        while !streamer.pending.is_empty() {
+            if let Some(TaskResult::Generated(chunk)) = workers.wait_result() {
+                streamer.accept(world, chunk);
+            }
+        }
+    }
+
+    #[test]
+    fn loads_radius_and_unloads_out_of_range() {
+        let workers = WorkerPool::new(Arc::new(WorldGenerator::new(3)), Arc::default(), None, 2);
+        let mut streamer = ChunkStreamer::new(1);
+        let mut world = World::new();
+
+        fill(&mut streamer, &mut world, &workers, Vec3::new(8.0, 70.0, 8.0));
+        assert_eq!(world.loaded_chunks().count(), 9);
+
+        // Anda dois chunks para +X: as colunas x = -1 e x = 0 saem do alcance
+        let moved = streamer.update(&mut world, &workers, Vec3::new(40.0, 70.0, 8.0));
+        let mut unloaded = moved.unloaded.clone();
+        unloaded.sort();
+        assert_eq!(unloaded, vec![(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 0), (0, 1)]);
+
+        fill(&mut streamer, &mut world, &workers, Vec3::new(40.0, 70.0, 8.0));
+        assert!(world.loaded_chunks().all(|c| streamer.in_range((2, 0), c)));
+        assert_eq!(world.loaded_chunks().count(), 9);
+    }
+
+    #[test]
+    fn late_chunks_outside_range_are_discarded() {
+        let mut streamer = ChunkStreamer::new(1);
+        let mut world = World::new();
+        streamer.pending.insert((5, 5));
+        assert!(!streamer.accept(&mut world, Chunk::new(5, 5)));
+        assert!(!world.has_chunk(5, 5));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,337 @@
+use std::collections::HashSet;
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use crate::game::blocks::model::BlockModels;
+use super::World;
+use super::anvil::SharedStorage;
+use super::chunk::Chunk;
+use super::culling::SectionVisibility;
+use super::generation::WorldGenerator;
+use super::mesher::{mesh_section, SectionMesh};
+
+pub enum Task {
+    Generate { x: i32, z: i32 },
+    // Refaz só as `sections` pedidas; `snapshot` contém o chunk e os vizinhos necessários para as faces da borda
+    Mesh { x: i32, z: i32, version: u64, sections: Vec<usize>, snapshot: World },
+    // Chunk descarregado; gerar de novo a mesma posição espera o salvamento terminar
+    Save(Chunk),
+}
+
+impl Task {
+    pub fn chunk(&self) -> (i32, i32) {
+        match *self {
+            Task::Generate { x, z } | Task::Mesh { x, z, .. } => (x, z),
+            Task::Save(ref chunk) => (chunk.x, chunk.z),
+        }
+    }
+}
+
+pub enum TaskResult {
+    Generated(Chunk),
+    // Cada seção vem com as ligações entre suas faces, usadas no cave culling
+    Meshed { x: i32, z: i32, version: u64, sections: Vec<(usize, SectionMesh, SectionVisibility)> },
+}
+
+// Fila compartilhada; a prioridade é a distância até o chunk do jogador
+#[derive(Default)]
+struct Queue {
+    tasks: Vec<Task>,
+    center: (i32, i32),
+    saving: HashSet<(i32, i32)>, // Chunks com salvamento na fila ou em andamento
+    shutdown: bool,
+}
+
+impl Queue {
+    fn distance(&self, task: &Task) -> i32 {
+        let (x, z) = task.chunk();
+        (x - self.center.0).pow(2) + (z - self.center.1).pow(2)
+    }
+
+    // Salvamentos primeiro, depois o mais perto; a busca linear reflete na hora mudanças de `center`.
+    // Um chunk ainda sendo salvo não pode ser lido do disco, então a geração dele fica na fila
+    fn pop_nearest(&mut self) -> Option<Task> {
+        let index = (0..self.tasks.len())
+            .filter(|&i| match self.tasks[i] {
+                Task::Generate { x, z } => !self.shutdown && !self.saving.contains(&(x, z)),
+                Task::Mesh { .. } => !self.shutdown,
+                Task::Save(_) => true,
+            })
+            .min_by_key(|&i| (!matches!(self.tasks[i], Task::Save(_)), self.distance(&self.tasks[i])))?;
+        Some(self.tasks.swap_remove(index))
+    }
+}
+
+type Shared = Arc<(Mutex<Queue>, Condvar)>;
+
+// Gera e calcula as faces visíveis dos chunks fora da thread de renderização
+pub struct WorkerPool {
+    shared: Shared,
+    results: mpsc::Receiver<TaskResult>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    // Com `storage`, chunks já salvos são carregados do disco em vez de gerados
+    pub fn new(
+        generator: Arc<WorldGenerator>,
+        models: Arc<BlockModels>,
+        storage: Option<SharedStorage>,
+        threads: usize,
+    ) -> Self {
+        let shared: Shared = Arc::new((Mutex::new(Queue::default()), Condvar::new()));
+        let (sender, results) = mpsc::channel();
+
+        let threads = (0..threads.max(1))
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                let generator = Arc::clone(&generator);
+                let models = Arc::clone(&models);
+                let storage = storage.clone();
+                let sender = sender.clone();
+                thread::spawn(move || worker_loop(&shared, &generator, &models, storage.as_ref(), &sender))
+            })
+            .collect();
+
+        Self { shared, results, threads }
+    }
+
+    // Deixa uma thread livre para a renderização
+    pub fn default_threads() -> usize {
+        thread::available_parallelism().map_or(1, |n| n.get().saturating_sub(1).max(1))
+    }
+
+    pub fn submit(&self, task: Task) {
+        let (queue, condvar) = &*self.shared;
+        let mut queue = queue.lock().unwrap();
+        if let Task::Save(chunk) = &task {
+            queue.saving.insert((chunk.x, chunk.z));
+        }
+        queue.tasks.push(task);
+        condvar.notify_one();
+    }
+
+    pub fn set_center(&self, center: (i32, i32)) {
+        self.shared.0.lock().unwrap().center = center;
+    }
+
+    // Remove da fila as tarefas cujo chunk não é mais desejado; salvamentos nunca são cancelados
+    pub fn cancel(&self, mut wanted: impl FnMut((i32, i32)) -> bool) {
+        self.shared.0.lock().unwrap().tasks.retain(|task| matches!(task, Task::Save(_)) || wanted(task.chunk()));
+    }
+
+    pub fn try_results(&self) -> impl Iterator<Item = TaskResult> + '_ {
+        self.results.try_iter()
+    }
+
+    // Bloqueia até o próximo resultado (usado nos testes)
+    #[cfg(test)]
+    pub fn wait_result(&self) -> Option<TaskResult> {
+        self.results.recv().ok()
+    }
+}
+
+// Os workers terminam os salvamentos pendentes antes de sair
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        let (queue, condvar) = &*self.shared;
+        queue.lock().unwrap().shutdown = true;
+        condvar.notify_all();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn worker_loop(
+    shared: &Shared,
+    generator: &WorldGenerator,
+    models: &BlockModels,
+    storage: Option<&SharedStorage>,
+    sender: &mpsc::Sender<TaskResult>,
+) {
+    let (queue, condvar) = &**shared;
+    loop {
+        let task = {
+            let mut queue = queue.lock().unwrap();
+            loop {
+                if let Some(task) = queue.pop_nearest() {
+                    break task;
+                }
+                if queue.shutdown {
+                    return;
+                }
+                queue = condvar.wait(queue).unwrap();
+            }
+        };
+
+        let result = match task {
+            Task::Generate { x, z } => TaskResult::Generated(lit(load_or_generate(generator, storage, x, z))),
+            Task::Mesh { x, z, version, sections, snapshot } => TaskResult::Meshed {
+                x,
+                z,
+                version,
+                sections: sections
+                    .into_iter()
+                    .map(|section| {
+                        let mesh = mesh_section(&snapshot, models, x, z, section);
+                        (section, mesh, SectionVisibility::compute(&snapshot, x, z, section))
+                    })
+                    .collect(),
+            },
+            Task::Save(chunk) => {
+                if let Some(storage) = storage {
+                    if let Err(err) = storage.lock().unwrap().save_chunk(&chunk) {
+                        eprintln!("Failed to save chunk ({}, {}): {}", chunk.x, chunk.z, err);
+                    }
+                }
+                // Libera a geração que estiver esperando por este chunk
+                queue.lock().unwrap().saving.remove(&(chunk.x, chunk.z));
+                condvar.notify_all();
+                continue;
+            }
+        };
+
+        if sender.send(result).is_err() {
+            return;
+        }
+    }
+}
+
+// Um chunk salvo corrompido é regerado em vez de derrubar o worker
+fn load_or_generate(generator: &WorldGenerator, storage: Option<&SharedStorage>, x: i32, z: i32) -> Chunk {
+    if let Some(storage) = storage {
+        match storage.lock().unwrap().load_chunk(x, z) {
+            Ok(Some(chunk)) => return chunk,
+            Ok(None) => {}
+            Err(err) => eprintln!("Failed to load chunk ({}, {}): {}", x, z, err),
+        }
+    }
+    generator.generate_chunk(x, z)
+}
+
+// Calcula a luz própria do chunk num mundo só com ele; a das bordas é feita ao inserir no mundo
+fn lit(chunk: Chunk) -> Chunk {
+    let (x, z) = (chunk.x, chunk.z);
+    let mut world = World::new();
+    world.insert_chunk(chunk);
+    world.light_chunk(x, z);
+    world.remove_chunk(x, z).expect("chunk was just inserted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::world::light::LightKind;
+
+    #[test]
+    fn queue_pops_nearest_to_current_center() {
+        let mut queue = Queue::default();
+        for (x, z) in [(5, 5), (1, 0), (-3, 2)] {
+            queue.tasks.push(Task::Generate { x, z });
+        }
+
+        assert_eq!(queue.pop_nearest().unwrap().chunk(), (1, 0));
+        queue.center = (-3, 3);
+        assert_eq!(queue.pop_nearest().unwrap().chunk(), (-3, 2));
+        assert_eq!(queue.pop_nearest().unwrap().chunk(), (5, 5));
+        assert!(queue.pop_nearest().is_none());
+    }
+
+    #[test]
+    fn pool_generates_submitted_chunks() {
+        let pool = WorkerPool::new(Arc::new(WorldGenerator::new(9)), Arc::default(), None, 2);
+        pool.submit(Task::Generate { x: 2, z: -1 });
+
+        match pool.wait_result() {
+            Some(TaskResult::Generated(chunk)) => {
+                assert_eq!((chunk.x, chunk.z), (2, -1));
+                // Já vem com a luz calculada: o céu não chega no fundo do mundo
+                assert_eq!(chunk.light(0, 1, 0, LightKind::Sky), 0);
+            }
+            _ => panic!("esperava um chunk gerado"),
+        }
+    }
+
+    #[test]
+    fn cancelled_tasks_are_dropped_from_queue() {
+        let pool = WorkerPool::new(Arc::new(WorldGenerator::new(9)), Arc::default(), None, 1);
+        // Segura a fila para que nenhum worker pegue as tarefas antes do cancelamento
+        {
+            let mut queue = pool.shared.0.lock().unwrap();
+            queue.tasks.push(Task::Generate { x: 0, z: 0 });
+            queue.tasks.push(Task::Generate { x: 9, z: 9 });
+        }
+        pool.cancel(|(x, _)| x < 5);
+        pool.shared.1.notify_all();
+
+        match pool.wait_result() {
+            Some(TaskResult::Generated(chunk)) => assert_eq!((chunk.x, chunk.z), (0, 0)),
+            _ => panic!("esperava um chunk gerado"),
+        }
+        assert!(pool.shared.0.lock().unwrap().tasks.is_empty());
+    }
+
+    #[test]
+    fn saved_chunks_are_loaded_instead_of_generated() {
+        use crate::game::blocks::BlockId;
+        use crate::game::world::anvil::RegionStorage;
+
+        let dir = std::env::temp_dir().join(format!("betacraft-workers-{}", std::process::id()));
+        let mut storage = RegionStorage::new(&dir).unwrap();
+        let mut chunk = Chunk::new(4, 4);
+        chunk.set_block(1, 1, 1, BlockId(1));
+        storage.save_chunk(&chunk).unwrap();
+
+        let pool = WorkerPool::new(Arc::new(WorldGenerator::new(9)), Arc::default(), Some(storage.shared()), 1);
+        pool.submit(Task::Generate { x: 4, z: 4 });
+        match pool.wait_result() {
+            Some(TaskResult::Generated(loaded)) => assert_eq!(loaded.blocks().count(), 1),
+            _ => panic!("esperava um chunk carregado"),
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn generation_waits_for_pending_save_of_the_same_chunk() {
+        let mut queue = Queue::default();
+        queue.saving.insert((0, 0));
+        queue.tasks.push(Task::Generate { x: 0, z: 0 });
+        queue.tasks.push(Task::Generate { x: 7, z: 7 });
+        queue.tasks.push(Task::Save(Chunk::new(3, 3)));
+
+        // O salvamento vem antes mesmo estando mais longe, e (0, 0) fica esperando
+        assert!(matches!(queue.pop_nearest(), Some(Task::Save(_))));
+        assert_eq!(queue.pop_nearest().unwrap().chunk(), (7, 7));
+        assert!(queue.pop_nearest().is_none());
+        queue.saving.remove(&(0, 0));
+        assert_eq!(queue.pop_nearest().unwrap().chunk(), (0, 0));
+    }
+
+    #[test]
+    fn unloaded_chunks_are_saved_before_being_loaded_again() {
+        use crate::game::blocks::BlockId;
+        use crate::game::world::anvil::RegionStorage;
+
+        let dir = std::env::temp_dir().join(format!("betacraft-workers-save-{}", std::process::id()));
+        let storage = RegionStorage::new(&dir).unwrap().shared();
+        let pool = WorkerPool::new(Arc::new(WorldGenerator::new(9)), Arc::default(), Some(Arc::clone(&storage)), 2);
+        let mut chunk = Chunk::new(-2, 5);
+        chunk.set_block(3, 3, 3, BlockId(1));
+        pool.submit(Task::Save(chunk));
+        pool.submit(Task::Generate { x: -2, z: 5 });
+        match pool.wait_result() {
+            Some(TaskResult::Generated(loaded)) => assert_eq!(loaded.blocks().count(), 1),
+            _ => panic!("esperava o chunk salvo"),
+        }
+
+        // Salvamentos ainda na fila terminam quando o pool é destruído
+        for x in 10..14 {
+            pool.submit(Task::Save(Chunk::new(x, 0)));
+        }
+        drop(pool);
+        for x in 10..14 {
+            assert!(storage.lock().unwrap().load_chunk(x, 0).unwrap().is_some(), "{}", x);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Limite de aninhamento, para que um arquivo malicioso não estoure a pilha
+const MAX_DEPTH: usize = 128;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(BTreeMap<String, Json>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonError {
+    pub pos: usize, // Em bytes
+    pub reason: &'static str,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON at byte {}: {}", self.pos, self.reason)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Json::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Json::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&BTreeMap<String, Json>> {
+        match self {
+            Json::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+}
+
+// JSON estrito (RFC 8259), usado pelas definições de bloco e pelos resource packs
+pub fn parse(text: &str) -> Result<Json, JsonError> {
+    let mut parser = Parser { text, pos: 0 };
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos != text.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, reason: &'static str) -> JsonError {
+        JsonError { pos: self.pos, reason }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error("unexpected character"));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn accept(&mut self, expected: u8) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json, JsonError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => {
+                for (word, value) in [("true", Json::Bool(true)), ("false", Json::Bool(false)), ("null", Json::Null)] {
+                    if self.text[self.pos..].starts_with(word) {
+                        self.pos += word.len();
+                        return Ok(value);
+                    }
+                }
+                Err(self.error("expected a value"))
+            }
+            None => Err(self.error("unexpected end of JSON")),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Json, JsonError> {
+        self.expect(b'{')?;
+        let mut map = BTreeMap::new();
+        if self.accept(b'}') {
+            return Ok(Json::Object(map));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a key"));
+            }
+            let key = self.string()?;
+            self.expect(b':')?;
+            map.insert(key, self.value(depth + 1)?);
+            if !self.accept(b',') {
+                break;
+            }
+        }
+        self.expect(b'}')?;
+        Ok(Json::Object(map))
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Json, JsonError> {
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        if self.accept(b']') {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value(depth + 1)?);
+            if !self.accept(b',') {
+                break;
+            }
+        }
+        self.expect(b']')?;
+        Ok(Json::Array(items))
+    }
+
+    fn number(&mut self) -> Result<Json, JsonError> {
+        let start = self.pos;
+        let bytes = &self.text.as_bytes()[start..];
+        let len = bytes
+            .iter()
+            .position(|b| !matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+            .unwrap_or(bytes.len());
+        let token = &self.text[start..start + len];
+        match token.parse() {
+            Ok(value) if is_json_number(token) => {
+                self.pos += len;
+                Ok(Json::Number(value))
+            }
+            _ => Err(self.error("invalid number")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.pos += 1; // Aspas de abertura
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            // Caracteres fora do BMP vêm como um par de surrogates: \ud83d\ude00
+                            let value = match hex4(&mut chars) {
+                                Some(high @ 0xD800..=0xDBFF) => {
+                                    let low = match (chars.next(), chars.next()) {
+                                        (Some((_, '\\')), Some((_, 'u'))) => hex4(&mut chars),
+                                        _ => None,
+                                    };
+                                    low.filter(|low| (0xDC00..=0xDFFF).contains(low))
+                                        .map(|low| 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+                                }
+                                other => other,
+                            };
+                            match value.and_then(char::from_u32) {
+                                Some(c) => c,
+                                None => {
+                                    self.pos += offset;
+                                    return Err(self.error("invalid unicode escape"));
+                                }
+                            }
+                        }
+                        _ => {
+                            self.pos += offset;
+                            return Err(self.error("invalid escape"));
+                        }
+                    };
+                    out.push(escaped);
+                }
+                c if (c as u32) < 0x20 => {
+                    self.pos += offset;
+                    return Err(self.error("control character in string"));
+                }
+                c => out.push(c),
+            }
+        }
+        self.pos = self.text.len();
+        Err(self.error("unterminated string"))
+    }
+}
+
+fn hex4(chars: &mut std::str::CharIndices) -> Option<u32> {
+    let hex: String = chars.take(4).map(|(_, c)| c).collect();
+    u32::from_str_radix(&hex, 16).ok().filter(|_| hex.len() == 4)
+}
+
+// Gramática do JSON: -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?; o `parse` do Rust aceita mais ("1.", "+1")
+fn is_json_number(token: &str) -> bool {
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let mut rest = token.strip_prefix('-').unwrap_or(token);
+    let int = digits(rest);
+    if int == 0 || (int > 1 && rest.starts_with('0')) {
+        return false;
+    }
+    rest = &rest[int..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = digits(fraction);
+        if len == 0 {
+            return false;
+        }
+        rest = &fraction[len..];
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        let len = digits(exponent);
+        if len == 0 {
+            return false;
+        }
+        rest = &exponent[len..];
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_values() {
+        let value = parse(r#" { "id": "minecraft:stone", "hardness": 1.5, "tags": [true, null, -2e3], "empty": {} } "#).unwrap();
+        assert_eq!(value.get("id").and_then(Json::as_str), Some("minecraft:stone"));
+        assert_eq!(value.get("hardness").and_then(Json::as_f64), Some(1.5));
+        assert_eq!(
+            value.get("tags").and_then(Json::as_array),
+            Some(&[Json::Bool(true), Json::Null, Json::Number(-2000.0)][..])
+        );
+        assert_eq!(value.get("empty").and_then(Json::as_object).map(BTreeMap::len), Some(0));
+    }
+
+    #[test]
+    fn decodes_escapes() {
+        assert_eq!(parse(r#""a\"b\\c\/\né😀""#).unwrap(), Json::String("a\"b\\c/\né😀".to_string()));
+        assert_eq!(parse(r#""\ud83d\ude00\u00e9""#).unwrap(), Json::String("😀é".to_string()));
+        assert!(parse(r#""\ud83d""#).is_err());
+        assert!(parse(r#""\x""#).is_err());
+        assert!(parse("\"a\nb\"").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        for text in ["", "{", "[1,]", "{\"a\" 1}", "{a: 1}", "01", "1.", ".5", "+1", "1e", "tru", "[1] 2", "'a'"] {
+            assert!(parse(text).is_err(), "{:?}", text);
+        }
+        assert_eq!(parse("[1,]").unwrap_err().pos, 3);
+        assert_eq!(parse(&"[".repeat(1000)).unwrap_err().reason, "nested too deeply");
+    }
+
+    #[test]
+    fn accepts_valid_numbers() {
+        for (text, value) in [("0", 0.0), ("-0.5", -0.5), ("10", 10.0), ("1E2", 100.0), ("2.5e-1", 0.25)] {
+            assert_eq!(parse(text).unwrap(), Json::Number(value), "{}", text);
+        }
+    }
+}
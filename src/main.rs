@@ -1,16 +1,77 @@
 use gl::types::*;
 use glfw::{Action, Context, Key};
 use glam::{Mat4, Vec3};
-use std::collections::HashMap;
-use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
 
 mod opengl;
 mod game;
+// Parser completo; nem todo acessor é usado ainda
+#[allow(dead_code)]
+mod json;
+// Codec completo; nem todo formato baseado em NBT (rede, estruturas) é usado ainda
+#[allow(dead_code)]
+mod nbt;
 
-use game::blocks::Block;
+use game::atlas::{TextureAtlas, Tile};
+use game::blocks::definitions::load_definitions;
+use game::blocks::model::{resolve_appearances, BlockModels};
+use game::blocks::registry::{install_registry, BlockRegistry};
+use game::blocks::{registry, BlockId};
+use game::hud::{hearts, hotbar_layout, Heart};
+use game::interaction::{self, Mining, DESTROY_STAGES};
+use game::inventory::{max_stack_size, ItemStack};
+use game::fog::{self, Fog};
+use game::options::Options;
+use game::resources::ResourceManager;
+use game::sky;
+use game::world::World;
+use game::world::anvil::RegionStorage;
+use game::world::level::{GameMode, LevelData, PlayerData, TICKS_PER_SECOND};
+use game::world::mesher::{affected_sections, block_overlay};
+use game::world::chunk::{SECTION_COUNT, SECTION_HEIGHT};
+use game::world::culling::{section_bounds, visible_sections, CullingStats, Frustum, SectionVisibility};
+use opengl::mesh::GpuMesh;
+use game::world::generation::WorldGenerator;
+use game::world::streaming::ChunkStreamer;
+use game::world::workers::{Task, TaskResult, WorkerPool};
 use game::{Player, PlayerInput};
 
+const WORLD_DIR: &str = "saves/world";
+const BLOCKS_DIR: &str = "blocks";
+const RESOURCE_PACKS_DIR: &str = "resourcepacks";
+const BUILTIN_PACK: &str = "resources";
+const OPTIONS_FILE: &str = "options.txt";
+const HOTBAR_KEYS: [Key; 9] = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9];
+// Horas do dia que F7 percorre: dia, meio-dia, noite e meia-noite, como no `/time set`
+const TIME_PRESETS: [(i64, &str); 4] = [(1000, "day"), (6000, "noon"), (13000, "night"), (18000, "midnight")];
+
 fn main() {
+    // Resource packs do usuário sobre o pack embutido; o primeiro que tem um asset vence
+    let resources = ResourceManager::load(Path::new(RESOURCE_PACKS_DIR), Path::new(BUILTIN_PACK))
+        .expect("Failed to load the built-in resource pack");
+    for pack in resources.packs() {
+        println!("Resource pack: {} ({})", pack.name, pack.meta.description);
+    }
+
+    // Blocos definidos em blocks/*.json; precisa vir antes de qualquer uso do registro. Os modelos
+    // são resolvidos antes de instalar porque blocos que não são cubos inteiros não escondem os vizinhos
+    let block_definitions = load_definitions(Path::new(BLOCKS_DIR)).expect("Failed to load block definitions");
+    let mut block_registry = BlockRegistry::from_definitions(&block_definitions);
+    let (appearances, model_errors) = resolve_appearances(
+        &block_definitions,
+        &mut block_registry,
+        |name| resources.model(name),
+        |name| resources.blockstate(name),
+    );
+    for err in model_errors {
+        eprintln!("{}", err);
+    }
+    if install_registry(block_registry).is_err() {
+        unreachable!("block registry used before the definitions were loaded");
+    }
+
     let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
     glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
     glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
@@ -23,68 +84,43 @@ fn main() {
     window.set_key_polling(true);
     window.set_cursor_pos_polling(true);
     window.set_mouse_button_polling(true);
+    window.set_scroll_polling(true);
     window.set_cursor_mode(glfw::CursorMode::Disabled);
 
     gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
 
-    let block_size: f32 = 1.0;
-
-    let face_vertices: [[f32; 30]; 6] = [
-        [-0.5, -0.5,  0.5, 0.0, 0.0,  0.5, -0.5,  0.5, 1.0, 0.0,  0.5,  0.5,  0.5, 1.0, 1.0,
-         -0.5, -0.5,  0.5, 0.0, 0.0,  0.5,  0.5,  0.5, 1.0, 1.0, -0.5,  0.5,  0.5, 0.0, 1.0],
-        [-0.5, -0.5, -0.5, 0.0, 0.0,  0.5, -0.5, -0.5, 1.0, 0.0,  0.5,  0.5, -0.5, 1.0, 1.0,
-         -0.5, -0.5, -0.5, 0.0, 0.0,  0.5,  0.5, -0.5, 1.0, 1.0, -0.5,  0.5, -0.5, 0.0, 1.0],
-        [-0.5,  0.5, -0.5, 0.0, 0.0,  0.5,  0.5, -0.5, 1.0, 0.0,  0.5,  0.5,  0.5, 1.0, 1.0,
-         -0.5,  0.5, -0.5, 0.0, 0.0,  0.5,  0.5,  0.5, 1.0, 1.0, -0.5,  0.5,  0.5, 0.0, 1.0],
-        [-0.5, -0.5, -0.5, 0.0, 0.0,  0.5, -0.5, -0.5, 1.0, 0.0,  0.5, -0.5,  0.5, 1.0, 1.0,
-         -0.5, -0.5, -0.5, 0.0, 0.0,  0.5, -0.5,  0.5, 1.0, 1.0, -0.5, -0.5,  0.5, 0.0, 1.0],
-        [-0.5, -0.5, -0.5, 0.0, 0.0, -0.5, -0.5,  0.5, 1.0, 0.0, -0.5,  0.5,  0.5, 1.0, 1.0,
-         -0.5, -0.5, -0.5, 0.0, 0.0, -0.5,  0.5,  0.5, 1.0, 1.0, -0.5,  0.5, -0.5, 0.0, 1.0],
-        [ 0.5, -0.5, -0.5, 0.0, 0.0,  0.5, -0.5,  0.5, 1.0, 0.0,  0.5,  0.5,  0.5, 1.0, 1.0,
-          0.5, -0.5, -0.5, 0.0, 0.0,  0.5,  0.5,  0.5, 1.0, 1.0,  0.5,  0.5, -0.5, 0.0, 1.0],
-    ];
-
-    let mut vao = 0;
-    let mut vbo = 0;
-    unsafe {
-        gl::GenVertexArrays(1, &mut vao);
-        gl::GenBuffers(1, &mut vbo);
-
-        gl::BindVertexArray(vao);
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            (face_vertices[0].len() * 6 * std::mem::size_of::<f32>()) as GLsizeiptr,
-            face_vertices.as_ptr() as *const _,
-            gl::STATIC_DRAW,
-        );
-
-        gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 5 * std::mem::size_of::<f32>() as GLsizei, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
-        gl::VertexAttribPointer(1, 2, gl::FLOAT, gl::FALSE, 5 * std::mem::size_of::<f32>() as GLsizei, (3 * std::mem::size_of::<f32>()) as *const _);
-        gl::EnableVertexAttribArray(1);
-    }
-
     let vertex_shader_source = r#"
         #version 330 core
         layout(location = 0) in vec3 aPos;
         layout(location = 1) in vec2 aTexCoord;
-        uniform mat4 model;
+        layout(location = 2) in vec3 aNormal;
+        layout(location = 3) in vec4 aTile;
+        layout(location = 4) in float aLayer;
+        layout(location = 5) in vec3 aTint;
+        layout(location = 6) in vec2 aLight;
+        layout(location = 7) in float aAo;
         uniform mat4 view;
         uniform mat4 projection;
         out vec2 TexCoord;
-        out vec3 FragPos;
         out vec3 Normal;
+        out vec4 Tile;
+        flat out float Layer;
+        out vec3 Tint;
+        out vec2 Light;
+        out float Ao;
+        out float Distance;
         void main() {
-            gl_Position = projection * view * model * vec4(aPos, 1.0);
+            // Os vértices das malhas de seção já estão em coordenadas do mundo
+            vec4 viewPos = view * vec4(aPos, 1.0);
+            gl_Position = projection * viewPos;
+            Distance = length(viewPos.xyz);
             TexCoord = aTexCoord;
-            FragPos = vec3(model * vec4(aPos, 1.0));
-            if (gl_VertexID < 6) Normal = vec3(0.0, 0.0, 1.0);
-            else if (gl_VertexID < 12) Normal = vec3(0.0, 0.0, -1.0);
-            else if (gl_VertexID < 18) Normal = vec3(0.0, 1.0, 0.0);
-            else if (gl_VertexID < 24) Normal = vec3(0.0, -1.0, 0.0);
-            else if (gl_VertexID < 30) Normal = vec3(-1.0, 0.0, 0.0);
-            else Normal = vec3(1.0, 0.0, 0.0);
+            Normal = aNormal;
+            Tile = aTile;
+            Layer = aLayer;
+            Tint = aTint;
+            Light = aLight;
+            Ao = aAo;
         }
     "#;
 
@@ -92,22 +128,61 @@ fn main() {
         #version 330 core
         out vec4 FragColor;
         in vec2 TexCoord;
-        in vec3 FragPos;
         in vec3 Normal;
-        uniform sampler2D texture1;
+        in vec4 Tile;
+        flat in float Layer;
+        in vec3 Tint;
+        in vec2 Light;
+        in float Ao;
+        in float Distance;
+        uniform sampler2D atlas;
+        uniform sampler2DArray layers;
+        uniform bool useArray;
         uniform float opacity = 1.0;
-        uniform vec3 lightPos;
-        uniform vec3 lightColor;
+        uniform float daylight = 1.0;
+        uniform int fogMode; // 0 linear, 1 exponencial
+        uniform float fogStart;
+        uniform float fogEnd;
+        uniform float fogDensity;
+        uniform vec3 fogColor;
+        vec4 blockColor() {
+            if (useArray) {
+                return texture(layers, vec3(TexCoord, Layer));
+            }
+            // Repete a textura dentro do tile; os gradientes vêm das UVs contínuas para o
+            // mipmap não saltar na costura entre blocos de um quad mesclado
+            vec2 uv = Tile.xy + fract(TexCoord) * Tile.zw;
+            return textureGrad(atlas, uv, dFdx(TexCoord) * Tile.zw, dFdy(TexCoord) * Tile.zw);
+        }
+        // Curva de brilho do vanilla: os primeiros níveis abaixo de 15 escurecem pouco
+        float brightness(float level) {
+            return level / (4.0 - 3.0 * level);
+        }
+        // Quanto da cor do bloco sobra na distância do fragmento
+        float fogVisibility() {
+            if (fogMode == 1) {
+                return exp(-fogDensity * Distance);
+            }
+            return clamp((fogEnd - Distance) / (fogEnd - fogStart), 0.0, 1.0);
+        }
+        // Topo mais claro, base mais escura e lados no meio, como no vanilla
+        float faceShade(vec3 normal) {
+            if (normal.y > 0.5) return 1.0;
+            if (normal.y < -0.5) return 0.5;
+            return abs(normal.z) > 0.5 ? 0.8 : 0.6;
+        }
         void main() {
-            vec4 texColor = texture(texture1, TexCoord);
-            float ambientStrength = 0.3;
-            vec3 ambient = ambientStrength * lightColor;
-            vec3 norm = normalize(Normal);
-            vec3 lightDir = normalize(lightPos - FragPos);
-            float diff = max(dot(norm, lightDir), 0.0);
-            vec3 diffuse = diff * lightColor;
-            vec3 result = (ambient + diffuse) * texColor.rgb;
-            FragColor = vec4(result, texColor.a * opacity);
+            vec4 texColor = blockColor();
+            // Pixels vazios de plantas e grades não escrevem profundidade
+            if (texColor.a < 0.1) {
+                discard;
+            }
+            texColor.rgb *= Tint;
+            // Vale o mais forte entre céu (mais fraco à noite) e blocos, interpolado entre os cantos
+            // junto com a oclusão; um mínimo para cavernas não ficarem pretas
+            float light = mix(0.05, 1.0, brightness(max(Light.x * daylight, Light.y)));
+            vec3 color = texColor.rgb * light * Ao * faceShade(normalize(Normal));
+            FragColor = vec4(mix(fogColor, color, fogVisibility()), texColor.a * opacity);
         }
     "#;
 
@@ -119,90 +194,148 @@ fn main() {
         gl::DeleteShader(vertex_shader);
         gl::DeleteShader(fragment_shader);
         gl::Enable(gl::DEPTH_TEST);
-        gl::ClearColor(0.2, 0.4, 0.8, 1.0);
         gl::Enable(gl::BLEND);
         gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
     }
 
-    let mut block_textures: HashMap<String, [GLuint; 6]> = HashMap::new();
-    let dirt_texture = game::load_texture("textures/dirt.png");
-    block_textures.insert("minecraft:dirt".to_string(), [dirt_texture; 6]);
-    let grass_top = game::load_texture("textures/grass/grass_top.png");
-    let grass_side = game::load_texture("textures/grass/grass_side.png");
-    block_textures.insert("minecraft:grass_block".to_string(), [grass_side, grass_side, grass_top, dirt_texture, grass_side, grass_side]);
-    let stone_texture = game::load_texture("textures/stone.png");
-    block_textures.insert("minecraft:stone".to_string(), [stone_texture; 6]);
-    let water_texture = game::load_texture("textures/water.png");
-    block_textures.insert("minecraft:water".to_string(), [water_texture; 6]);
-    let oak_log_vertical = game::load_texture("textures/oak_log/oak_log_vertical.png");
-    let oak_log_sides = game::load_texture("textures/oak_log/oak_log_side.png");
-    block_textures.insert("minecraft:oak_log".to_string(), [oak_log_sides, oak_log_sides, oak_log_vertical, oak_log_vertical, oak_log_sides, oak_log_sides]);
-    let oak_leaves_texture = game::load_texture("textures/oak_leaves.png");
-    block_textures.insert("minecraft:oak_leaves".to_string(), [oak_leaves_texture; 6]);
-
-    let (mut blocks, spawn_point) = game::world::generation::assembly(16, 16);
-    update_visible_faces(&mut blocks); // Calcula faces visíveis após geração
-
-    let mut player = Player {
-        position: Vec3::new(spawn_point.0 as f32, spawn_point.1 as f32, spawn_point.2 as f32),
-        velocity: Vec3::ZERO,
-        size: Vec3::new(0.6, 1.8, 0.6),
-        on_ground: false,
-        yaw: -90.0,
-        pitch: 0.0,
-        front: Vec3::new(0.0, 0.0, -1.0),
-        up: Vec3::new(0.0, 1.0, 0.0),
-        speed: 4.317,
-    };
+    let registry = registry();
 
-    for block in &mut blocks {
-        block.is_dynamic = block.id == "minecraft:sand"; 
+    // Todas as texturas de bloco num atlas (ou num array, se o atlas passar do limite do driver)
+    let textures = resources.textures("block");
+    let (max_texture_size, max_layers) = unsafe { opengl::atlas::limits() };
+    let atlas = TextureAtlas::build(&textures, max_texture_size, max_layers)
+        .expect("Block textures exceed the GPU texture limits");
+    let gpu_atlas = unsafe { opengl::atlas::upload(&atlas) };
+
+    // Faces sem textura conhecida usam a terra
+    let missing = atlas.tiles.get("minecraft:block/dirt").copied().unwrap_or(Tile::FULL);
+    let (block_models, unknown) = BlockModels::from_appearances(&appearances, &atlas.tiles, missing);
+    for name in unknown {
+        eprintln!("Missing block texture {}", name);
     }
+    let block_models = Arc::new(block_models);
+    let destroy_tiles: Vec<Option<Tile>> = (0..DESTROY_STAGES)
+        .map(|stage| atlas.tiles.get(&format!("minecraft:block/destroy_stage_{}", stage)).copied())
+        .collect();
 
-    fn is_solid_block(blocks: &Vec<Block>, x: i32, y: i32, z: i32) -> bool {
-        blocks.iter().any(|b| b.x == x && b.y == y && b.z == z && b.id != "minecraft:water")
+    unsafe {
+        gl::UseProgram(shader_program);
+        let uniform = |name: &str| gl::GetUniformLocation(shader_program, std::ffi::CString::new(name).unwrap().as_ptr());
+        gl::Uniform1i(uniform("atlas"), opengl::atlas::ATLAS_UNIT as GLint);
+        gl::Uniform1i(uniform("layers"), opengl::atlas::ARRAY_UNIT as GLint);
+        gl::Uniform1i(uniform("useArray"), gpu_atlas.array as GLint);
     }
+    let (view_loc, proj_loc, opacity_loc) = unsafe {
+        (
+            gl::GetUniformLocation(shader_program, c"view".as_ptr()),
+            gl::GetUniformLocation(shader_program, c"projection".as_ptr()),
+            gl::GetUniformLocation(shader_program, c"opacity".as_ptr()),
+        )
+    };
+    let (daylight_loc, fog_mode_loc, fog_color_loc) = unsafe {
+        (
+            gl::GetUniformLocation(shader_program, c"daylight".as_ptr()),
+            gl::GetUniformLocation(shader_program, c"fogMode".as_ptr()),
+            gl::GetUniformLocation(shader_program, c"fogColor".as_ptr()),
+        )
+    };
+    let (fog_start_loc, fog_end_loc, fog_density_loc) = unsafe {
+        (
+            gl::GetUniformLocation(shader_program, c"fogStart".as_ptr()),
+            gl::GetUniformLocation(shader_program, c"fogEnd".as_ptr()),
+            gl::GetUniformLocation(shader_program, c"fogDensity".as_ptr()),
+        )
+    };
+
+    // Um mundo já salvo mantém seu seed; senão vem do primeiro argumento (ex.: `cargo run -- 1234`)
+    let world_dir = Path::new(WORLD_DIR);
+    let saved_level = LevelData::load(world_dir).expect("Failed to read level.dat");
+    let seed = match &saved_level {
+        Some(level) => level.seed,
+        None => std::env::args()
+            .nth(1)
+            .and_then(|arg| arg.parse::<u64>().ok())
+            .unwrap_or_else(rand::random),
+    };
+    println!("Seed: {}", seed);
+
+    // Mundo salvo no formato Anvil, legível pelo Minecraft Java e por ferramentas como o MCASelector
+    let storage = RegionStorage::new(world_dir)
+        .expect("Failed to create world directory")
+        .shared();
+
+    let generator = Arc::new(WorldGenerator::new(seed));
+    let workers = WorkerPool::new(Arc::clone(&generator), Arc::clone(&block_models), Some(Arc::clone(&storage)), WorkerPool::default_threads());
+    let mut options = Options::load(Path::new(OPTIONS_FILE)).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {}", OPTIONS_FILE, err);
+        Options::default()
+    });
+    let mut streamer = ChunkStreamer::new(options.render_distance);
+    let mut world = World::new();
+    let mut level = saved_level.unwrap_or_else(|| {
+        let level = LevelData::new(seed, generator.find_spawn_point());
+        if let Err(err) = level.save(world_dir) {
+            eprintln!("Failed to save level.dat: {}", err);
+        }
+        level
+    });
+    let spawn_point = level.spawn;
+
+    // Malha na GPU de cada seção (chunk x, chunk z, seção) e a versão do último pedido de cada uma
+    let mut section_meshes: HashMap<(i32, i32, usize), GpuMesh> = HashMap::new();
+    let mut mesh_versions: HashMap<(i32, i32, usize), u64> = HashMap::new();
+    let mut next_mesh_version: u64 = 0;
+    // Ligações entre as faces de cada seção, vindas junto com a malha
+    let mut section_visibility: HashMap<(i32, i32, usize), SectionVisibility> = HashMap::new();
 
-    fn update_visible_faces(blocks: &mut Vec<Block>) {
-        let mut block_positions: HashMap<(i32, i32, i32), String> = HashMap::new();
-        for block in blocks.iter() {
-            block_positions.insert((block.x, block.y, block.z), block.id.clone());
-        }
-    
-        for block in blocks.iter_mut() {
-            block.visible_faces = [
-                !block_positions.contains_key(&(block.x, block.y, block.z + 1))
-                    || block_positions[&(block.x, block.y, block.z + 1)] == "minecraft:water",
-                !block_positions.contains_key(&(block.x, block.y, block.z - 1))
-                    || block_positions[&(block.x, block.y, block.z - 1)] == "minecraft:water",
-                !block_positions.contains_key(&(block.x, block.y + 1, block.z))
-                    || block_positions[&(block.x, block.y + 1, block.z)] == "minecraft:water",
-                !block_positions.contains_key(&(block.x, block.y - 1, block.z))
-                    || block_positions[&(block.x, block.y - 1, block.z)] == "minecraft:water",
-                !block_positions.contains_key(&(block.x - 1, block.y, block.z))
-                    || block_positions[&(block.x - 1, block.y, block.z)] == "minecraft:water",
-                !block_positions.contains_key(&(block.x + 1, block.y, block.z))
-                    || block_positions[&(block.x + 1, block.y, block.z)] == "minecraft:water",
-            ];
+    let spawn_position = Vec3::new(spawn_point.0 as f32, spawn_point.1 as f32, spawn_point.2 as f32);
+    let mut player = Player::new();
+    player.position = spawn_position;
+    match &level.player {
+        Some(saved) => {
+            player.position = saved.position;
+            player.set_rotation(saved.yaw, saved.pitch);
+            player.inventory = saved.inventory.clone();
+            player.set_game_mode(saved.game_mode);
+            player.health = saved.health;
+        }
+        // Num mundo novo, começa no modo padrão do mundo com uma pilha de cada bloco definido, menos os fluidos
+        None => {
+            player.set_game_mode(level.game_mode);
+            for definition in block_definitions.iter().filter(|definition| !definition.properties.fluid) {
+                player.inventory.add(ItemStack::new(&definition.name, max_stack_size(&definition.name)));
+            }
         }
     }
+    let mut tick_time = 0.0;
+
+    // Blocos com gravidade que ainda podem cair
+    let mut dynamic_blocks: Vec<(i32, i32, i32)> = Vec::new();
 
-    fn update_blocks(blocks: &mut Vec<Block>, delta_time: f32) {
+    // Retorna as posições alteradas: de onde algum bloco saiu e para onde foi
+    fn update_blocks(world: &mut World, dynamic_blocks: &mut Vec<(i32, i32, i32)>, delta_time: f32) -> Vec<(i32, i32, i32)> {
         const GRAVITY: f32 = -9.81;
-        for i in 0..blocks.len() {
-            if blocks[i].is_dynamic {
-                let mut block = blocks[i].clone();
-                let mut velocity_y = 0.0;
-                velocity_y += GRAVITY * delta_time;
-                let new_y = block.y as f32 + velocity_y * delta_time;
-                if !is_solid_block(blocks, block.x, (new_y - 0.5) as i32, block.z) {
-                    block.y = new_y.round() as i32;
-                    blocks[i] = block;
-                } else {
-                    blocks[i].is_dynamic = false;
-                }
+        let mut moved = Vec::new();
+
+        dynamic_blocks.retain_mut(|(x, y, z)| {
+            let mut velocity_y = 0.0;
+            velocity_y += GRAVITY * delta_time;
+            let new_y = *y as f32 + velocity_y * delta_time;
+            if world.is_solid_block(*x, (new_y - 0.5) as i32, *z) {
+                return false;
             }
-        }
+
+            let new_y = new_y.round() as i32;
+            if new_y != *y {
+                let id = world.remove_block(*x, *y, *z);
+                world.set_block(*x, new_y, *z, id);
+                moved.push((*x, *y, *z));
+                moved.push((*x, new_y, *z));
+                *y = new_y;
+            }
+            true
+        });
+        moved
     }
 
     let mut last_x = 400.0;
@@ -212,6 +345,17 @@ fn main() {
     let mut delta_time = 0.0;
     let mut last_frame = 0.0;
     let mut input = PlayerInput::default();
+    let mut title = String::new();
+    // F3 mostra no título quantas seções o frustum e o cave culling descartaram no último quadro
+    let mut show_culling = false;
+    let mut culling_stats = CullingStats::default();
+
+    // Botão esquerdo segurado quebra o bloco mirado; o direito coloca o bloco da mão contra a face
+    let mut mining = Mining::default();
+    let mut attacking = false;
+    let mut place_requested = false;
+    let mut crack_stage = None;
+    let mut crack_mesh: Option<GpuMesh> = None;
 
     let mut crosshair_vao = 0;
     let mut crosshair_vbo = 0;
@@ -246,7 +390,8 @@ fn main() {
 
     let mut crosshair_ebo = 0;
     let crosshair_program;
-    let crosshair_texture = game::load_texture("textures/crosshair.png");
+    let crosshair_image = resources.image("assets/minecraft/textures/gui/sprites/hud/crosshair.png");
+    let crosshair_texture = game::load_texture(crosshair_image.as_ref());
     unsafe {
         gl::GenVertexArrays(1, &mut crosshair_vao);
         gl::GenBuffers(1, &mut crosshair_vbo);
@@ -271,14 +416,202 @@ fn main() {
         gl::DeleteShader(crosshair_fs);
     }
 
-    let light_pos = Vec3::new(5.0, 5.0, 5.0);
-    let light_color = Vec3::new(1.0, 1.0, 1.0);
+    // Contorno do bloco mirado: linhas pretas finas, como no vanilla
+    let outline_vertex_shader = r#"
+        #version 330 core
+        layout(location = 0) in vec3 aPos;
+        uniform mat4 view;
+        uniform mat4 projection;
+        void main() {
+            gl_Position = projection * view * vec4(aPos, 1.0);
+        }
+    "#;
+
+    let outline_fragment_shader = r#"
+        #version 330 core
+        out vec4 FragColor;
+        void main() {
+            FragColor = vec4(0.0, 0.0, 0.0, 0.4);
+        }
+    "#;
+
+    let outline_program;
+    let outline_box;
+    unsafe {
+        let outline_vs = opengl::compile::service(outline_vertex_shader, gl::VERTEX_SHADER);
+        let outline_fs = opengl::compile::service(outline_fragment_shader, gl::FRAGMENT_SHADER);
+        outline_program = opengl::link::service(outline_vs, outline_fs);
+        outline_box = opengl::lines::create();
+
+        gl::DeleteShader(outline_vs);
+        gl::DeleteShader(outline_fs);
+    }
+    let (outline_view_loc, outline_proj_loc) = unsafe {
+        (gl::GetUniformLocation(outline_program, c"view".as_ptr()), gl::GetUniformLocation(outline_program, c"projection".as_ptr()))
+    };
+
+    // Sprites da HUD e ícones dos blocos (tirados do atlas) num quad posicionado por `rect`
+    let hud_vertex_shader = r#"
+        #version 330 core
+        layout(location = 0) in vec2 aCorner;
+        uniform vec4 rect;
+        uniform vec4 uvRect;
+        out vec2 TexCoord;
+        void main() {
+            gl_Position = vec4(rect.xy + aCorner * rect.zw, 0.0, 1.0);
+            TexCoord = uvRect.xy + aCorner * uvRect.zw;
+        }
+    "#;
+
+    let hud_fragment_shader = r#"
+        #version 330 core
+        out vec4 FragColor;
+        in vec2 TexCoord;
+        uniform sampler2D image;
+        uniform sampler2DArray layers;
+        uniform bool useArray;
+        uniform float layer;
+        uniform vec3 tint;
+        void main() {
+            vec4 color = useArray ? texture(layers, vec3(TexCoord, layer)) : texture(image, TexCoord);
+            FragColor = vec4(color.rgb * tint, color.a);
+        }
+    "#;
+
+    let hud_program;
+    let hud_quad;
+    let hotbar_texture = game::load_texture(resources.image("assets/minecraft/textures/gui/sprites/hud/hotbar.png").as_ref());
+    let selection_texture =
+        game::load_texture(resources.image("assets/minecraft/textures/gui/sprites/hud/hotbar_selection.png").as_ref());
+    let heart_textures = ["container", "full", "half"].map(|name| {
+        let path = format!("assets/minecraft/textures/gui/sprites/hud/heart/{}.png", name);
+        game::load_texture(resources.image(&path).as_ref())
+    });
+    let hud_uniform = |program: GLuint, name: &str| unsafe {
+        gl::GetUniformLocation(program, std::ffi::CString::new(name).unwrap().as_ptr())
+    };
+    unsafe {
+        let hud_vs = opengl::compile::service(hud_vertex_shader, gl::VERTEX_SHADER);
+        let hud_fs = opengl::compile::service(hud_fragment_shader, gl::FRAGMENT_SHADER);
+        hud_program = opengl::link::service(hud_vs, hud_fs);
+        hud_quad = opengl::quad::create();
+
+        gl::DeleteShader(hud_vs);
+        gl::DeleteShader(hud_fs);
+        gl::UseProgram(hud_program);
+        gl::Uniform1i(hud_uniform(hud_program, "image"), opengl::atlas::ATLAS_UNIT as GLint);
+        gl::Uniform1i(hud_uniform(hud_program, "layers"), opengl::atlas::ARRAY_UNIT as GLint);
+    }
+    let (hud_rect_loc, hud_uv_loc) = (hud_uniform(hud_program, "rect"), hud_uniform(hud_program, "uvRect"));
+    let (hud_array_loc, hud_layer_loc) = (hud_uniform(hud_program, "useArray"), hud_uniform(hud_program, "layer"));
+    let hud_tint_loc = hud_uniform(hud_program, "tint");
+
+    // Céu desenhado atrás de tudo na tela inteira: degradê do horizonte para o alto, com o brilho
+    // do nascer e do pôr do sol do lado do sol
+    let sky_vertex_shader = r#"
+        #version 330 core
+        layout(location = 0) in vec2 aCorner;
+        uniform mat4 inverseViewProjection; // Só a rotação da câmera
+        out vec3 Direction;
+        void main() {
+            vec2 ndc = aCorner * 2.0 - 1.0;
+            vec4 far = inverseViewProjection * vec4(ndc, 1.0, 1.0);
+            Direction = far.xyz / far.w;
+            gl_Position = vec4(ndc, 0.0, 1.0);
+        }
+    "#;
+
+    let sky_fragment_shader = r#"
+        #version 330 core
+        out vec4 FragColor;
+        in vec3 Direction;
+        uniform vec3 skyColor;
+        uniform vec3 fogColor;
+        uniform vec4 sunrise; // Alfa 0 fora do nascer e do pôr do sol
+        uniform vec3 sunDirection;
+        void main() {
+            vec3 direction = normalize(Direction);
+            vec3 color = mix(fogColor, skyColor, smoothstep(0.0, 0.4, direction.y));
+            vec2 toward = direction.xz;
+            vec2 sun = sunDirection.xz;
+            if (sunrise.a > 0.0 && length(toward) > 0.0 && length(sun) > 0.0) {
+                float facing = max(dot(normalize(toward), normalize(sun)), 0.0);
+                float glow = sunrise.a * facing * facing * (1.0 - smoothstep(0.0, 0.5, abs(direction.y)));
+                color = mix(color, sunrise.rgb, glow);
+            }
+            FragColor = vec4(color, 1.0);
+        }
+    "#;
+
+    // Sol e lua: quads a uma unidade da câmera na direção deles, somados ao céu (preto some)
+    let celestial_vertex_shader = r#"
+        #version 330 core
+        layout(location = 0) in vec2 aCorner;
+        uniform mat4 viewProjection; // Só a rotação da câmera
+        uniform vec3 center;
+        uniform vec3 right;
+        uniform vec3 up;
+        uniform vec4 uvRect;
+        out vec2 TexCoord;
+        void main() {
+            vec2 offset = aCorner * 2.0 - 1.0;
+            gl_Position = viewProjection * vec4(center + offset.x * right + offset.y * up, 1.0);
+            // A primeira linha da imagem é o topo do quad
+            TexCoord = uvRect.xy + vec2(aCorner.x, 1.0 - aCorner.y) * uvRect.zw;
+        }
+    "#;
+
+    let celestial_fragment_shader = r#"
+        #version 330 core
+        out vec4 FragColor;
+        in vec2 TexCoord;
+        uniform sampler2D image;
+        void main() {
+            FragColor = vec4(texture(image, TexCoord).rgb, 1.0);
+        }
+    "#;
+
+    let sky_program;
+    let celestial_program;
+    let sun_texture = game::load_texture(resources.image("assets/minecraft/textures/environment/sun.png").as_ref());
+    let moon_texture = game::load_texture(resources.image("assets/minecraft/textures/environment/moon_phases.png").as_ref());
+    unsafe {
+        let sky_vs = opengl::compile::service(sky_vertex_shader, gl::VERTEX_SHADER);
+        let sky_fs = opengl::compile::service(sky_fragment_shader, gl::FRAGMENT_SHADER);
+        sky_program = opengl::link::service(sky_vs, sky_fs);
+        let celestial_vs = opengl::compile::service(celestial_vertex_shader, gl::VERTEX_SHADER);
+        let celestial_fs = opengl::compile::service(celestial_fragment_shader, gl::FRAGMENT_SHADER);
+        celestial_program = opengl::link::service(celestial_vs, celestial_fs);
+
+        gl::DeleteShader(sky_vs);
+        gl::DeleteShader(sky_fs);
+        gl::DeleteShader(celestial_vs);
+        gl::DeleteShader(celestial_fs);
+        gl::UseProgram(celestial_program);
+        gl::Uniform1i(hud_uniform(celestial_program, "image"), opengl::atlas::ATLAS_UNIT as GLint);
+    }
+    let sky_uniform = |name: &str| hud_uniform(sky_program, name);
+    let (sky_inverse_loc, sky_color_loc, sky_fog_loc) =
+        (sky_uniform("inverseViewProjection"), sky_uniform("skyColor"), sky_uniform("fogColor"));
+    let (sky_sunrise_loc, sky_sun_loc) = (sky_uniform("sunrise"), sky_uniform("sunDirection"));
+    let celestial_uniform = |name: &str| hud_uniform(celestial_program, name);
+    let (celestial_view_projection_loc, celestial_center_loc) = (celestial_uniform("viewProjection"), celestial_uniform("center"));
+    let (celestial_right_loc, celestial_up_loc, celestial_uv_loc) =
+        (celestial_uniform("right"), celestial_uniform("up"), celestial_uniform("uvRect"));
 
     while !window.should_close() {
       let current_frame = glfw.get_time() as f32;
       delta_time = current_frame - last_frame;
       last_frame = current_frame;
 
+      // O tempo do mundo anda em ticks fixos de 1/20 s
+      let tick_length = 1.0 / TICKS_PER_SECOND as f32;
+      tick_time += delta_time;
+      while tick_time >= tick_length {
+          level.tick();
+          tick_time -= tick_length;
+      }
+
       glfw.poll_events();
       for (_, event) in glfw::flush_messages(&events) {
           match event {
@@ -307,6 +640,45 @@ fn main() {
               glfw::WindowEvent::Key(Key::Space, _, Action::Release, _) => {
                   input.jump = false;
               }
+              glfw::WindowEvent::Key(Key::LeftShift, _, action, _) => {
+                  input.sneak = action != Action::Release;
+              }
+              glfw::WindowEvent::Key(Key::F4, _, Action::Press, _) => {
+                  player.set_game_mode(player.game_mode.next());
+                  println!("Game mode: {}", player.game_mode.name());
+              }
+              glfw::WindowEvent::Key(Key::F3, _, Action::Press, _) => {
+                  show_culling = !show_culling;
+              }
+              glfw::WindowEvent::Key(Key::F7, _, Action::Press, _) => {
+                  let now = level.time_of_day();
+                  let (time, name) = TIME_PRESETS
+                      .into_iter()
+                      .find(|&(time, _)| time > now)
+                      .unwrap_or(TIME_PRESETS[0]);
+                  level.set_time_of_day(time);
+                  println!("Time set to {} ({})", name, time);
+              }
+              glfw::WindowEvent::Key(Key::F8, _, Action::Press, _) => {
+                  options.cycle_render_distance();
+                  streamer.render_distance = options.render_distance;
+                  println!("Render distance: {} chunks", options.render_distance);
+              }
+              glfw::WindowEvent::Key(key, _, Action::Press, _) if HOTBAR_KEYS.contains(&key) => {
+                  if let Some(slot) = HOTBAR_KEYS.iter().position(|&hotbar_key| hotbar_key == key) {
+                      player.inventory.select(slot);
+                  }
+              }
+              glfw::WindowEvent::Scroll(_, y_offset) if y_offset != 0.0 => {
+                  // Rolar para baixo anda para a direita
+                  player.inventory.scroll(-y_offset.signum() as i32);
+              }
+              glfw::WindowEvent::MouseButton(glfw::MouseButtonLeft, action, _) => {
+                  attacking = action == Action::Press;
+              }
+              glfw::WindowEvent::MouseButton(glfw::MouseButtonRight, Action::Press, _) => {
+                  place_requested = true;
+              }
               glfw::WindowEvent::CursorPos(xpos, ypos) => {
                   let (xpos, ypos) = (xpos as f32, ypos as f32);
                   if first_mouse {
@@ -335,50 +707,323 @@ fn main() {
           }
       }
 
-        // Atualizar o player com física e movimentação WASD
-        player.update(&blocks, delta_time, &input);
-        update_blocks(&mut blocks, delta_time);
+        // Pede chunks em volta do jogador e recebe o que os workers terminaram
+        let stream = streamer.update(&mut world, &workers, player.position);
+        let mut loaded = Vec::new();
+        for result in workers.try_results() {
+            match result {
+                TaskResult::Generated(chunk) => {
+                    let key = (chunk.x, chunk.z);
+                    if streamer.accept(&mut world, chunk) {
+                        loaded.push(key);
+                    }
+                }
+                TaskResult::Meshed { x, z, version, sections } => {
+                    for (section, mesh, visibility) in sections {
+                        // Ignora resultados de pedidos já substituídos ou de chunks descarregados
+                        let key = (x, z, section);
+                        if mesh_versions.get(&key) != Some(&version) {
+                            continue;
+                        }
+                        section_visibility.insert(key, visibility);
+                        if let Some(old) = section_meshes.remove(&key) {
+                            unsafe { old.delete() };
+                        }
+                        if !mesh.is_empty() {
+                            section_meshes.insert(key, unsafe { opengl::mesh::upload(&mesh) });
+                        }
+                    }
+                }
+            }
+        }
+
+        for &(chunk_x, chunk_z) in &loaded {
+            if let Some(chunk) = world.chunk(chunk_x, chunk_z) {
+                dynamic_blocks.extend(
+                    chunk.blocks()
+                        .filter(|b| registry.has_gravity(b.id))
+                        .map(|b| (b.x, b.y, b.z)),
+                );
+            }
+        }
+        dynamic_blocks.retain(|&(x, _, z)| {
+            let ((chunk_x, chunk_z), _) = World::chunk_coords(x, z);
+            world.has_chunk(chunk_x, chunk_z)
+        });
+
+        // Atualizar o player com física e movimentação WASD (só com o chão já carregado)
+        let (player_chunk_x, player_chunk_z) = ChunkStreamer::chunk_at(player.position);
+        if world.has_chunk(player_chunk_x, player_chunk_z) {
+            player.update(&world, delta_time, &input);
+        }
+        if player.is_dead() {
+            println!("You died");
+            player.respawn(spawn_position);
+        }
+        let mut changed = update_blocks(&mut world, &mut dynamic_blocks, delta_time);
+
+        // Bloco sob a mira, mostrado no título da janela com o modo de jogo. O espectador não mira
+        let hit = match player.game_mode {
+            GameMode::Spectator => None,
+            _ => world.raycast(player.eye_position(), player.front, game::Player::REACH),
+        };
+        let hit_block = hit.map(|hit| (hit.block, world.get_block(hit.block.0, hit.block.1, hit.block.2)));
+        let mut new_title = match hit_block {
+            Some(((x, y, z), id)) => format!(
+                "Mini Minecraft [{}] - {} ({}, {}, {})",
+                player.game_mode.name(),
+                registry.get(id).name,
+                x,
+                y,
+                z
+            ),
+            None => format!("Mini Minecraft [{}]", player.game_mode.name()),
+        };
+        if show_culling {
+            new_title.push_str(&format!(
+                " | sections: {}/{} drawn (frustum {}, caves {})",
+                culling_stats.drawn,
+                culling_stats.total,
+                culling_stats.frustum,
+                culling_stats.caves
+            ));
+        }
+        if title != new_title {
+            window.set_title(&new_title);
+            title = new_title;
+        }
+
+        // No criativo tudo quebra na hora, até blocos indestrutíveis, e os blocos não acabam
+        let can_edit = player.game_mode.can_edit_blocks();
+        let mining_target = hit_block.filter(|_| can_edit).map(|(block, id)| match player.game_mode {
+            GameMode::Creative => (block, 0.0),
+            _ => (block, registry.properties(id).hardness),
+        });
+        if let Some((x, y, z)) = mining.update(mining_target, attacking, delta_time) {
+            let broken = world.remove_block(x, y, z);
+            changed.push((x, y, z));
+            // Ainda não há itens no chão: o bloco vai direto para o inventário, se couber
+            if player.game_mode.is_survival_like() {
+                player.inventory.add(ItemStack::new(&registry.get(broken).name, 1));
+            }
+            // O que tinha gravidade em cima perde o apoio
+            if registry.has_gravity(world.get_block(x, y + 1, z)) {
+                dynamic_blocks.push((x, y + 1, z));
+            }
+        }
+        if std::mem::take(&mut place_requested) && can_edit {
+            let held = player.inventory.selected_stack().and_then(|stack| registry.id(&stack.item));
+            if let (Some(block), Some((x, y, z))) = (held, hit.as_ref().and_then(interaction::placement)) {
+                // Só ocupa ar ou fluido, e nunca um espaço onde o jogador ficaria preso
+                let current = world.get_block(x, y, z);
+                let replaceable = current == BlockId::AIR || registry.is_fluid(current);
+                let traps_player = registry.is_solid(block) && interaction::blocks_player(&player, (x, y, z));
+                if replaceable && !traps_player {
+                    world.set_block(x, y, z, block);
+                    if player.game_mode.is_survival_like() {
+                        player.inventory.take_selected(1);
+                    }
+                    changed.push((x, y, z));
+                    if registry.has_gravity(block) {
+                        dynamic_blocks.push((x, y, z));
+                    }
+                }
+            }
+        }
+
+        // Rachaduras do bloco sendo quebrado; a malha só é refeita quando o estágio muda
+        if mining.stage() != crack_stage {
+            crack_stage = mining.stage();
+            if let Some(mesh) = crack_mesh.take() {
+                unsafe { mesh.delete() };
+            }
+            crack_mesh = crack_stage.and_then(|((x, y, z), stage)| {
+                let tile = destroy_tiles[stage as usize]?;
+                Some(unsafe { opengl::mesh::upload(&block_overlay(x, y, z, tile)) })
+            });
+        }
+
+        // Seções a remalhar: todas as de chunks que entraram ou saíram (e dos vizinhos, cujas
+        // faces da borda dependem deles), as encostadas em blocos que mudaram e as que tiveram a
+        // luz refeita por causa deles
+        let mut dirty_sections: HashMap<(i32, i32), HashSet<usize>> = HashMap::new();
+        let mut relit = Vec::new();
+        for &(chunk_x, chunk_z) in &loaded {
+            relit.extend(world.light_seams(chunk_x, chunk_z));
+        }
+        for &(x, y, z) in &changed {
+            relit.extend(world.update_light(x, y, z));
+        }
+        for (chunk_x, chunk_z, section) in relit {
+            dirty_sections.entry((chunk_x, chunk_z)).or_default().insert(section);
+        }
+        for &(chunk_x, chunk_z) in loaded.iter().chain(&stream.unloaded) {
+            for (dx, dz) in [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)] {
+                dirty_sections.entry((chunk_x + dx, chunk_z + dz)).or_default().extend(0..SECTION_COUNT);
+            }
+        }
+        for &(x, y, z) in &changed {
+            for (chunk_x, chunk_z, section) in affected_sections(x, y, z) {
+                dirty_sections.entry((chunk_x, chunk_z)).or_default().insert(section);
+            }
+        }
+        for ((chunk_x, chunk_z), sections) in dirty_sections {
+            if world.has_chunk(chunk_x, chunk_z) {
+                next_mesh_version += 1;
+                for &section in &sections {
+                    mesh_versions.insert((chunk_x, chunk_z, section), next_mesh_version);
+                }
+                workers.submit(Task::Mesh {
+                    x: chunk_x,
+                    z: chunk_z,
+                    version: next_mesh_version,
+                    sections: sections.into_iter().collect(),
+                    snapshot: world.snapshot_around(chunk_x, chunk_z),
+                });
+            } else {
+                for section in 0..SECTION_COUNT {
+                    if let Some(mesh) = section_meshes.remove(&(chunk_x, chunk_z, section)) {
+                        unsafe { mesh.delete() };
+                    }
+                    mesh_versions.remove(&(chunk_x, chunk_z, section));
+                    section_visibility.remove(&(chunk_x, chunk_z, section));
+                }
+            }
+        }
 
         unsafe {
+            // Debaixo d'água a neblina densa esconde o céu; fora dela a neblina tem a cor do horizonte
+            let day_time = level.day_time;
+            let eye_block = (player.eye_position() + Vec3::splat(0.5)).floor();
+            let underwater = registry.is_fluid(world.get_block(eye_block.x as i32, eye_block.y as i32, eye_block.z as i32));
+            let fog = if underwater {
+                Fog::underwater(sky::daylight(day_time))
+            } else {
+                Fog::terrain(streamer.render_distance, sky::fog_color(day_time))
+            };
+            gl::ClearColor(fog.color.x, fog.color.y, fog.color.z, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            // Céu, sol e lua só giram com a câmera; ficam atrás do terreno sem escrever profundidade
+            let sky_projection = Mat4::perspective_rh_gl(45.0_f32.to_radians(), 800.0 / 600.0, 0.1, 100.0);
+            let sky_view_projection = sky_projection * Mat4::look_at_rh(Vec3::ZERO, player.front, player.up);
+            gl::Disable(gl::DEPTH_TEST);
+            gl::UseProgram(sky_program);
+            gl::UniformMatrix4fv(sky_inverse_loc, 1, gl::FALSE, sky_view_projection.inverse().as_ref().as_ptr());
+            gl::Uniform3fv(sky_color_loc, 1, sky::sky_color(day_time).as_ref().as_ptr());
+            gl::Uniform3fv(sky_fog_loc, 1, fog.color.as_ref().as_ptr());
+            gl::Uniform4fv(sky_sunrise_loc, 1, sky::sunrise_color(day_time).unwrap_or_default().as_ref().as_ptr());
+            gl::Uniform3fv(sky_sun_loc, 1, sky::sun_direction(day_time).as_ref().as_ptr());
+            if !underwater {
+                hud_quad.draw();
+            }
+
+            gl::BlendFunc(gl::ONE, gl::ONE);
+            gl::UseProgram(celestial_program);
+            gl::UniformMatrix4fv(celestial_view_projection_loc, 1, gl::FALSE, sky_view_projection.as_ref().as_ptr());
+            let sun = sky::sun_direction(day_time);
+            // Tamanhos do vanilla (30 e 20 a 100 blocos), com os lados paralelos ao eixo Z
+            for (direction, half_size, texture, uv) in [
+                (sun, 0.15, sun_texture, Tile::FULL.rect),
+                (-sun, 0.1, moon_texture, sky::moon_phase_uv(sky::moon_phase(day_time))),
+            ]
+            .into_iter()
+            .filter(|_| !underwater)
+            {
+                gl::Uniform3fv(celestial_center_loc, 1, direction.as_ref().as_ptr());
+                gl::Uniform3fv(celestial_right_loc, 1, (Vec3::Z * half_size).as_ref().as_ptr());
+                gl::Uniform3fv(celestial_up_loc, 1, (Vec3::Z.cross(direction) * half_size).as_ref().as_ptr());
+                gl::Uniform4fv(celestial_uv_loc, 1, uv.as_ptr());
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                hud_quad.draw();
+            }
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::Enable(gl::DEPTH_TEST);
+
             gl::UseProgram(shader_program);
-            gl::BindVertexArray(vao);
+            gl::Uniform1f(daylight_loc, sky::daylight(day_time));
+            gl::Uniform1i(fog_mode_loc, fog.mode.id());
+            gl::Uniform1f(fog_start_loc, fog.start);
+            gl::Uniform1f(fog_end_loc, fog.end);
+            gl::Uniform1f(fog_density_loc, fog.density);
+            gl::Uniform3fv(fog_color_loc, 1, fog.color.as_ref().as_ptr());
 
             let view = Mat4::look_at_rh(
-                player.position + Vec3::new(0.0, player.size.y * 0.8, 0.0),
-                player.position + Vec3::new(0.0, player.size.y * 0.8, 0.0) + player.front,
+                player.eye_position(),
+                player.eye_position() + player.front,
                 player.up,
             );
 
-            let projection = Mat4::perspective_rh_gl(45.0_f32.to_radians(), 800.0 / 600.0, 0.1, 100.0);
+            // O plano de corte acompanha a distância de renderização, atrás do fim da neblina
+            let far_plane = fog::far_plane(streamer.render_distance);
+            let projection = Mat4::perspective_rh_gl(45.0_f32.to_radians(), 800.0 / 600.0, 0.1, far_plane);
 
-            let view_loc = gl::GetUniformLocation(shader_program, "view\0".as_ptr() as *const _);
-            let proj_loc = gl::GetUniformLocation(shader_program, "projection\0".as_ptr() as *const _);
             gl::UniformMatrix4fv(view_loc, 1, gl::FALSE, view.as_ref().as_ptr());
             gl::UniformMatrix4fv(proj_loc, 1, gl::FALSE, projection.as_ref().as_ptr());
 
-            let light_pos_loc = gl::GetUniformLocation(shader_program, "lightPos\0".as_ptr() as *const _);
-            let light_color_loc = gl::GetUniformLocation(shader_program, "lightColor\0".as_ptr() as *const _);
-            gl::Uniform3fv(light_pos_loc, 1, light_pos.as_ref().as_ptr());
-            gl::Uniform3fv(light_color_loc, 1, light_color.as_ref().as_ptr());
-
-            for block in &blocks {
-                let model = Mat4::from_scale(Vec3::new(block_size, block_size, block_size)) *
-                            Mat4::from_translation(Vec3::new(block.x as f32, block.y as f32, block.z as f32));
-                let model_loc = gl::GetUniformLocation(shader_program, "model\0".as_ptr() as *const _);
-                gl::UniformMatrix4fv(model_loc, 1, gl::FALSE, model.as_ref().as_ptr());
-
-                let textures = block_textures.get(&block.id).unwrap_or(&block_textures["minecraft:dirt"]);
-                let opacity_loc = gl::GetUniformLocation(shader_program, "opacity\0".as_ptr() as *const _);
-                let opacity = if block.id == "minecraft:water" { 0.5 } else { 1.0 };
-                gl::Uniform1f(opacity_loc, opacity);
-
-                for face in 0..6 {
-                    if block.visible_faces[face] {
-                        gl::BindTexture(gl::TEXTURE_2D, textures[face]);
-                        gl::DrawArrays(gl::TRIANGLES, (face * 6) as GLint, 6);
-                    }
+            // Só desenha as seções dentro do volume de visão e alcançáveis da câmera por espaços
+            // abertos. Chunks ainda sem malha deixam a busca passar; fora do mundo na vertical ela
+            // não começa e fica só o frustum
+            let frustum = Frustum::from_matrix(projection * view);
+            let ((camera_x, camera_z), _) = World::chunk_coords(eye_block.x as i32, eye_block.z as i32);
+            let camera_section = (eye_block.y as i32).div_euclid(SECTION_HEIGHT);
+            let reachable = (0..SECTION_COUNT as i32).contains(&camera_section).then(|| {
+                visible_sections((camera_x, camera_section, camera_z), |(x, z, section)| {
+                    world.has_chunk(x, z).then(|| {
+                        section_visibility.get(&(x, z, section)).copied().unwrap_or(SectionVisibility::ALL)
+                    })
+                })
+            });
+            culling_stats = CullingStats { total: section_meshes.len(), ..CullingStats::default() };
+
+            gpu_atlas.bind();
+            let mut visible = Vec::new();
+            for (&(x, z, section), mesh) in &section_meshes {
+                let (min, max) = section_bounds(x, z, section);
+                if !frustum.intersects(min, max) {
+                    culling_stats.frustum += 1;
+                    continue;
                 }
+                if reachable.as_ref().is_some_and(|reachable| !reachable.contains(&(x, z, section))) {
+                    culling_stats.caves += 1;
+                    continue;
+                }
+                culling_stats.drawn += 1;
+                visible.push(((min + max) / 2.0, mesh));
+            }
+
+            // Primeiro tudo que é opaco, para a profundidade já estar lá quando os translúcidos
+            // misturarem com o que está atrás deles
+            gl::Uniform1f(opacity_loc, 1.0);
+            for (_, mesh) in &visible {
+                mesh.draw_layer(false);
+            }
+            if let Some(mesh) = &crack_mesh {
+                // Por cima do bloco, sem esconder o que vier depois
+                gl::DepthMask(gl::FALSE);
+                mesh.draw();
+                gl::DepthMask(gl::TRUE);
+            }
+
+            // Translúcidos de trás para frente e sem gravar profundidade, para uma seção de água
+            // não esconder a outra
+            let eye = player.eye_position();
+            visible.sort_by(|(a, _), (b, _)| b.distance_squared(eye).total_cmp(&a.distance_squared(eye)));
+            gl::Uniform1f(opacity_loc, 0.5);
+            gl::DepthMask(gl::FALSE);
+            for (_, mesh) in &visible {
+                mesh.draw_layer(true);
+            }
+            gl::DepthMask(gl::TRUE);
+
+            // Segue o formato do modelo (meio bloco para slabs), um pouco afastado das faces
+            if let Some(((x, y, z), id)) = hit_block {
+                let (min, max) = block_models.bounds(id);
+                let corner = Vec3::new(x as f32, y as f32, z as f32) - Vec3::splat(0.5);
+                gl::UseProgram(outline_program);
+                gl::UniformMatrix4fv(outline_view_loc, 1, gl::FALSE, view.as_ref().as_ptr());
+                gl::UniformMatrix4fv(outline_proj_loc, 1, gl::FALSE, projection.as_ref().as_ptr());
+                outline_box.draw(corner + min - Vec3::splat(0.002), corner + max + Vec3::splat(0.002));
             }
 
             gl::Disable(gl::DEPTH_TEST);
@@ -386,19 +1031,96 @@ fn main() {
             gl::BindVertexArray(crosshair_vao);
             gl::BindTexture(gl::TEXTURE_2D, crosshair_texture);
             gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+
+            // Hotbar: fundo, ícones dos blocos nos slots e a moldura do slot na mão
+            let (width, height) = window.get_framebuffer_size();
+            let layout = hotbar_layout((width as f32, height as f32), player.inventory.selected());
+            gl::UseProgram(hud_program);
+            let draw_hud = |rect: game::hud::Rect, uv: [f32; 4], layer: Option<u32>, tint: [f32; 3]| {
+                gl::Uniform4f(hud_rect_loc, rect.x, rect.y, rect.width, rect.height);
+                gl::Uniform4fv(hud_uv_loc, 1, uv.as_ptr());
+                gl::Uniform1i(hud_array_loc, layer.is_some() as GLint);
+                gl::Uniform1f(hud_layer_loc, layer.unwrap_or(0) as f32);
+                gl::Uniform3fv(hud_tint_loc, 1, tint.as_ptr());
+                hud_quad.draw();
+            };
+            gl::BindTexture(gl::TEXTURE_2D, hotbar_texture);
+            draw_hud(layout.background, Tile::FULL.rect, None, [1.0; 3]);
+            gpu_atlas.bind();
+            for (stack, &rect) in player.inventory.slots.iter().zip(&layout.icons) {
+                let Some(block) = stack.as_ref().and_then(|stack| registry.id(&stack.item)) else { continue };
+                let (tile, tint) = block_models.icon(block);
+                if gpu_atlas.array {
+                    draw_hud(rect, Tile::FULL.rect, Some(tile.layer), tint);
+                } else {
+                    draw_hud(rect, tile.rect, None, tint);
+                }
+            }
+            gl::BindTexture(gl::TEXTURE_2D, selection_texture);
+            draw_hud(layout.selection, Tile::FULL.rect, None, [1.0; 3]);
+
+            // Vida só nos modos em que se toma dano
+            if player.game_mode.is_survival_like() {
+                let [container, full, half] = heart_textures;
+                for (rect, heart) in hearts((width as f32, height as f32), player.health) {
+                    gl::BindTexture(gl::TEXTURE_2D, container);
+                    draw_hud(rect, Tile::FULL.rect, None, [1.0; 3]);
+                    let fill = match heart {
+                        Heart::Full => full,
+                        Heart::Half => half,
+                        Heart::Empty => continue,
+                    };
+                    gl::BindTexture(gl::TEXTURE_2D, fill);
+                    draw_hud(rect, Tile::FULL.rect, None, [1.0; 3]);
+                }
+            }
             gl::Enable(gl::DEPTH_TEST);
         }
 
         window.swap_buffers();
     }
 
+    if let Err(err) = world.save(&mut storage.lock().unwrap()) {
+        eprintln!("Failed to save world: {}", err);
+    }
+    level.player = Some(PlayerData {
+        position: player.position,
+        yaw: player.yaw,
+        pitch: player.pitch,
+        inventory: player.inventory.clone(),
+        game_mode: player.game_mode,
+        health: player.health,
+    });
+    if let Err(err) = level.save(world_dir) {
+        eprintln!("Failed to save level.dat: {}", err);
+    }
+    if let Err(err) = options.save(Path::new(OPTIONS_FILE)) {
+        eprintln!("Failed to save {}: {}", OPTIONS_FILE, err);
+    }
+
     unsafe {
-        gl::DeleteVertexArrays(1, &vao);
-        gl::DeleteBuffers(1, &vbo);
+        for mesh in section_meshes.values() {
+            mesh.delete();
+        }
+        if let Some(mesh) = &crack_mesh {
+            mesh.delete();
+        }
+        gpu_atlas.delete();
         gl::DeleteProgram(shader_program);
         gl::DeleteVertexArrays(1, &crosshair_vao);
         gl::DeleteBuffers(1, &crosshair_vbo);
         gl::DeleteBuffers(1, &crosshair_ebo);
         gl::DeleteProgram(crosshair_program);
+        outline_box.delete();
+        gl::DeleteProgram(outline_program);
+        hud_quad.delete();
+        gl::DeleteProgram(hud_program);
+        gl::DeleteProgram(sky_program);
+        gl::DeleteProgram(celestial_program);
+        gl::DeleteTextures(1, &hotbar_texture);
+        gl::DeleteTextures(1, &selection_texture);
+        gl::DeleteTextures(heart_textures.len() as GLsizei, heart_textures.as_ptr());
+        gl::DeleteTextures(1, &sun_texture);
+        gl::DeleteTextures(1, &moon_texture);
     }
 }
\ No newline at end of file
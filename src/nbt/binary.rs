@@ -0,0 +1,423 @@
+use std::collections::BTreeMap;
+use super::{NbtError, Tag};
+
+pub const MAX_DEPTH: usize = 512; // Mesmo limite do Minecraft
+// Reserva máxima de uma lista antes de ler os elementos: cada nível aninhado reservaria de novo
+// sobre a mesma entrada, e um Tag ocupa bem mais que seus bytes no arquivo
+const MAX_LIST_PREALLOC: usize = 1024;
+
+// Lê um NBT binário big-endian não comprimido: (nome da raiz, composto raiz)
+pub fn read(bytes: &[u8]) -> Result<(String, Tag), NbtError> {
+    let mut reader = Reader { data: bytes, pos: 0 };
+    let id = reader.u8()?;
+    if id != 10 {
+        return Err(NbtError::InvalidRoot(id));
+    }
+    let name = reader.string()?;
+    let tag = reader.payload(id, 0)?;
+    Ok((name, tag))
+}
+
+pub fn write(name: &str, tag: &Tag) -> Result<Vec<u8>, NbtError> {
+    if !matches!(tag, Tag::Compound(_)) {
+        return Err(NbtError::InvalidRoot(tag.id()));
+    }
+    let mut out = Vec::new();
+    out.push(tag.id());
+    write_string(&mut out, name)?;
+    write_payload(&mut out, tag)?;
+    Ok(out)
+}
+
+// Variante do protocolo de rede (1.20.2+): raiz sem nome, de qualquer tipo.
+// Retorna também quantos bytes foram lidos, já que o NBT vem no meio de um pacote
+pub fn read_network(bytes: &[u8]) -> Result<(Tag, usize), NbtError> {
+    let mut reader = Reader { data: bytes, pos: 0 };
+    let id = reader.u8()?;
+    if id == 0 {
+        return Err(NbtError::InvalidRoot(id));
+    }
+    let tag = reader.payload(id, 0)?;
+    Ok((tag, reader.pos))
+}
+
+pub fn write_network(tag: &Tag) -> Result<Vec<u8>, NbtError> {
+    let mut out = vec![tag.id()];
+    write_payload(&mut out, tag)?;
+    Ok(out)
+}
+
+pub(super) struct Reader<'a> {
+    pub(super) data: &'a [u8],
+    pub(super) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], NbtError> {
+        let end = self.pos.checked_add(len).ok_or(NbtError::UnexpectedEof)?;
+        let slice = self.data.get(self.pos..end).ok_or(NbtError::UnexpectedEof)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub(super) fn u8(&mut self) -> Result<u8, NbtError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], NbtError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn i32(&mut self) -> Result<i32, NbtError> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    // Tamanho de array/lista, recusando valores que não cabem no que resta da entrada
+    fn length(&mut self, element_size: usize) -> Result<usize, NbtError> {
+        let len = self.i32()?;
+        if len < 0 {
+            return Err(NbtError::NegativeLength(len));
+        }
+        let len = len as usize;
+        if len.saturating_mul(element_size.max(1)) > self.remaining() {
+            return Err(NbtError::UnexpectedEof);
+        }
+        Ok(len)
+    }
+
+    pub(super) fn string(&mut self) -> Result<String, NbtError> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        decode_mutf8(self.take(len)?)
+    }
+
+    pub(super) fn payload(&mut self, id: u8, depth: usize) -> Result<Tag, NbtError> {
+        if depth > MAX_DEPTH {
+            return Err(NbtError::TooDeep);
+        }
+
+        Ok(match id {
+            1 => Tag::Byte(self.u8()? as i8),
+            2 => Tag::Short(i16::from_be_bytes(self.array()?)),
+            3 => Tag::Int(self.i32()?),
+            4 => Tag::Long(i64::from_be_bytes(self.array()?)),
+            5 => Tag::Float(f32::from_be_bytes(self.array()?)),
+            6 => Tag::Double(f64::from_be_bytes(self.array()?)),
+            7 => {
+                let len = self.length(1)?;
+                Tag::ByteArray(self.take(len)?.iter().map(|&b| b as i8).collect())
+            }
+            8 => Tag::String(self.string()?),
+            9 => {
+                let element = self.u8()?;
+                let len = self.length(min_payload_size(element))?;
+                if element == 0 && len > 0 {
+                    return Err(NbtError::InvalidTagId(0));
+                }
+                let mut list = Vec::with_capacity(len.min(MAX_LIST_PREALLOC));
+                for _ in 0..len {
+                    list.push(self.payload(element, depth + 1)?);
+                }
+                Tag::List(list)
+            }
+            10 => {
+                let mut map = BTreeMap::new();
+                loop {
+                    let child = self.u8()?;
+                    if child == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    map.insert(name, self.payload(child, depth + 1)?);
+                }
+                Tag::Compound(map)
+            }
+            11 => {
+                let len = self.length(4)?;
+                Tag::IntArray((0..len).map(|_| self.i32()).collect::<Result<_, _>>()?)
+            }
+            12 => {
+                let len = self.length(8)?;
+                Tag::LongArray(
+                    (0..len)
+                        .map(|_| self.array().map(i64::from_be_bytes))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            other => return Err(NbtError::InvalidTagId(other)),
+        })
+    }
+}
+
+// Menor payload possível de cada tipo, para recusar listas que não cabem no resto da entrada
+fn min_payload_size(id: u8) -> usize {
+    match id {
+        2 | 8 => 2,               // String: só o tamanho u16
+        3 | 5 | 7 | 11 | 12 => 4, // Arrays: só o tamanho i32
+        4 | 6 => 8,
+        9 => 5,                   // Tipo do elemento + tamanho
+        _ => 1,                   // Byte, composto com só o TAG_End
+    }
+}
+
+pub(super) fn write_string(out: &mut Vec<u8>, value: &str) -> Result<(), NbtError> {
+    let encoded = encode_mutf8(value);
+    let len = u16::try_from(encoded.len()).map_err(|_| NbtError::StringTooLong(encoded.len()))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(&encoded);
+    Ok(())
+}
+
+pub(super) fn write_payload(out: &mut Vec<u8>, tag: &Tag) -> Result<(), NbtError> {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Double(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::ByteArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            out.extend(values.iter().map(|&b| b as u8));
+        }
+        Tag::String(value) => write_string(out, value)?,
+        Tag::List(list) => {
+            let element = list.first().map_or(0, Tag::id);
+            if list.iter().any(|t| t.id() != element) {
+                return Err(NbtError::MixedList);
+            }
+            out.push(element);
+            out.extend_from_slice(&(list.len() as i32).to_be_bytes());
+            for item in list {
+                write_payload(out, item)?;
+            }
+        }
+        Tag::Compound(map) => {
+            for (name, value) in map {
+                out.push(value.id());
+                write_string(out, name)?;
+                write_payload(out, value)?;
+            }
+            out.push(0);
+        }
+        Tag::IntArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Tag::LongArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+// UTF-8 modificado do Java: '\0' vira 0xC0 0x80 e caracteres fora do BMP viram pares substitutos
+fn encode_mutf8(value: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.len());
+    for unit in value.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => out.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                out.push(0xC0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                out.push(0xE0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    out
+}
+
+fn decode_mutf8(bytes: &[u8]) -> Result<String, NbtError> {
+    // Caminho rápido: ASCII/UTF-8 sem nulos nem pares substitutos
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        if !s.contains('\0') {
+            return Ok(s.to_string());
+        }
+    }
+
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    let continuation = |i: usize| -> Result<u16, NbtError> {
+        match bytes.get(i) {
+            Some(&b) if b & 0xC0 == 0x80 => Ok((b & 0x3F) as u16),
+            _ => Err(NbtError::InvalidUtf8),
+        }
+    };
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b & 0x80 == 0 {
+            units.push(b as u16);
+            i += 1;
+        } else if b & 0xE0 == 0xC0 {
+            units.push(((b & 0x1F) as u16) << 6 | continuation(i + 1)?);
+            i += 2;
+        } else if b & 0xF0 == 0xE0 {
+            units.push(((b & 0x0F) as u16) << 12 | continuation(i + 1)? << 6 | continuation(i + 2)?);
+            i += 3;
+        } else {
+            return Err(NbtError::InvalidUtf8);
+        }
+    }
+    String::from_utf16(&units).map_err(|_| NbtError::InvalidUtf8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_tag_type() {
+        let mut root = Tag::compound();
+        root.insert("byte", -3i8)
+            .insert("short", 1234i16)
+            .insert("int", -99_999i32)
+            .insert("long", i64::MAX)
+            .insert("float", 1.5f32)
+            .insert("double", -0.25f64)
+            .insert("string", "olá \0 mundo 𝄞")
+            .insert("longs", vec![1i64, -2, 3]);
+        root.insert("bytes", Tag::ByteArray(vec![1, -1, 0]));
+        root.insert("ints", Tag::IntArray(vec![7, 8]));
+        root.insert("list", Tag::List(vec![Tag::Int(1), Tag::Int(2)]));
+        root.insert("empty", Tag::List(Vec::new()));
+        let mut nested = Tag::compound();
+        nested.insert("name", "minecraft:stone");
+        root.insert("nested", nested);
+
+        let bytes = write("root", &root).unwrap();
+        assert_eq!(read(&bytes).unwrap(), ("root".to_string(), root));
+    }
+
+    #[test]
+    fn encodes_modified_utf8() {
+        assert_eq!(encode_mutf8("\0"), vec![0xC0, 0x80]);
+        // U+1D11E vira um par substituto de 3 + 3 bytes
+        assert_eq!(encode_mutf8("𝄞").len(), 6);
+    }
+
+    #[test]
+    fn rejects_truncated_and_oversized_input() {
+        let mut root = Tag::compound();
+        root.insert("values", vec![1i64; 16]);
+        let bytes = write("", &root).unwrap();
+
+        for len in 0..bytes.len() {
+            assert!(read(&bytes[..len]).is_err());
+        }
+        // Tamanho de array gigante não pode alocar antes de validar
+        let huge = [10, 0, 0, 12, 0, 1, b'a', 0x7F, 0xFF, 0xFF, 0xFF];
+        assert_eq!(read(&huge), Err(NbtError::UnexpectedEof));
+        // Lista de longs: 4 elementos não cabem em 31 bytes
+        let longs = [10, 0, 0, 9, 0, 1, b'a', 4, 0, 0, 0, 4].iter().copied().chain([0; 31]).collect::<Vec<_>>();
+        assert_eq!(read(&longs), Err(NbtError::UnexpectedEof));
+    }
+
+    #[test]
+    fn nested_lists_with_huge_lengths_do_not_exhaust_memory() {
+        // Listas de listas quase no limite de profundidade, cada uma dizendo ter tantos elementos
+        // quanto cabem no resto; o enchimento lê como listas vazias até a entrada acabar
+        const LEVELS: usize = MAX_DEPTH - 10;
+        const PADDING: usize = 4 << 20;
+        let mut bytes = vec![10, 0, 0, 9, 0, 1, b'a'];
+        for level in 0..LEVELS {
+            let remaining = (LEVELS - level - 1) * 5 + PADDING;
+            bytes.push(9);
+            bytes.extend_from_slice(&((remaining / 5) as i32).to_be_bytes());
+        }
+        bytes.resize(bytes.len() + PADDING, 0);
+        assert_eq!(read(&bytes), Err(NbtError::UnexpectedEof));
+    }
+
+    #[test]
+    fn network_root_has_no_name() {
+        let mut root = Tag::compound();
+        root.insert("text", "olá");
+        let named = write("", &root).unwrap();
+        let network = write_network(&root).unwrap();
+        // Sem os dois bytes do tamanho do nome vazio
+        assert_eq!(network.len(), named.len() - 2);
+
+        let mut packet = network.clone();
+        packet.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(read_network(&packet).unwrap(), (root, network.len()));
+        assert_eq!(read_network(&write_network(&Tag::from("oi")).unwrap()).unwrap().0, Tag::from("oi"));
+        assert_eq!(read_network(&[0]), Err(NbtError::InvalidRoot(0)));
+    }
+
+    #[test]
+    fn writer_rejects_mixed_lists() {
+        let mut root = Tag::compound();
+        root.insert("list", Tag::List(vec![Tag::Int(1), Tag::Byte(2)]));
+        assert_eq!(write("", &root), Err(NbtError::MixedList));
+    }
+
+    #[test]
+    fn writer_rejects_strings_longer_than_u16() {
+        let limit = "a".repeat(u16::MAX as usize);
+        let mut root = Tag::compound();
+        root.insert("fits", limit.as_str());
+        assert_eq!(read(&write("", &root).unwrap()).unwrap().1, root);
+
+        // O nul vira dois bytes em UTF-8 modificado, passando do limite
+        root.insert("value", format!("{}\0", limit));
+        assert_eq!(write("", &root), Err(NbtError::StringTooLong(65537)));
+        let mut root = Tag::compound();
+        root.insert(&limit.repeat(2), 1i32);
+        assert_eq!(write("", &root), Err(NbtError::StringTooLong(131070)));
+        assert_eq!(write(&limit.repeat(2), &Tag::compound()), Err(NbtError::StringTooLong(131070)));
+    }
+
+    #[test]
+    fn random_and_mutated_input_never_panics() {
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+        use crate::nbt::compression::{read_compressed, write_compressed, Compression};
+
+        let mut root = Tag::compound();
+        root.insert("name", "minecraft:stone")
+            .insert("values", vec![1i64, 2, 3])
+            .insert("list", Tag::List(vec![Tag::compound(), Tag::compound()]));
+        let valid = [
+            write("", &root).unwrap(),
+            write_network(&root).unwrap(),
+            write_compressed("", &root, Compression::Gzip).unwrap(),
+            write_compressed("", &root, Compression::Zlib).unwrap(),
+        ];
+
+        let mut rng = StdRng::seed_from_u64(0x6e6274);
+        for _ in 0..5_000 {
+            let mut bytes = valid[rng.gen_range(0..valid.len())].clone();
+            for _ in 0..rng.gen_range(1..4) {
+                let i = rng.gen_range(0..bytes.len());
+                bytes[i] = rng.gen();
+            }
+            bytes.truncate(rng.gen_range(0..=bytes.len()));
+            let _ = read(&bytes);
+            let _ = read_network(&bytes);
+            let _ = read_compressed(&bytes);
+
+            let noise: Vec<u8> = (0..rng.gen_range(0..64)).map(|_| rng.gen()).collect();
+            let _ = read(&noise);
+            let _ = read_network(&noise);
+        }
+
+        // Listas aninhadas além do limite falham sem estourar a pilha
+        let mut deep = vec![9u8];
+        for _ in 0..10_000 {
+            deep.extend_from_slice(&[9, 0, 0, 0, 1]);
+        }
+        assert_eq!(read_network(&deep), Err(NbtError::TooDeep));
+    }
+}
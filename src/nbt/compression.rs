@@ -0,0 +1,103 @@
+use std::io::{Read, Write};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use super::{binary, NbtError, Tag};
+
+// Limite do NBT descomprimido, para que um arquivo pequeno não expanda até esgotar a memória
+pub const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip, // level.dat, estruturas (.nbt)
+    Zlib, // Chunks Anvil
+}
+
+impl Compression {
+    // Identifica pelos primeiros bytes: 1F 8B para gzip, 78 para zlib
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes {
+            [0x1F, 0x8B, ..] => Compression::Gzip,
+            [0x78, second, ..] if (0x78u16 << 8 | *second as u16).is_multiple_of(31) => Compression::Zlib,
+            _ => Compression::None,
+        }
+    }
+}
+
+pub fn decompress(bytes: &[u8], compression: Compression) -> Result<Vec<u8>, NbtError> {
+    let mut out = Vec::new();
+    let result = match compression {
+        Compression::None => {
+            out.extend_from_slice(bytes);
+            Ok(0)
+        }
+        Compression::Gzip => GzDecoder::new(bytes).take(MAX_DECOMPRESSED_SIZE + 1).read_to_end(&mut out),
+        Compression::Zlib => ZlibDecoder::new(bytes).take(MAX_DECOMPRESSED_SIZE + 1).read_to_end(&mut out),
+    };
+    result.map_err(|_| NbtError::InvalidCompression)?;
+    if out.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(NbtError::TooLarge);
+    }
+    Ok(out)
+}
+
+pub fn compress(bytes: &[u8], compression: Compression) -> Vec<u8> {
+    let level = flate2::Compression::default();
+    // Escrever num Vec não falha
+    match compression {
+        Compression::None => bytes.to_vec(),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), level);
+            encoder.write_all(bytes).unwrap();
+            encoder.finish().unwrap()
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), level);
+            encoder.write_all(bytes).unwrap();
+            encoder.finish().unwrap()
+        }
+    }
+}
+
+// Lê NBT em qualquer uma das três formas, detectando a compressão
+pub fn read_compressed(bytes: &[u8]) -> Result<(String, Tag), NbtError> {
+    binary::read(&decompress(bytes, Compression::detect(bytes))?)
+}
+
+pub fn write_compressed(name: &str, tag: &Tag, compression: Compression) -> Result<Vec<u8>, NbtError> {
+    Ok(compress(&binary::write(name, tag)?, compression))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_compression() {
+        let mut root = Tag::compound();
+        root.insert("seed", 42i64).insert("name", "mundo");
+
+        for compression in [Compression::None, Compression::Gzip, Compression::Zlib] {
+            let bytes = write_compressed("Data", &root, compression).unwrap();
+            assert_eq!(Compression::detect(&bytes), compression);
+            assert_eq!(read_compressed(&bytes).unwrap(), ("Data".to_string(), root.clone()));
+        }
+    }
+
+    #[test]
+    fn corrupt_streams_are_errors() {
+        let mut root = Tag::compound();
+        root.insert("value", 1i32);
+        let mut bytes = write_compressed("", &root, Compression::Gzip).unwrap();
+        bytes.truncate(bytes.len() / 2);
+        assert!(read_compressed(&bytes).is_err());
+        assert_eq!(decompress(&[0x78, 0x9C, 0xFF, 0xFF], Compression::Zlib), Err(NbtError::InvalidCompression));
+    }
+
+    #[test]
+    fn decompression_is_capped() {
+        let zeros = vec![0u8; MAX_DECOMPRESSED_SIZE as usize + 1];
+        let bomb = compress(&zeros, Compression::Gzip);
+        assert_eq!(decompress(&bomb, Compression::Gzip), Err(NbtError::TooLarge));
+    }
+}
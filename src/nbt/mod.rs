@@ -0,0 +1,184 @@
+pub mod binary;
+pub mod compression;
+pub mod snbt;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub use binary::{read, write};
+pub use compression::Compression;
+
+// Árvore de tags NBT; compostos ordenados para uma saída determinística
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(BTreeMap<String, Tag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::Float(_) => 5,
+            Tag::Double(_) => 6,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(_) => 9,
+            Tag::Compound(_) => 10,
+            Tag::IntArray(_) => 11,
+            Tag::LongArray(_) => 12,
+        }
+    }
+
+    pub fn compound() -> Self {
+        Tag::Compound(BTreeMap::new())
+    }
+
+    // Insere num composto; não faz nada em outras tags
+    pub fn insert(&mut self, key: &str, value: impl Into<Tag>) -> &mut Self {
+        if let Tag::Compound(map) = self {
+            map.insert(key.to_string(), value.into());
+        }
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        match self {
+            Tag::Compound(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Tag::Byte(v) => Some(v as i64),
+            Tag::Short(v) => Some(v as i64),
+            Tag::Int(v) => Some(v as i64),
+            Tag::Long(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Tag::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn as_long_array(&self) -> Option<&[i64]> {
+        match self {
+            Tag::LongArray(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl From<i8> for Tag {
+    fn from(v: i8) -> Self {
+        Tag::Byte(v)
+    }
+}
+
+impl From<i16> for Tag {
+    fn from(v: i16) -> Self {
+        Tag::Short(v)
+    }
+}
+
+impl From<i32> for Tag {
+    fn from(v: i32) -> Self {
+        Tag::Int(v)
+    }
+}
+
+impl From<i64> for Tag {
+    fn from(v: i64) -> Self {
+        Tag::Long(v)
+    }
+}
+
+impl From<f32> for Tag {
+    fn from(v: f32) -> Self {
+        Tag::Float(v)
+    }
+}
+
+impl From<f64> for Tag {
+    fn from(v: f64) -> Self {
+        Tag::Double(v)
+    }
+}
+
+impl From<&str> for Tag {
+    fn from(v: &str) -> Self {
+        Tag::String(v.to_string())
+    }
+}
+
+impl From<String> for Tag {
+    fn from(v: String) -> Self {
+        Tag::String(v)
+    }
+}
+
+impl From<Vec<i64>> for Tag {
+    fn from(v: Vec<i64>) -> Self {
+        Tag::LongArray(v)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtError {
+    UnexpectedEof,
+    InvalidTagId(u8),
+    InvalidUtf8,
+    NegativeLength(i32),
+    TooDeep,        // Aninhamento acima de MAX_DEPTH
+    MixedList,      // Lista com elementos de tipos diferentes
+    InvalidRoot(u8), // A raiz precisa ser um composto
+    InvalidCompression,
+    TooLarge,       // Descomprimido acima de MAX_DECOMPRESSED_SIZE
+    InvalidSnbt(usize, &'static str), // Posição em bytes no texto e motivo
+    StringTooLong(usize), // Em bytes de UTF-8 modificado; o prefixo u16 limita a 65535
+}
+
+impl fmt::Display for NbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NbtError::UnexpectedEof => write!(f, "unexpected end of NBT data"),
+            NbtError::InvalidTagId(id) => write!(f, "invalid NBT tag id {}", id),
+            NbtError::InvalidUtf8 => write!(f, "invalid modified UTF-8 string"),
+            NbtError::NegativeLength(len) => write!(f, "negative NBT length {}", len),
+            NbtError::TooDeep => write!(f, "NBT nested too deeply"),
+            NbtError::MixedList => write!(f, "NBT list elements have different types"),
+            NbtError::InvalidRoot(id) => write!(f, "invalid NBT root tag {}", id),
+            NbtError::InvalidCompression => write!(f, "corrupt gzip/zlib NBT stream"),
+            NbtError::TooLarge => write!(f, "decompressed NBT is too large"),
+            NbtError::InvalidSnbt(pos, reason) => write!(f, "invalid SNBT at byte {}: {}", pos, reason),
+            NbtError::StringTooLong(len) => write!(f, "NBT string of {} bytes exceeds 65535", len),
+        }
+    }
+}
+
+impl std::error::Error for NbtError {}
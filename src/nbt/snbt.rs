@@ -0,0 +1,390 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use super::binary::MAX_DEPTH;
+use super::{NbtError, Tag};
+
+// Lê NBT em texto (SNBT), como em `{Name:"minecraft:stone",Count:1b}`
+pub fn parse(text: &str) -> Result<Tag, NbtError> {
+    let mut parser = Parser { text, pos: 0 };
+    let tag = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos != text.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    Ok(tag)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize, // Em bytes, sempre numa fronteira de caractere
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, reason: &'static str) -> NbtError {
+        NbtError::InvalidSnbt(self.pos, reason)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), NbtError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error("unexpected character"));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    // Consome `separator` se for o próximo caractere, pulando espaços
+    fn accept(&mut self, separator: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(separator) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Tag, NbtError> {
+        if depth > MAX_DEPTH {
+            return Err(NbtError::TooDeep);
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.compound(depth),
+            Some('[') => self.list(depth),
+            Some('"' | '\'') => Ok(Tag::String(self.quoted()?)),
+            Some(_) => {
+                let token = self.unquoted();
+                if token.is_empty() {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(scalar(token))
+            }
+            None => Err(self.error("unexpected end of SNBT")),
+        }
+    }
+
+    fn compound(&mut self, depth: usize) -> Result<Tag, NbtError> {
+        self.expect('{')?;
+        let mut map = BTreeMap::new();
+        if self.accept('}') {
+            return Ok(Tag::Compound(map));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = match self.peek() {
+                Some('"' | '\'') => self.quoted()?,
+                _ => match self.unquoted() {
+                    "" => return Err(self.error("expected a key")),
+                    key => key.to_string(),
+                },
+            };
+            self.expect(':')?;
+            map.insert(key, self.value(depth + 1)?);
+            if !self.accept(',') {
+                break;
+            }
+        }
+        self.expect('}')?;
+        Ok(Tag::Compound(map))
+    }
+
+    fn list(&mut self, depth: usize) -> Result<Tag, NbtError> {
+        self.expect('[')?;
+
+        // Arrays tipados: [B; ...], [I; ...], [L; ...]
+        let rest = &self.text[self.pos..];
+        let array = match rest.as_bytes() {
+            [kind @ (b'B' | b'I' | b'L'), b';', ..] => Some(*kind),
+            _ => None,
+        };
+        if let Some(kind) = array {
+            self.pos += 2;
+            return self.array(kind);
+        }
+
+        let mut list = Vec::new();
+        if self.accept(']') {
+            return Ok(Tag::List(list));
+        }
+        loop {
+            let item = self.value(depth + 1)?;
+            if list.first().is_some_and(|first: &Tag| first.id() != item.id()) {
+                return Err(NbtError::MixedList);
+            }
+            list.push(item);
+            if !self.accept(',') {
+                break;
+            }
+        }
+        self.expect(']')?;
+        Ok(Tag::List(list))
+    }
+
+    fn array(&mut self, kind: u8) -> Result<Tag, NbtError> {
+        let mut values = Vec::new();
+        if !self.accept(']') {
+            loop {
+                self.skip_whitespace();
+                let value = scalar(self.unquoted());
+                let value = match (kind, value) {
+                    (b'B', Tag::Byte(v)) => v as i64,
+                    (b'I', Tag::Int(v)) => v as i64,
+                    (b'L', Tag::Long(v)) => v,
+                    _ => return Err(self.error("array element has the wrong type")),
+                };
+                values.push(value);
+                if !self.accept(',') {
+                    break;
+                }
+            }
+            self.expect(']')?;
+        }
+
+        Ok(match kind {
+            b'B' => Tag::ByteArray(values.into_iter().map(|v| v as i8).collect()),
+            b'I' => Tag::IntArray(values.into_iter().map(|v| v as i32).collect()),
+            _ => Tag::LongArray(values),
+        })
+    }
+
+    fn quoted(&mut self) -> Result<String, NbtError> {
+        let quote = self.peek().unwrap();
+        self.pos += 1;
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, escaped @ ('\\' | '"' | '\''))) => out.push(escaped),
+                    _ => {
+                        self.pos += offset;
+                        return Err(self.error("invalid escape"));
+                    }
+                },
+                c if c == quote => {
+                    self.pos += offset + 1;
+                    return Ok(out);
+                }
+                c => out.push(c),
+            }
+        }
+        self.pos = self.text.len();
+        Err(self.error("unterminated string"))
+    }
+
+    fn unquoted(&mut self) -> &'a str {
+        let start = self.pos;
+        let len = self.text[start..]
+            .find(|c: char| !is_unquoted_char(c))
+            .unwrap_or(self.text.len() - start);
+        self.pos += len;
+        &self.text[start..start + len]
+    }
+}
+
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+// Como no Minecraft: números com sufixo opcional, true/false viram bytes e o resto é string
+fn scalar(token: &str) -> Tag {
+    match token {
+        "true" => return Tag::Byte(1),
+        "false" => return Tag::Byte(0),
+        _ => {}
+    }
+
+    let (body, suffix) = match token.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&token[..i], Some(c.to_ascii_lowercase())),
+        _ => (token, None),
+    };
+    let is_integer = !body.is_empty()
+        && body.strip_prefix(['-', '+']).unwrap_or(body).bytes().all(|b| b.is_ascii_digit());
+    let is_decimal = body.bytes().any(|b| b.is_ascii_digit())
+        && body.bytes().all(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'));
+
+    let parsed = match suffix {
+        Some('b') if is_integer => body.parse().ok().map(Tag::Byte),
+        Some('s') if is_integer => body.parse().ok().map(Tag::Short),
+        Some('l') if is_integer => body.parse().ok().map(Tag::Long),
+        Some('f') if is_decimal => body.parse().ok().map(Tag::Float),
+        Some('d') if is_decimal => body.parse().ok().map(Tag::Double),
+        None if is_integer => body.parse().ok().map(Tag::Int),
+        None if is_decimal => body.parse().ok().map(Tag::Double),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| Tag::String(token.to_string()))
+}
+
+// Texto que `parse` lê de volta como a mesma tag
+pub fn to_snbt(tag: &Tag) -> String {
+    let mut out = String::new();
+    write_tag(&mut out, tag).unwrap();
+    out
+}
+
+fn write_tag(out: &mut String, tag: &Tag) -> fmt::Result {
+    match tag {
+        Tag::Byte(v) => write!(out, "{}b", v),
+        Tag::Short(v) => write!(out, "{}s", v),
+        Tag::Int(v) => write!(out, "{}", v),
+        Tag::Long(v) => write!(out, "{}L", v),
+        Tag::Float(v) => write!(out, "{}f", v),
+        Tag::Double(v) => write!(out, "{}d", v),
+        Tag::ByteArray(values) => write_array(out, 'B', values.iter().map(|v| format!("{}b", v))),
+        Tag::String(value) => write_quoted(out, value),
+        Tag::List(list) => {
+            out.push('[');
+            for (i, item) in list.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_tag(out, item)?;
+            }
+            out.push(']');
+            Ok(())
+        }
+        Tag::Compound(map) => {
+            out.push('{');
+            for (i, (key, value)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                if !key.is_empty() && key.chars().all(is_unquoted_char) {
+                    out.push_str(key);
+                } else {
+                    write_quoted(out, key)?;
+                }
+                out.push(':');
+                write_tag(out, value)?;
+            }
+            out.push('}');
+            Ok(())
+        }
+        Tag::IntArray(values) => write_array(out, 'I', values.iter().map(|v| v.to_string())),
+        Tag::LongArray(values) => write_array(out, 'L', values.iter().map(|v| format!("{}L", v))),
+    }
+}
+
+fn write_array(out: &mut String, kind: char, values: impl Iterator<Item = String>) -> fmt::Result {
+    write!(out, "[{};", kind)?;
+    for (i, value) in values.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&value);
+    }
+    out.push(']');
+    Ok(())
+}
+
+fn write_quoted(out: &mut String, value: &str) -> fmt::Result {
+    out.push('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    Ok(())
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&to_snbt(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vanilla_style_snbt() {
+        let tag = parse(r#"{ Name: "minecraft:oak_log", Properties: {axis: y}, Count: 1b, Big: 3000000000L,
+            Pos: [1.5d, 64.0d, -3d], Motion: [0.0, 2e1, -1.], Items: [], 'odd key': 'it\'s', ok: true}"#).unwrap();
+        assert_eq!(tag.get("Name").and_then(Tag::as_str), Some("minecraft:oak_log"));
+        assert_eq!(tag.get("Properties").and_then(|p| p.get("axis")), Some(&Tag::from("y")));
+        assert_eq!(tag.get("Count"), Some(&Tag::Byte(1)));
+        assert_eq!(tag.get("Big"), Some(&Tag::Long(3_000_000_000)));
+        assert_eq!(tag.get("Pos"), Some(&Tag::List(vec![Tag::Double(1.5), Tag::Double(64.0), Tag::Double(-3.0)])));
+        assert_eq!(tag.get("Motion"), Some(&Tag::List(vec![Tag::Double(0.0), Tag::Double(20.0), Tag::Double(-1.0)])));
+        assert_eq!(tag.get("odd key"), Some(&Tag::from("it's")));
+        assert_eq!(tag.get("ok"), Some(&Tag::Byte(1)));
+    }
+
+    #[test]
+    fn out_of_range_numbers_become_strings() {
+        assert_eq!(parse("300b").unwrap(), Tag::from("300b"));
+        assert_eq!(parse("3000000000").unwrap(), Tag::from("3000000000"));
+        assert_eq!(parse("1.2.3").unwrap(), Tag::from("1.2.3"));
+    }
+
+    #[test]
+    fn typed_arrays() {
+        assert_eq!(parse("[B; 1b, -2b]").unwrap(), Tag::ByteArray(vec![1, -2]));
+        assert_eq!(parse("[I;]").unwrap(), Tag::IntArray(Vec::new()));
+        assert_eq!(parse("[L; 5L]").unwrap(), Tag::LongArray(vec![5]));
+        assert!(parse("[I; 1b]").is_err());
+    }
+
+    #[test]
+    fn printed_snbt_parses_back() {
+        let mut root = Tag::compound();
+        root.insert("byte", -3i8)
+            .insert("short", 1234i16)
+            .insert("int", -99_999i32)
+            .insert("long", i64::MIN)
+            .insert("float", 0.1f32)
+            .insert("double", 1e300f64)
+            .insert("string", "aspas \" e \\ barra")
+            .insert("longs", vec![1i64, -2]);
+        root.insert("bytes", Tag::ByteArray(vec![1, -1]));
+        root.insert("ints", Tag::IntArray(vec![7, 8]));
+        root.insert("nested", Tag::List(vec![Tag::List(vec![Tag::Int(1)]), Tag::List(Vec::new())]));
+        root.insert("chave com espaço", Tag::compound());
+        root.insert("", "vazio");
+
+        let text = root.to_string();
+        assert_eq!(parse(&text).unwrap(), root);
+        assert_eq!(to_snbt(&Tag::List(vec![Tag::Byte(1), Tag::Byte(2)])), "[1b,2b]");
+    }
+
+    #[test]
+    fn malformed_snbt_is_an_error() {
+        for text in ["", "{", "{a:}", "{:1}", "[1,", "[1,2b]", "\"abc", "{a:1}}", "'\\x'", "[B;"] {
+            assert!(parse(text).is_err(), "{:?}", text);
+        }
+        let deep = "[".repeat(10_000);
+        assert_eq!(parse(&deep), Err(NbtError::TooDeep));
+    }
+
+    #[test]
+    fn random_text_never_panics() {
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+
+        let alphabet: Vec<char> = "{}[]:;,\"'\\ BILbslfd0123456789.-+eé true".chars().collect();
+        let mut rng = StdRng::seed_from_u64(0x736e6274);
+        for _ in 0..5_000 {
+            let text: String = (0..rng.gen_range(0..40))
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect();
+            if let Ok(tag) = parse(&text) {
+                // Tudo que é aceito precisa sobreviver a uma volta pelo texto
+                assert_eq!(parse(&to_snbt(&tag)).as_ref(), Ok(&tag), "{:?}", text);
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use gl::types::*;
+use crate::game::atlas::{AtlasImages, TextureAtlas};
+
+// Unidades de textura fixas: o shader declara os dois samplers e escolhe pelo uniform `useArray`
+pub const ATLAS_UNIT: GLuint = 0;
+pub const ARRAY_UNIT: GLuint = 1;
+
+pub struct GpuAtlas {
+  texture: GLuint,
+  pub array: bool,
+}
+
+// Limites do driver: (GL_MAX_TEXTURE_SIZE, GL_MAX_ARRAY_TEXTURE_LAYERS)
+pub unsafe fn limits() -> (u32, u32) {
+  let (mut size, mut layers) = (0, 0);
+  gl::GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut size);
+  gl::GetIntegerv(gl::MAX_ARRAY_TEXTURE_LAYERS, &mut layers);
+  (size as u32, layers as u32)
+}
+
+pub unsafe fn upload(atlas: &TextureAtlas) -> GpuAtlas {
+  let mut texture = 0;
+  gl::GenTextures(1, &mut texture);
+
+  let target = match &atlas.images {
+    AtlasImages::Atlas(image) => {
+      gl::ActiveTexture(gl::TEXTURE0 + ATLAS_UNIT);
+      gl::BindTexture(gl::TEXTURE_2D, texture);
+      gl::TexImage2D(
+        gl::TEXTURE_2D,
+        0,
+        gl::RGBA as GLint,
+        image.width() as GLint,
+        image.height() as GLint,
+        0,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        image.as_ptr() as *const _,
+      );
+      gl::TEXTURE_2D
+    }
+    AtlasImages::Layers(layers) => {
+      let (width, height) = layers.first().map_or((1, 1), |layer| layer.dimensions());
+      gl::ActiveTexture(gl::TEXTURE0 + ARRAY_UNIT);
+      gl::BindTexture(gl::TEXTURE_2D_ARRAY, texture);
+      gl::TexImage3D(
+        gl::TEXTURE_2D_ARRAY,
+        0,
+        gl::RGBA as GLint,
+        width as GLint,
+        height as GLint,
+        layers.len().max(1) as GLint,
+        0,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+        std::ptr::null(),
+      );
+      for (index, layer) in layers.iter().enumerate() {
+        gl::TexSubImage3D(
+          gl::TEXTURE_2D_ARRAY,
+          0,
+          0,
+          0,
+          index as GLint,
+          width as GLint,
+          height as GLint,
+          1,
+          gl::RGBA,
+          gl::UNSIGNED_BYTE,
+          layer.as_ptr() as *const _,
+        );
+      }
+      gl::TEXTURE_2D_ARRAY
+    }
+  };
+
+  // No atlas a repetição é feita no shader; no array o GL_REPEAT faz o trabalho
+  gl::TexParameteri(target, gl::TEXTURE_WRAP_S, gl::REPEAT as GLint);
+  gl::TexParameteri(target, gl::TEXTURE_WRAP_T, gl::REPEAT as GLint);
+  gl::TexParameteri(target, gl::TEXTURE_MIN_FILTER, gl::NEAREST_MIPMAP_LINEAR as GLint);
+  gl::TexParameteri(target, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+  if let Some(level) = atlas.max_mip_level() {
+    gl::TexParameteri(target, gl::TEXTURE_MAX_LEVEL, level as GLint);
+  }
+  gl::GenerateMipmap(target);
+  gl::ActiveTexture(gl::TEXTURE0);
+
+  GpuAtlas { texture, array: target == gl::TEXTURE_2D_ARRAY }
+}
+
+impl GpuAtlas {
+  pub unsafe fn bind(&self) {
+    if self.array {
+      gl::ActiveTexture(gl::TEXTURE0 + ARRAY_UNIT);
+      gl::BindTexture(gl::TEXTURE_2D_ARRAY, self.texture);
+      gl::ActiveTexture(gl::TEXTURE0);
+    } else {
+      gl::ActiveTexture(gl::TEXTURE0 + ATLAS_UNIT);
+      gl::BindTexture(gl::TEXTURE_2D, self.texture);
+    }
+  }
+
+  pub unsafe fn delete(&self) {
+    gl::DeleteTextures(1, &self.texture);
+  }
+}
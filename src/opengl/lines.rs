@@ -0,0 +1,60 @@
+use gl::types::*;
+use glam::Vec3;
+
+// Arestas de uma caixa, reenviadas a cada draw (contorno do bloco mirado)
+pub struct LineBox {
+  vao: GLuint,
+  vbo: GLuint,
+}
+
+// As 12 arestas como pares de pontos para GL_LINES
+fn edges(min: Vec3, max: Vec3) -> [[f32; 3]; 24] {
+  let corner = |i: usize| {
+    let pick = |bit: usize, axis: usize| if i & bit == 0 { min[axis] } else { max[axis] };
+    [pick(1, 0), pick(2, 1), pick(4, 2)]
+  };
+  let mut lines = [[0.0; 3]; 24];
+  let mut next = 0;
+  for i in 0..8 {
+    // Liga cada canto aos vizinhos com um bit a mais, para não repetir arestas
+    for bit in [1, 2, 4] {
+      if i & bit == 0 {
+        lines[next] = corner(i);
+        lines[next + 1] = corner(i | bit);
+        next += 2;
+      }
+    }
+  }
+  lines
+}
+
+pub unsafe fn create() -> LineBox {
+  let (mut vao, mut vbo) = (0, 0);
+  gl::GenVertexArrays(1, &mut vao);
+  gl::GenBuffers(1, &mut vbo);
+
+  gl::BindVertexArray(vao);
+  gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+  gl::BufferData(gl::ARRAY_BUFFER, std::mem::size_of::<[[f32; 3]; 24]>() as GLsizeiptr, std::ptr::null(), gl::DYNAMIC_DRAW);
+  gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 3 * std::mem::size_of::<f32>() as GLsizei, std::ptr::null());
+  gl::EnableVertexAttribArray(0);
+  gl::BindVertexArray(0);
+
+  LineBox { vao, vbo }
+}
+
+impl LineBox {
+  // Com o programa de linhas já em uso
+  pub unsafe fn draw(&self, min: Vec3, max: Vec3) {
+    let lines = edges(min, max);
+    gl::BindVertexArray(self.vao);
+    gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+    gl::BufferSubData(gl::ARRAY_BUFFER, 0, std::mem::size_of_val(&lines) as GLsizeiptr, lines.as_ptr() as *const _);
+    gl::DrawArrays(gl::LINES, 0, lines.len() as GLsizei);
+  }
+
+  pub unsafe fn delete(&self) {
+    gl::DeleteVertexArrays(1, &self.vao);
+    gl::DeleteBuffers(1, &self.vbo);
+  }
+}
@@ -0,0 +1,90 @@
+use gl::types::*;
+use crate::game::world::mesher::{Batch, SectionMesh, Vertex};
+
+// Buffers de uma seção já enviados para a GPU
+pub struct GpuMesh {
+  vao: GLuint,
+  vbo: GLuint,
+  ebo: GLuint,
+  pub batches: Vec<Batch>,
+}
+
+pub unsafe fn upload(mesh: &SectionMesh) -> GpuMesh {
+  let (mut vao, mut vbo, mut ebo) = (0, 0, 0);
+  gl::GenVertexArrays(1, &mut vao);
+  gl::GenBuffers(1, &mut vbo);
+  gl::GenBuffers(1, &mut ebo);
+
+  gl::BindVertexArray(vao);
+  gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+  gl::BufferData(
+    gl::ARRAY_BUFFER,
+    std::mem::size_of_val(mesh.vertices.as_slice()) as GLsizeiptr,
+    mesh.vertices.as_ptr() as *const _,
+    gl::STATIC_DRAW,
+  );
+  gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+  gl::BufferData(
+    gl::ELEMENT_ARRAY_BUFFER,
+    std::mem::size_of_val(mesh.indices.as_slice()) as GLsizeiptr,
+    mesh.indices.as_ptr() as *const _,
+    gl::STATIC_DRAW,
+  );
+
+  // Layout de `Vertex`: posição (3), uv (2), normal (3), tile (4), camada (1), cor (3),
+  // luz do céu e dos blocos (2), oclusão (1)
+  let stride = std::mem::size_of::<Vertex>() as GLsizei;
+  let float = std::mem::size_of::<f32>();
+  gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+  gl::EnableVertexAttribArray(0);
+  gl::VertexAttribPointer(1, 2, gl::FLOAT, gl::FALSE, stride, (3 * float) as *const _);
+  gl::EnableVertexAttribArray(1);
+  gl::VertexAttribPointer(2, 3, gl::FLOAT, gl::FALSE, stride, (5 * float) as *const _);
+  gl::EnableVertexAttribArray(2);
+  gl::VertexAttribPointer(3, 4, gl::FLOAT, gl::FALSE, stride, (8 * float) as *const _);
+  gl::EnableVertexAttribArray(3);
+  gl::VertexAttribPointer(4, 1, gl::FLOAT, gl::FALSE, stride, (12 * float) as *const _);
+  gl::EnableVertexAttribArray(4);
+  gl::VertexAttribPointer(5, 3, gl::FLOAT, gl::FALSE, stride, (13 * float) as *const _);
+  gl::EnableVertexAttribArray(5);
+  gl::VertexAttribPointer(6, 2, gl::FLOAT, gl::FALSE, stride, (16 * float) as *const _);
+  gl::EnableVertexAttribArray(6);
+  gl::VertexAttribPointer(7, 1, gl::FLOAT, gl::FALSE, stride, (18 * float) as *const _);
+  gl::EnableVertexAttribArray(7);
+  gl::BindVertexArray(0);
+
+  GpuMesh { vao, vbo, ebo, batches: mesh.batches.clone() }
+}
+
+impl GpuMesh {
+  pub unsafe fn draw(&self) {
+    gl::BindVertexArray(self.vao);
+    for batch in &self.batches {
+      gl::DrawElements(
+        gl::TRIANGLES,
+        batch.index_count as GLsizei,
+        gl::UNSIGNED_INT,
+        (batch.first_index * std::mem::size_of::<u32>()) as *const _,
+      );
+    }
+  }
+
+  // Só os grupos opacos ou só os translúcidos, para desenhar todos os opacos da cena primeiro
+  pub unsafe fn draw_layer(&self, translucent: bool) {
+    gl::BindVertexArray(self.vao);
+    for batch in self.batches.iter().filter(|batch| batch.translucent == translucent) {
+      gl::DrawElements(
+        gl::TRIANGLES,
+        batch.index_count as GLsizei,
+        gl::UNSIGNED_INT,
+        (batch.first_index * std::mem::size_of::<u32>()) as *const _,
+      );
+    }
+  }
+
+  pub unsafe fn delete(&self) {
+    gl::DeleteVertexArrays(1, &self.vao);
+    gl::DeleteBuffers(1, &self.vbo);
+    gl::DeleteBuffers(1, &self.ebo);
+  }
+}
@@ -1,2 +1,5 @@
 pub mod link;
-pub mod compile;
\ No newline at end of file
+pub mod compile;
+pub mod mesh;pub mod atlas;
+pub mod lines;
+pub mod quad;
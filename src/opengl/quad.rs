@@ -0,0 +1,35 @@
+use gl::types::*;
+
+// Quad de (0, 0) a (1, 1) para desenhar sprites da HUD; o shader posiciona com o uniform `rect`
+pub struct UnitQuad {
+  vao: GLuint,
+  vbo: GLuint,
+}
+
+pub unsafe fn create() -> UnitQuad {
+  let corners: [f32; 8] = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+  let (mut vao, mut vbo) = (0, 0);
+  gl::GenVertexArrays(1, &mut vao);
+  gl::GenBuffers(1, &mut vbo);
+
+  gl::BindVertexArray(vao);
+  gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+  gl::BufferData(gl::ARRAY_BUFFER, std::mem::size_of_val(&corners) as GLsizeiptr, corners.as_ptr() as *const _, gl::STATIC_DRAW);
+  gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 2 * std::mem::size_of::<f32>() as GLsizei, std::ptr::null());
+  gl::EnableVertexAttribArray(0);
+  gl::BindVertexArray(0);
+
+  UnitQuad { vao, vbo }
+}
+
+impl UnitQuad {
+  pub unsafe fn draw(&self) {
+    gl::BindVertexArray(self.vao);
+    gl::DrawArrays(gl::TRIANGLE_FAN, 0, 4);
+  }
+
+  pub unsafe fn delete(&self) {
+    gl::DeleteVertexArrays(1, &self.vao);
+    gl::DeleteBuffers(1, &self.vbo);
+  }
+}